@@ -0,0 +1,266 @@
+## Navigation
+
+nav-gigs = Gigs
+nav-rehearsals = Rehearsals
+nav-members = Members
+nav-finances = Finances
+nav-venues = Venues
+nav-promo = Promo
+nav-log = Log
+nav-bands = Bands
+language = Language
+
+## Songs
+
+loading = Loading...
+songs-setlist = Setlist
+songs-all = All songs
+setlist-fill = Fill
+setlist-clear = Clear
+filter-query = Search by title, artist or lyrics
+filter-all-readiness = Any status
+filter-not-played-days = Not played in days
+filter-search = Search
+readiness-new = New
+readiness-learning = Learning
+readiness-ready = Ready
+sort-title = Title
+sort-artist = Artist
+sort-last_played = Longest not played
+sort-bpm = BPM
+song-practise = Practise
+song-played = Played
+song-never-played = Never
+song-lyrics = Lyrics
+song-edit = Edit
+song-play = Play
+song-no-audio = No audio
+song-random = Random
+song-form-artist = Artist
+song-form-title = Title
+song-form-bpm = BPM
+song-form-readiness = Status
+song-form-duration = Duration (seconds)
+song-form-key = Key, e.g. Am
+song-form-arrangement = Arrangement, e.g. intro 4, verse 16 x2, chorus 8
+song-form-lyrics = Edit lyrics
+filter-min-bpm = Min bpm
+filter-max-bpm = Max bpm
+tag-new = New tag
+tag-all = All tags
+section-intro = Intro
+section-verse = Verse
+section-pre_chorus = Pre-chorus
+section-chorus = Chorus
+section-bridge = Bridge
+section-solo = Solo
+section-interlude = Interlude
+section-outro = Outro
+song-roles = Roles
+song-role-lead_vocal = Lead vocals
+song-role-backing = Backing
+song-role-tuning = Tuning
+song-role-capo = Capo
+song-role-instrument = Instrument
+song-role-default-tuning = standard
+song-role-default-capo = none
+song-role-default-instrument = own instrument
+notes-show = Notes
+notes-stage = Stage
+notes-loose = Notes on lines that no longer exist
+notes-placeholder = E.g. hold the last chorus
+notes-add = Note
+
+## Gigs
+
+gigs-new = New gig
+gigs-group-by-venue = Group by venue
+gigs-deleted = Deleted gigs
+calendar = Calendar
+calendar-member-name = Band member's name
+calendar-personal-link = Personal link
+gig-venue = Venue
+gig-time = Time
+gig-date = Date
+gig-add-break = Break
+gig-break = break
+gig-awkward-keys =
+    { $count ->
+        [one] One awkward key change.
+       *[other] { $count } awkward key changes.
+    } Smoother:
+gig-apply-order = Apply order
+gig-key-change = Key { $from } → { $to }
+new-gig-empty-setlist = Empty setlist
+new-gig-templates = Templates
+new-gig-copy-of = Copy of gig
+new-gig-create = Create
+song-count =
+    { $count ->
+        [one] 1 song
+       *[other] { $count } songs
+    }
+new-gig-duplicate = Duplicate
+new-gig-template-name = Template name
+new-gig-save-template = Save as template
+new-gig-template-saved = Template saved
+confirm-sure = Are you sure?
+gigs-none-deleted = No deleted gigs
+gigs-restore = Restore
+
+## Sharing
+
+share = Share
+share-with-lyrics = with lyrics,{" "}
+share-valid-until = valid until { $date }
+share-include-lyrics = With lyrics
+share-expires-on = Valid until
+share-create = Shareable link
+
+## Members
+
+member-name = Name
+member-instrument = Instrument
+member-required = Essential
+member-available-after-all = Available after all
+member-reason = Reason
+member-unavailable = Can't make it
+member-not-available = Not available
+member-required-out = An essential member can't make it
+rsvp-yes = Yes
+rsvp-maybe = Maybe
+rsvp-no = No
+warning-cant-make-it = { $name } can't make it
+warning-unavailable = { $name } is not available on this date
+warning-unavailable-because = { $name } is not available on this date: { $note }
+
+## Finances
+
+save = Save
+finance-fee = Fee
+finance-deposit = Deposit
+finance-outstanding = Still to receive: { $amount }
+finance-expenses = Expenses
+finance-description = Description
+finance-amount = Amount
+finance-net = Net
+finance-payouts = Payout
+finance-total = Total
+finance-per-member = Per member
+expense-fuel = Fuel
+expense-rental = Rental
+expense-food = Food
+expense-other = Other
+
+## Setlist generator
+
+generator-open = Generate setlist
+generator-target-minutes = Total length (min)
+generator-sets = Number of sets
+generator-pause-minutes = Break (min)
+generator-opener-tag = Opener tag
+generator-closer-tag = Closer tag
+generator-no-tag = None
+generator-avoid-same-artist = Never the same artist twice in a row
+generator-generate = Generate
+generator-shuffle = Different order
+generator-replaces = Replaces the current order
+generator-tag = tag '{ $tag }'
+generator-most-energy = most energy ({ $bpm } bpm)
+generator-break = Break of { $minutes } minutes
+generator-no-songs-left = Set { $set }: no songs left
+generator-opener = Set { $set } opens with '{ $title }': { $reason }
+generator-closer = Set { $set } closes with '{ $title }': { $reason }
+generator-forced = '{ $title }': same artist as the previous song, nothing else fit
+generator-fits = '{ $title }': { $bpm } bpm fits the build-up of the set
+generator-total = Total playing time { $playing } of { $available } available
+
+## Log
+
+audit-by = { $summary } by { $actor }
+audit-undo = Undo
+audit-undone-at = Undone at { $at }
+audit-entity-song = Song
+audit-entity-gig = Gig
+audit-entity-setlist = Setlist
+audit-action-create = created
+audit-action-update = changed
+audit-action-delete = deleted
+audit-action-restore = restored
+audit-action-clear = cleared
+audit-action-replace = replaced
+audit-action-undo = reverted
+
+## Revisions
+
+revisions = Versions
+revisions-none = Not edited yet
+revisions-current = current
+revisions-restore = Restore this version
+revisions-first = First saved version
+revisions-lyrics-unchanged = Lyrics unchanged
+revision-artist = Artist
+revision-title = Title
+revision-bpm = BPM
+revision-readiness = Status
+revision-duration = Duration
+revision-tags = Tags
+revision-arrangement = Arrangement
+revision-key = Key
+
+## Venues
+
+venue-new = New venue
+venue-name = Name
+venue-address = Address
+venue-contact-name = Contact person
+venue-contact-phone = Phone
+venue-contact-email = Email
+venue-stage-size = Stage
+venue-technical-notes = Power, PA, backline, parking...
+venue-played = Played
+venue-never-played = Never yet
+venue-none = None
+
+## Rehearsals
+
+rehearsal-attendees = Present, comma separated
+rehearsal-start = Start rehearsal
+rehearsal-in-progress = Rehearsal in progress
+rehearsal-nothing-played = Nothing played yet, tick off songs in the list
+rehearsal-notes = Notes
+rehearsal-summary = Summary
+rehearsal-close = Finish
+rehearsal-busy = In progress
+rehearsal-title = Rehearsal { $date }
+rehearsal-duration = { $duration } hours
+rehearsal-attendance = Attendance
+rehearsal-played = Played
+
+## Bands
+
+bands-new = Name of a new band
+bands-members-of = Members of { $band }
+bands-open-to-everyone = This band has no members yet, so everyone can open it.
+bands-user-name = User name
+bands-add = Add
+role-admin = Admin
+role-member = Member
+
+## Promo
+
+promo-pop = Pop
+promo-round = Round
+promo-square = Square
+promo-transparent = Transparent
+
+## Errors
+
+error-not_found = Not found
+error-locked = Locked
+error-validation = Invalid
+error-conflict = Conflict
+error-storage = Storage error
+error-internal = Error
+error-back = Back to the songs
+error-page-not-found = this page does not exist
@@ -0,0 +1,266 @@
+## Navigatie
+
+nav-gigs = Gigs
+nav-rehearsals = Repetities
+nav-members = Leden
+nav-finances = Financiën
+nav-venues = Locaties
+nav-promo = Promo
+nav-log = Logboek
+nav-bands = Bands
+language = Taal
+
+## Nummers
+
+loading = Laden...
+songs-setlist = Setlist
+songs-all = Alle nummers
+setlist-fill = Vullen
+setlist-clear = Legen
+filter-query = Zoek op titel, artiest of tekst
+filter-all-readiness = Alle statussen
+filter-not-played-days = Niet gespeeld in dagen
+filter-search = Zoek
+readiness-new = Nieuw
+readiness-learning = Instuderen
+readiness-ready = Klaar
+sort-title = Titel
+sort-artist = Artiest
+sort-last_played = Langst niet gespeeld
+sort-bpm = BPM
+song-practise = Oefen
+song-played = Gespeeld
+song-never-played = Nooit
+song-lyrics = Tekst
+song-edit = Bewerken
+song-play = Afspelen
+song-no-audio = Geen audio
+song-random = Willekeurig
+song-form-artist = Artiest
+song-form-title = Titel
+song-form-bpm = BPM
+song-form-readiness = Status
+song-form-duration = Duur (seconden)
+song-form-key = Toonsoort, bijv. Am
+song-form-arrangement = Opbouw, bijv. intro 4, couplet 16 x2, refrein 8
+song-form-lyrics = Tekst bewerken
+filter-min-bpm = Min bpm
+filter-max-bpm = Max bpm
+tag-new = Nieuwe tag
+tag-all = Alle tags
+section-intro = Intro
+section-verse = Couplet
+section-pre_chorus = Pre-refrein
+section-chorus = Refrein
+section-bridge = Bridge
+section-solo = Solo
+section-interlude = Tussenspel
+section-outro = Outro
+song-roles = Rollen
+song-role-lead_vocal = Leadzang
+song-role-backing = Backing
+song-role-tuning = Stemming
+song-role-capo = Capo
+song-role-instrument = Instrument
+song-role-default-tuning = standaard
+song-role-default-capo = geen
+song-role-default-instrument = eigen instrument
+notes-show = Notities
+notes-stage = Podium
+notes-loose = Notities bij regels die niet meer bestaan
+notes-placeholder = Bijv. laatste refrein aanhouden
+notes-add = Notitie
+
+## Optredens
+
+gigs-new = Nieuw optreden
+gigs-group-by-venue = Groepeer per locatie
+gigs-deleted = Verwijderde optredens
+calendar = Agenda
+calendar-member-name = Naam bandlid
+calendar-personal-link = Persoonlijke link
+gig-venue = Locatie
+gig-time = Tijd
+gig-date = Datum
+gig-add-break = Pauze
+gig-break = pauze
+gig-awkward-keys =
+    { $count ->
+        [one] Eén lastige toonsoortwissel.
+       *[other] { $count } lastige toonsoortwissels.
+    } Soepeler:
+gig-apply-order = Volgorde toepassen
+gig-key-change = Toonsoort { $from } → { $to }
+new-gig-empty-setlist = Lege setlist
+new-gig-templates = Templates
+new-gig-copy-of = Kopie van optreden
+new-gig-create = Aanmaken
+song-count =
+    { $count ->
+        [one] 1 nummer
+       *[other] { $count } nummers
+    }
+new-gig-duplicate = Dupliceer
+new-gig-template-name = Naam template
+new-gig-save-template = Bewaar als template
+new-gig-template-saved = Template bewaard
+confirm-sure = Zeker weten?
+gigs-none-deleted = Geen verwijderde optredens
+gigs-restore = Herstellen
+
+## Delen
+
+share = Delen
+share-with-lyrics = met teksten,{" "}
+share-valid-until = geldig t/m { $date }
+share-include-lyrics = Met songteksten
+share-expires-on = Geldig t/m
+share-create = Deelbare link
+
+## Leden
+
+member-name = Naam
+member-instrument = Instrument
+member-required = Onmisbaar
+member-available-after-all = Toch beschikbaar
+member-reason = Reden
+member-unavailable = Kan niet
+member-not-available = Niet beschikbaar
+member-required-out = Onmisbaar lid kan niet
+rsvp-yes = Kan
+rsvp-maybe = Misschien
+rsvp-no = Kan niet
+warning-cant-make-it = { $name } kan niet
+warning-unavailable = { $name } is niet beschikbaar op deze datum
+warning-unavailable-because = { $name } is niet beschikbaar op deze datum: { $note }
+
+## Financiën
+
+save = Opslaan
+finance-fee = Gage
+finance-deposit = Aanbetaling
+finance-outstanding = Nog te ontvangen: { $amount }
+finance-expenses = Kosten
+finance-description = Omschrijving
+finance-amount = Bedrag
+finance-net = Netto
+finance-payouts = Verdeling
+finance-total = Totaal
+finance-per-member = Per lid
+expense-fuel = Brandstof
+expense-rental = Huur
+expense-food = Eten
+expense-other = Overig
+
+## Setlist genereren
+
+generator-open = Genereer setlist
+generator-target-minutes = Totale duur (min)
+generator-sets = Aantal sets
+generator-pause-minutes = Pauze (min)
+generator-opener-tag = Opener tag
+generator-closer-tag = Afsluiter tag
+generator-no-tag = Geen
+generator-avoid-same-artist = Niet twee keer dezelfde artiest achter elkaar
+generator-generate = Genereer
+generator-shuffle = Andere volgorde
+generator-replaces = Vervangt de huidige volgorde
+generator-tag = tag '{ $tag }'
+generator-most-energy = meeste energie ({ $bpm } bpm)
+generator-break = Pauze van { $minutes } minuten
+generator-no-songs-left = Set { $set }: geen nummers meer over
+generator-opener = Set { $set } opent met '{ $title }': { $reason }
+generator-closer = Set { $set } sluit af met '{ $title }': { $reason }
+generator-forced = '{ $title }': zelfde artiest als het vorige nummer, er paste niets anders
+generator-fits = '{ $title }': { $bpm } bpm past bij de opbouw van de set
+generator-total = Totale speeltijd { $playing } van { $available } beschikbaar
+
+## Logboek
+
+audit-by = { $summary } door { $actor }
+audit-undo = Ongedaan maken
+audit-undone-at = Ongedaan gemaakt op { $at }
+audit-entity-song = Nummer
+audit-entity-gig = Optreden
+audit-entity-setlist = Setlist
+audit-action-create = aangemaakt
+audit-action-update = gewijzigd
+audit-action-delete = verwijderd
+audit-action-restore = hersteld
+audit-action-clear = geleegd
+audit-action-replace = vervangen
+audit-action-undo = teruggezet
+
+## Versies
+
+revisions = Versies
+revisions-none = Nog niet bewerkt
+revisions-current = huidig
+revisions-restore = Deze versie herstellen
+revisions-first = Eerste bewaarde versie
+revisions-lyrics-unchanged = Tekst niet gewijzigd
+revision-artist = Artiest
+revision-title = Titel
+revision-bpm = BPM
+revision-readiness = Status
+revision-duration = Duur
+revision-tags = Tags
+revision-arrangement = Opbouw
+revision-key = Toonsoort
+
+## Locaties
+
+venue-new = Nieuwe locatie
+venue-name = Naam
+venue-address = Adres
+venue-contact-name = Contactpersoon
+venue-contact-phone = Telefoon
+venue-contact-email = E-mail
+venue-stage-size = Podium
+venue-technical-notes = Stroom, PA, backline, parkeren...
+venue-played = Gespeeld
+venue-never-played = Nog nooit
+venue-none = Geen
+
+## Repetities
+
+rehearsal-attendees = Aanwezig, komma gescheiden
+rehearsal-start = Start repetitie
+rehearsal-in-progress = Repetitie bezig
+rehearsal-nothing-played = Nog niets gespeeld, vink nummers af in de lijst
+rehearsal-notes = Notities
+rehearsal-summary = Samenvatting
+rehearsal-close = Afronden
+rehearsal-busy = Bezig
+rehearsal-title = Repetitie { $date }
+rehearsal-duration = { $duration } uur
+rehearsal-attendance = Aanwezigheid
+rehearsal-played = Gespeeld
+
+## Bands
+
+bands-new = Naam van een nieuwe band
+bands-members-of = Leden van { $band }
+bands-open-to-everyone = Deze band heeft nog geen leden, dus iedereen kan hem openen.
+bands-user-name = Gebruikersnaam
+bands-add = Toevoegen
+role-admin = Beheerder
+role-member = Lid
+
+## Promo
+
+promo-pop = Pop
+promo-round = Rond
+promo-square = Vierkant
+promo-transparent = Transparant
+
+## Fouten
+
+error-not_found = Niet gevonden
+error-locked = Vergrendeld
+error-validation = Ongeldig
+error-conflict = Conflict
+error-storage = Opslagfout
+error-internal = Fout
+error-back = Terug naar de nummers
+error-page-not-found = deze pagina bestaat niet
@@ -0,0 +1,4 @@
+ALTER TABLE songs ADD COLUMN readiness VARCHAR NOT NULL DEFAULT 'ready';
+
+CREATE INDEX songs_search_idx ON songs
+    USING GIN (to_tsvector('simple', title || ' ' || artist || ' ' || lyrics));
@@ -0,0 +1,15 @@
+CREATE TABLE venues (
+  id SERIAL PRIMARY KEY,
+  name VARCHAR(255) NOT NULL UNIQUE,
+  address TEXT NOT NULL DEFAULT '',
+  contact_name VARCHAR(255) NOT NULL DEFAULT '',
+  contact_phone VARCHAR(64) NOT NULL DEFAULT '',
+  contact_email VARCHAR(255) NOT NULL DEFAULT '',
+  stage_size VARCHAR(255) NOT NULL DEFAULT '',
+  technical_notes TEXT NOT NULL DEFAULT ''
+);
+
+INSERT INTO venues (name) SELECT DISTINCT venue FROM gigs WHERE venue <> '' AND venue <> 'Nieuw';
+
+ALTER TABLE gigs ADD COLUMN venue_id INTEGER REFERENCES venues(id) ON DELETE SET NULL;
+UPDATE gigs SET venue_id = venues.id FROM venues WHERE venues.name = gigs.venue;
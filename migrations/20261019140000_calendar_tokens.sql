@@ -0,0 +1,6 @@
+CREATE TABLE calendar_tokens (
+  id SERIAL PRIMARY KEY,
+  name VARCHAR(255) NOT NULL,
+  token VARCHAR(64) NOT NULL UNIQUE,
+  created_at TIMESTAMP NOT NULL DEFAULT CURRENT_TIMESTAMP
+);
@@ -0,0 +1,20 @@
+CREATE TABLE rehearsals (
+  id SERIAL PRIMARY KEY,
+  date DATE NOT NULL DEFAULT CURRENT_DATE,
+  location VARCHAR(255) NOT NULL DEFAULT '',
+  attendees VARCHAR[] NOT NULL DEFAULT '{}',
+  notes TEXT NOT NULL DEFAULT '',
+  started_at TIMESTAMP NOT NULL DEFAULT CURRENT_TIMESTAMP,
+  closed_at TIMESTAMP
+);
+
+-- At most one rehearsal can be in progress.
+CREATE UNIQUE INDEX rehearsals_open_idx ON rehearsals ((closed_at IS NULL)) WHERE closed_at IS NULL;
+
+CREATE TABLE rehearsal_songs (
+  rehearsal_id INTEGER NOT NULL REFERENCES rehearsals(id) ON DELETE CASCADE,
+  song_id INTEGER NOT NULL REFERENCES songs(id) ON DELETE CASCADE,
+  notes TEXT NOT NULL DEFAULT '',
+  played_at TIMESTAMP NOT NULL DEFAULT CURRENT_TIMESTAMP,
+  PRIMARY KEY (rehearsal_id, song_id)
+);
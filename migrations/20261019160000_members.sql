@@ -0,0 +1,28 @@
+CREATE TABLE members (
+  id SERIAL PRIMARY KEY,
+  name VARCHAR(255) NOT NULL UNIQUE,
+  instrument VARCHAR(255) NOT NULL DEFAULT '',
+  -- Without a required member the band can't play.
+  required BOOLEAN NOT NULL DEFAULT TRUE
+);
+
+CREATE TABLE member_unavailability (
+  member_id INTEGER NOT NULL REFERENCES members(id) ON DELETE CASCADE,
+  date DATE NOT NULL,
+  note VARCHAR(255) NOT NULL DEFAULT '',
+  PRIMARY KEY (member_id, date)
+);
+
+CREATE TABLE gig_attendance (
+  gig_id INTEGER NOT NULL REFERENCES gigs(id) ON DELETE CASCADE,
+  member_id INTEGER NOT NULL REFERENCES members(id) ON DELETE CASCADE,
+  status VARCHAR(16) NOT NULL,
+  PRIMARY KEY (gig_id, member_id)
+);
+
+CREATE TABLE rehearsal_attendance (
+  rehearsal_id INTEGER NOT NULL REFERENCES rehearsals(id) ON DELETE CASCADE,
+  member_id INTEGER NOT NULL REFERENCES members(id) ON DELETE CASCADE,
+  status VARCHAR(16) NOT NULL,
+  PRIMARY KEY (rehearsal_id, member_id)
+);
@@ -0,0 +1,11 @@
+CREATE TABLE song_roles (
+  id SERIAL PRIMARY KEY,
+  song_id INTEGER NOT NULL REFERENCES songs(id) ON DELETE CASCADE,
+  member_id INTEGER NOT NULL REFERENCES members(id) ON DELETE CASCADE,
+  -- lead_vocal, backing, tuning, capo or instrument
+  kind VARCHAR(32) NOT NULL,
+  -- The tuning, capo fret or instrument; empty for vocals.
+  value VARCHAR(255) NOT NULL DEFAULT ''
+);
+
+CREATE INDEX song_roles_song_id_idx ON song_roles (song_id);
@@ -0,0 +1,25 @@
+-- All amounts are in euro cents.
+CREATE TABLE gig_finances (
+  gig_id INTEGER PRIMARY KEY REFERENCES gigs(id) ON DELETE CASCADE,
+  fee_cents BIGINT NOT NULL DEFAULT 0,
+  deposit_cents BIGINT NOT NULL DEFAULT 0
+);
+
+CREATE TABLE gig_expenses (
+  id SERIAL PRIMARY KEY,
+  gig_id INTEGER NOT NULL REFERENCES gigs(id) ON DELETE CASCADE,
+  -- fuel, rental, food or other
+  category VARCHAR(32) NOT NULL,
+  description VARCHAR(255) NOT NULL DEFAULT '',
+  amount_cents BIGINT NOT NULL
+);
+
+CREATE INDEX gig_expenses_gig_id_idx ON gig_expenses (gig_id);
+
+-- Members without a row get one share; zero shares leaves a member out.
+CREATE TABLE gig_payout_shares (
+  gig_id INTEGER NOT NULL REFERENCES gigs(id) ON DELETE CASCADE,
+  member_id INTEGER NOT NULL REFERENCES members(id) ON DELETE CASCADE,
+  shares INTEGER NOT NULL CHECK (shares >= 0),
+  PRIMARY KEY (gig_id, member_id)
+);
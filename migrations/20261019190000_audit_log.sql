@@ -0,0 +1,20 @@
+-- Who changed what, with the song, gig or setlist as JSON before and after.
+CREATE TABLE audit_log (
+  id SERIAL PRIMARY KEY,
+  created_at TIMESTAMP NOT NULL DEFAULT now(),
+  actor VARCHAR(255) NOT NULL,
+  -- song, gig or setlist
+  entity VARCHAR(32) NOT NULL,
+  entity_id INTEGER NOT NULL,
+  -- create, update, delete, restore, clear, replace or undo
+  action VARCHAR(32) NOT NULL,
+  before JSONB,
+  after JSONB,
+  -- Set once a destructive action has been undone.
+  undone_at TIMESTAMP
+);
+
+CREATE INDEX audit_log_created_at_idx ON audit_log (created_at DESC);
+
+-- Deleted gigs stay around until restored.
+ALTER TABLE gigs ADD COLUMN deleted_at TIMESTAMP;
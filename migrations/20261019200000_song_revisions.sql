@@ -0,0 +1,16 @@
+-- Every saved state of a song's editable fields, newest with the highest id.
+CREATE TABLE song_revisions (
+  id SERIAL PRIMARY KEY,
+  song_id INTEGER NOT NULL REFERENCES songs(id) ON DELETE CASCADE,
+  created_at TIMESTAMP NOT NULL DEFAULT now(),
+  author VARCHAR(255) NOT NULL,
+  artist VARCHAR(255) NOT NULL,
+  title VARCHAR(255) NOT NULL,
+  bpm INTEGER,
+  lyrics TEXT NOT NULL,
+  readiness VARCHAR(16) NOT NULL,
+  duration INTEGER,
+  tags TEXT[] NOT NULL DEFAULT '{}'
+);
+
+CREATE INDEX song_revisions_song_id_idx ON song_revisions (song_id);
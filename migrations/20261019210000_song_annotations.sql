@@ -0,0 +1,13 @@
+-- Notes pinned to a line of a song's lyrics. The text of that line is kept so
+-- the note can find its line again after the lyrics are edited.
+CREATE TABLE song_annotations (
+  id SERIAL PRIMARY KEY,
+  song_id INTEGER NOT NULL REFERENCES songs(id) ON DELETE CASCADE,
+  member_id INTEGER NOT NULL REFERENCES members(id) ON DELETE CASCADE,
+  line INTEGER NOT NULL,
+  anchor TEXT NOT NULL,
+  body TEXT NOT NULL,
+  created_at TIMESTAMP NOT NULL DEFAULT now()
+);
+
+CREATE INDEX song_annotations_song_id_idx ON song_annotations (song_id);
@@ -0,0 +1,15 @@
+-- The arrangement of a song: its sections in playing order.
+CREATE TABLE song_sections (
+  id SERIAL PRIMARY KEY,
+  song_id INTEGER NOT NULL REFERENCES songs(id) ON DELETE CASCADE,
+  position INTEGER NOT NULL,
+  -- intro, verse, pre_chorus, chorus, bridge, solo, interlude or outro
+  kind VARCHAR(32) NOT NULL,
+  bars INTEGER NOT NULL CHECK (bars > 0),
+  repeats INTEGER NOT NULL DEFAULT 1 CHECK (repeats > 0)
+);
+
+CREATE INDEX song_sections_song_id_idx ON song_sections (song_id);
+
+-- Revisions keep the arrangement as written in the song form.
+ALTER TABLE song_revisions ADD COLUMN arrangement TEXT NOT NULL DEFAULT '';
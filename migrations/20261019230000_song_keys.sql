@@ -0,0 +1,8 @@
+-- Key and mode of a song, written like "C", "F#m" or "Bb".
+ALTER TABLE songs ADD COLUMN musical_key VARCHAR(8);
+ALTER TABLE song_revisions ADD COLUMN musical_key VARCHAR(8);
+
+-- Songs with ChordPro lyrics already say their key.
+UPDATE songs
+SET musical_key = left(trim(substring(lyrics from '\{key:\s*([^}]*)\}')), 8)
+WHERE lyrics ~ '\{key:';
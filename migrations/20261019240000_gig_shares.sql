@@ -0,0 +1,10 @@
+-- Public read-only links to a gig's running order, for venues and stand-ins.
+CREATE TABLE gig_shares (
+  id SERIAL PRIMARY KEY,
+  gig_id INTEGER NOT NULL REFERENCES gigs(id) ON DELETE CASCADE,
+  token VARCHAR(64) NOT NULL UNIQUE,
+  include_lyrics BOOLEAN NOT NULL DEFAULT FALSE,
+  -- The link works up to and including this day.
+  expires_on DATE NOT NULL,
+  created_at TIMESTAMP NOT NULL DEFAULT CURRENT_TIMESTAMP
+);
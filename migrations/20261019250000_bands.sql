@@ -0,0 +1,59 @@
+-- Every band has its own songs, setlists, gigs and so on. Tables below the
+-- ones with a band_id belong to the band of their parent.
+CREATE TABLE bands (
+  id SERIAL PRIMARY KEY,
+  name VARCHAR(255) NOT NULL UNIQUE,
+  created_at TIMESTAMP NOT NULL DEFAULT CURRENT_TIMESTAMP
+);
+
+INSERT INTO bands (name) VALUES ('My Dad Rocks');
+
+-- The users a proxy authenticates, by the name it passes on. A band without
+-- memberships is open to everyone.
+CREATE TABLE band_memberships (
+  band_id INTEGER NOT NULL REFERENCES bands(id) ON DELETE CASCADE,
+  user_name VARCHAR(255) NOT NULL,
+  -- admin or member
+  role VARCHAR(16) NOT NULL,
+  PRIMARY KEY (band_id, user_name)
+);
+
+-- Existing data belongs to the first band. The defaults are dropped again so
+-- every insert has to name its band.
+ALTER TABLE songs ADD COLUMN band_id INTEGER NOT NULL DEFAULT 1 REFERENCES bands(id) ON DELETE CASCADE;
+ALTER TABLE setlists ADD COLUMN band_id INTEGER NOT NULL DEFAULT 1 REFERENCES bands(id) ON DELETE CASCADE;
+ALTER TABLE gigs ADD COLUMN band_id INTEGER NOT NULL DEFAULT 1 REFERENCES bands(id) ON DELETE CASCADE;
+ALTER TABLE venues ADD COLUMN band_id INTEGER NOT NULL DEFAULT 1 REFERENCES bands(id) ON DELETE CASCADE;
+ALTER TABLE gig_templates ADD COLUMN band_id INTEGER NOT NULL DEFAULT 1 REFERENCES bands(id) ON DELETE CASCADE;
+ALTER TABLE members ADD COLUMN band_id INTEGER NOT NULL DEFAULT 1 REFERENCES bands(id) ON DELETE CASCADE;
+ALTER TABLE rehearsals ADD COLUMN band_id INTEGER NOT NULL DEFAULT 1 REFERENCES bands(id) ON DELETE CASCADE;
+ALTER TABLE tags ADD COLUMN band_id INTEGER NOT NULL DEFAULT 1 REFERENCES bands(id) ON DELETE CASCADE;
+ALTER TABLE calendar_tokens ADD COLUMN band_id INTEGER NOT NULL DEFAULT 1 REFERENCES bands(id) ON DELETE CASCADE;
+ALTER TABLE audit_log ADD COLUMN band_id INTEGER NOT NULL DEFAULT 1 REFERENCES bands(id) ON DELETE CASCADE;
+
+ALTER TABLE songs ALTER COLUMN band_id DROP DEFAULT;
+ALTER TABLE setlists ALTER COLUMN band_id DROP DEFAULT;
+ALTER TABLE gigs ALTER COLUMN band_id DROP DEFAULT;
+ALTER TABLE venues ALTER COLUMN band_id DROP DEFAULT;
+ALTER TABLE gig_templates ALTER COLUMN band_id DROP DEFAULT;
+ALTER TABLE members ALTER COLUMN band_id DROP DEFAULT;
+ALTER TABLE rehearsals ALTER COLUMN band_id DROP DEFAULT;
+ALTER TABLE tags ALTER COLUMN band_id DROP DEFAULT;
+ALTER TABLE calendar_tokens ALTER COLUMN band_id DROP DEFAULT;
+ALTER TABLE audit_log ALTER COLUMN band_id DROP DEFAULT;
+
+CREATE INDEX songs_band_id_idx ON songs (band_id);
+CREATE INDEX gigs_band_id_idx ON gigs (band_id);
+CREATE INDEX audit_log_band_id_idx ON audit_log (band_id, id DESC);
+
+-- Names only have to be unique within a band.
+ALTER TABLE tags DROP CONSTRAINT tags_name_key;
+ALTER TABLE tags ADD CONSTRAINT tags_band_id_name_key UNIQUE (band_id, name);
+ALTER TABLE venues DROP CONSTRAINT venues_name_key;
+ALTER TABLE venues ADD CONSTRAINT venues_band_id_name_key UNIQUE (band_id, name);
+ALTER TABLE members DROP CONSTRAINT members_name_key;
+ALTER TABLE members ADD CONSTRAINT members_band_id_name_key UNIQUE (band_id, name);
+
+-- Each band can have a rehearsal in progress.
+DROP INDEX rehearsals_open_idx;
+CREATE UNIQUE INDEX rehearsals_open_idx ON rehearsals (band_id) WHERE closed_at IS NULL;
@@ -0,0 +1,31 @@
+-- Fingerprints of the rows the migrations add themselves, like the original
+-- songs, the first band and its practice setlist. Restoring a backup replaces
+-- those without --force, as long as they are unchanged. Only a database made
+-- by this run of the migrations gets them: in one migrated over time, the
+-- rows are the band's own by now. Later migrations that change these rows
+-- have to fingerprint them again.
+CREATE TABLE migration_seeds (
+  table_name VARCHAR(255) PRIMARY KEY,
+  -- md5 of the rows as a backup exports them
+  fingerprint VARCHAR(32) NOT NULL
+);
+
+DO $$
+DECLARE
+  seeded TEXT;
+BEGIN
+  IF (SELECT MIN(installed_on) FROM _sqlx_migrations) > now() - INTERVAL '1 hour' THEN
+    FOR seeded IN
+      SELECT table_name FROM information_schema.tables
+      WHERE table_schema = 'public' AND table_type = 'BASE TABLE'
+        AND table_name NOT IN ('_sqlx_migrations', 'migration_seeds')
+    LOOP
+      EXECUTE format(
+        'INSERT INTO migration_seeds
+         SELECT %L, md5(json_agg(t)::text) FROM (SELECT * FROM %I ORDER BY 1, 2) t
+         HAVING COUNT(*) > 0',
+        seeded, seeded
+      );
+    END LOOP;
+  END IF;
+END $$;
@@ -0,0 +1,7 @@
+-- Settings of a user signed in through the proxy, kept for every device they
+-- use. Anyone not signed in keeps theirs in a cookie instead.
+CREATE TABLE user_settings (
+  user_name VARCHAR(255) PRIMARY KEY,
+  -- Code of the UI language, like `nl`
+  language VARCHAR(8) NOT NULL
+);
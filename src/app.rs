@@ -3,17 +3,30 @@ use leptos_meta::*;
 use leptos_router::*;
 
 use crate::components::{gig::Gig, home::HomePage};
+use crate::components::audit::{AuditLogView, DeletedGigs};
+use crate::components::bands::{BandSwitcher, Bands};
 use crate::components::edit_song::EditSongView;
+use crate::components::finances::FinanceOverview;
 use crate::components::gigs::Gigs;
+use crate::components::language::LanguagePicker;
+use crate::components::members::Members;
+use crate::components::new_gig::NewGig;
 use crate::components::promo::Promo;
+use crate::components::rehearsal::{RehearsalView, Rehearsals};
+use crate::components::share::SharedGigView;
 use crate::components::song_text::SongText;
+use crate::components::venues::{VenueView, Venues};
 use crate::error_template::{AppError, ErrorTemplate};
+use crate::i18n::{use_locale, Locale};
 
 #[component]
 pub fn App() -> impl IntoView {
     provide_meta_context();
+    let locale = Locale::detect();
+    provide_context(locale);
 
     view! {
+      <Html lang=locale.code()/>
       <Stylesheet id="leptos" href="/pkg/my-dad-rocks.css"/>
       <Stylesheet
         id="font-awesome"
@@ -25,48 +38,106 @@ pub fn App() -> impl IntoView {
       <div class="min-h-screen bg-ctp-base text-ctp-text">
         <Router fallback=|| {
             let mut outside_errors = Errors::default();
-            outside_errors.insert_with_default_key(AppError::NotFound);
+            outside_errors.insert_with_default_key(AppError::NotFound(use_locale().t("error-page-not-found")));
             view! { <ErrorTemplate outside_errors/> }.into_view()
         }>
-          <nav class="bg-ctp-surface0 shadow-md z-100">
-            <div class="mx-auto max-w px-4 py-2">
-              <div class="flex flex-1 items-center justify-center items-stretch justify-start">
-                <div class="flex flex-1 items-center">
-                  <a href="/" class="text-ctp-text rounded-md text-2xl font-bold">
-                    <img src="/promo/logo_transparent.png" width=60 height=60/>
-                  </a>
-                </div>
-                <div class="ml-6 block content-center">
-                  <div class="flex space-x-4">
-                    <a
-                      href="/gigs"
-                      class="bg-ctp-overlay2 text-ctp-mantle rounded-md px-3 py-2 text-md font-medium"
-                    >
-                      <i class="fa-solid fa-ticket"></i>
-                      Gigs
-                    </a>
-                    <a
-                      href="/promo"
-                      class="bg-ctp-overlay2 text-ctp-mantle rounded-md px-3 py-2 text-md font-medium"
-                    >
-                      <i class="fa-solid fa-rectangle-ad"></i>
-                      Promo
+          // Shared setlists are for people outside the band.
+          <Show when=move || !use_location().pathname.get().starts_with("/gedeeld/")>
+            <nav class="bg-ctp-surface0 shadow-md z-100">
+              <div class="mx-auto max-w px-4 py-2">
+                <div class="flex flex-1 items-center justify-center items-stretch justify-start">
+                  <div class="flex flex-1 items-center">
+                    <a href="/" class="text-ctp-text rounded-md text-2xl font-bold">
+                      <img src="/promo/logo_transparent.png" width=60 height=60/>
                     </a>
                   </div>
+                  <div class="ml-6 block content-center">
+                    <div class="flex space-x-4">
+                      <BandSwitcher/>
+                      <LanguagePicker/>
+                      <a
+                        href="/gigs"
+                        class="bg-ctp-overlay2 text-ctp-mantle rounded-md px-3 py-2 text-md font-medium"
+                      >
+                        <i class="fa-solid fa-ticket"></i>
+                        {locale.t("nav-gigs")}
+                      </a>
+                      <a
+                        href="/rehearsals"
+                        class="bg-ctp-overlay2 text-ctp-mantle rounded-md px-3 py-2 text-md font-medium"
+                      >
+                        <i class="fa-solid fa-drum"></i>
+                        {locale.t("nav-rehearsals")}
+                      </a>
+                      <a
+                        href="/members"
+                        class="bg-ctp-overlay2 text-ctp-mantle rounded-md px-3 py-2 text-md font-medium"
+                      >
+                        <i class="fa-solid fa-users"></i>
+                        {locale.t("nav-members")}
+                      </a>
+                      <a
+                        href="/finances"
+                        class="bg-ctp-overlay2 text-ctp-mantle rounded-md px-3 py-2 text-md font-medium"
+                      >
+                        <i class="fa-solid fa-euro-sign"></i>
+                        {locale.t("nav-finances")}
+                      </a>
+                      <a
+                        href="/venues"
+                        class="bg-ctp-overlay2 text-ctp-mantle rounded-md px-3 py-2 text-md font-medium"
+                      >
+                        <i class="fa-solid fa-location-dot"></i>
+                        {locale.t("nav-venues")}
+                      </a>
+                      <a
+                        href="/promo"
+                        class="bg-ctp-overlay2 text-ctp-mantle rounded-md px-3 py-2 text-md font-medium"
+                      >
+                        <i class="fa-solid fa-rectangle-ad"></i>
+                        {locale.t("nav-promo")}
+                      </a>
+                      <a
+                        href="/logboek"
+                        class="bg-ctp-overlay2 text-ctp-mantle rounded-md px-3 py-2 text-md font-medium"
+                      >
+                        <i class="fa-solid fa-clock-rotate-left"></i>
+                        {locale.t("nav-log")}
+                      </a>
+                      <a
+                        href="/bands"
+                        class="bg-ctp-overlay2 text-ctp-mantle rounded-md px-3 py-2 text-md font-medium"
+                      >
+                        <i class="fa-solid fa-people-group"></i>
+                        {locale.t("nav-bands")}
+                      </a>
+                    </div>
+                  </div>
                 </div>
               </div>
-            </div>
-          </nav>
+            </nav>
+          </Show>
 
           <div class="container mx-auto">
             <main>
               <Routes>
                 <Route path="/" view=HomePage/>
                 <Route path="/gigs" view=Gigs/>
+                <Route path="/gigs/new" view=NewGig/>
+                <Route path="/gigs/verwijderd" view=DeletedGigs/>
                 <Route path="/promo" view=Promo/>
                 <Route path="/gig/:id" view=Gig/>
+                <Route path="/rehearsals" view=Rehearsals/>
+                <Route path="/rehearsal/:id" view=RehearsalView/>
+                <Route path="/members" view=Members/>
+                <Route path="/finances" view=FinanceOverview/>
+                <Route path="/venues" view=Venues/>
+                <Route path="/venue/:id" view=VenueView/>
                 <Route path="/lyric/:id" view=SongText/>
                 <Route path="/song/:id" view=EditSongView/>
+                <Route path="/logboek" view=AuditLogView/>
+                <Route path="/bands" view=Bands/>
+                <Route path="/gedeeld/:token" view=SharedGigView/>
               </Routes>
             </main>
           </div>
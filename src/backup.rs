@@ -0,0 +1,224 @@
+use chrono::NaiveDateTime;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use thiserror::Error;
+
+/// Bumped whenever the layout of [`Archive`] itself changes.
+pub const ARCHIVE_FORMAT: u32 = 1;
+
+/// Every table with band data or user settings, parents before the tables
+/// referring to them so rows can be restored in this order.
+pub const TABLES: [&str; 26] = [
+    "bands",
+    "band_memberships",
+    "songs",
+    "tags",
+    "song_tags",
+    "setlists",
+    "venues",
+    "gigs",
+    "gig_templates",
+    "members",
+    "member_unavailability",
+    "gig_attendance",
+    "song_roles",
+    "rehearsals",
+    "rehearsal_songs",
+    "rehearsal_attendance",
+    "gig_finances",
+    "gig_expenses",
+    "gig_payout_shares",
+    "calendar_tokens",
+    "audit_log",
+    "song_revisions",
+    "song_annotations",
+    "song_sections",
+    "gig_shares",
+    "user_settings",
+];
+
+#[derive(Debug, Error)]
+pub enum BackupError {
+    #[error("unsupported archive format {0}, expected {ARCHIVE_FORMAT}")]
+    Format(u32),
+    #[error("archive was made with migration {archive}, but the database is at {database}")]
+    Schema { archive: i64, database: i64 },
+    #[error("unknown table in archive: {0}")]
+    UnknownTable(String),
+    #[error("the database already has data in {}; pass --force to replace it", .0.join(", "))]
+    NotEmpty(Vec<String>),
+    #[error(transparent)]
+    Json(#[from] serde_json::Error),
+    #[error(transparent)]
+    Database(#[from] sqlx::Error),
+}
+
+/// All rows of one table, as Postgres renders them with `row_to_json`.
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
+pub struct TableDump {
+    pub name: String,
+    pub rows: Vec<Value>,
+}
+
+/// A full copy of the band data. Songs carry their lyrics, plays and the
+/// MusicBrainz ids used for cover art; the art files themselves are not
+/// included since `generate` downloads them again.
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
+pub struct Archive {
+    pub format: u32,
+    /// Version of the last migration applied when the archive was made.
+    pub schema_version: i64,
+    pub exported_at: NaiveDateTime,
+    pub tables: Vec<TableDump>,
+}
+
+impl Archive {
+    pub fn rows(&self, table: &str) -> &[Value] {
+        self.tables
+            .iter()
+            .find(|t| t.name == table)
+            .map(|t| t.rows.as_slice())
+            .unwrap_or_default()
+    }
+
+    /// Checks the archive can be restored into a database at `schema_version`.
+    /// Table names end up in SQL, so anything not in [`TABLES`] is refused.
+    pub fn validate(&self, schema_version: i64) -> Result<(), BackupError> {
+        if self.format != ARCHIVE_FORMAT {
+            return Err(BackupError::Format(self.format));
+        }
+        if self.schema_version != schema_version {
+            return Err(BackupError::Schema {
+                archive: self.schema_version,
+                database: schema_version,
+            });
+        }
+        match self
+            .tables
+            .iter()
+            .find(|t| !TABLES.contains(&t.name.as_str()))
+        {
+            Some(table) => Err(BackupError::UnknownTable(table.name.clone())),
+            None => Ok(()),
+        }
+    }
+
+    /// Refuses to import over existing data unless `force` is given. The rows
+    /// a freshly migrated database got from the migrations don't count.
+    pub fn check_empty(non_empty: Vec<String>, force: bool) -> Result<(), BackupError> {
+        match force || non_empty.is_empty() {
+            true => Ok(()),
+            false => Err(BackupError::NotEmpty(non_empty)),
+        }
+    }
+
+    /// `_sqlx_migrations` only exists once the migrator has run, so this query
+    /// can't be checked at compile time.
+    pub async fn schema_version() -> Result<i64, BackupError> {
+        let version: Option<i64> = sqlx::query_scalar("SELECT MAX(version) FROM _sqlx_migrations")
+            .fetch_one(crate::database::get_db())
+            .await?;
+        Ok(version.unwrap_or_default())
+    }
+
+    /// The tables with rows of their own. Rows the migrations added and that
+    /// are unchanged since, going by the fingerprints in `migration_seeds`,
+    /// are left out, so a backup can be restored into a fresh database.
+    async fn in_use(tx: &mut sqlx::PgConnection) -> Result<Vec<String>, BackupError> {
+        let seeds: Vec<(String, String)> =
+            sqlx::query_as("SELECT table_name::text, fingerprint::text FROM migration_seeds")
+                .fetch_all(&mut *tx)
+                .await?;
+        let mut in_use = Vec::default();
+        for name in TABLES {
+            let fingerprint: Option<String> = sqlx::query_scalar(&format!(
+                "SELECT md5(json_agg(t)::text) FROM (SELECT * FROM {} ORDER BY 1, 2) t",
+                name
+            ))
+            .fetch_one(&mut *tx)
+            .await?;
+            let seeded = |f: &String| seeds.iter().any(|(t, s)| t == name && s == f);
+            if fingerprint.is_some_and(|f| !seeded(&f)) {
+                in_use.push(name.to_string());
+            }
+        }
+        Ok(in_use)
+    }
+
+    pub async fn export() -> Result<Self, BackupError> {
+        let db = crate::database::get_db();
+        let mut tables = Vec::with_capacity(TABLES.len());
+        for name in TABLES {
+            let rows: String = sqlx::query_scalar(&format!(
+                "SELECT COALESCE(json_agg(t), '[]')::text FROM (SELECT * FROM {} ORDER BY 1, 2) t",
+                name
+            ))
+            .fetch_one(db)
+            .await?;
+            tables.push(TableDump {
+                name: name.to_string(),
+                rows: serde_json::from_str(&rows)?,
+            });
+        }
+
+        Ok(Archive {
+            format: ARCHIVE_FORMAT,
+            schema_version: Self::schema_version().await?,
+            exported_at: chrono::Utc::now().naive_utc(),
+            tables,
+        })
+    }
+
+    /// Restores the archive into an empty database, or with `force` replaces
+    /// all band data, in a single transaction so importing the same archive
+    /// twice gives the same result.
+    pub async fn import(&self, force: bool) -> Result<(), BackupError> {
+        self.validate(Self::schema_version().await?)?;
+
+        let mut tx = crate::database::get_db().begin().await?;
+        Self::check_empty(Self::in_use(&mut tx).await?, force)?;
+
+        sqlx::query(&format!(
+            "TRUNCATE {} RESTART IDENTITY CASCADE",
+            TABLES.join(", ")
+        ))
+        .execute(&mut *tx)
+        .await?;
+        for name in TABLES {
+            let rows = self.rows(name);
+            if rows.is_empty() {
+                continue;
+            }
+            sqlx::query(&format!(
+                "INSERT INTO {0} SELECT * FROM json_populate_recordset(NULL::{0}, $1::json)",
+                name
+            ))
+            .bind(serde_json::to_string(rows)?)
+            .execute(&mut *tx)
+            .await?;
+        }
+
+        // Restored rows keep their ids, so continue the sequences after them.
+        let serial_tables: Vec<String> = sqlx::query_scalar(
+            "
+SELECT table_name::text FROM information_schema.columns
+WHERE table_schema = 'public' AND column_name = 'id' AND column_default LIKE 'nextval%'",
+        )
+        .fetch_all(&mut *tx)
+        .await?;
+        for name in TABLES
+            .iter()
+            .filter(|t| serial_tables.iter().any(|s| s == *t))
+        {
+            sqlx::query(&format!(
+                "SELECT setval(pg_get_serial_sequence('{0}', 'id'), COALESCE(MAX(id), 0) + 1, false) FROM {0}",
+                name
+            ))
+            .execute(&mut *tx)
+            .await?;
+        }
+
+        tx.commit().await?;
+        Ok(())
+    }
+}
@@ -0,0 +1,240 @@
+use axum::extract::{Host, Query};
+use axum::Extension;
+use axum::http::{header, HeaderMap, StatusCode};
+use axum::response::{IntoResponse, Response};
+use chrono::{Duration, NaiveDate, NaiveDateTime, NaiveTime, Utc};
+use serde::Deserialize;
+
+use crate::error::AppError;
+use crate::models::calendar_token::CalendarToken;
+use crate::models::gig::GigModel;
+use crate::models::rehearsal::{RehearsalModel, RehearsalSession};
+use crate::models::setlist_generator::DEFAULT_SONG_SECONDS;
+use crate::models::venue::Venue;
+use crate::repository::Repositories;
+
+/// How long a gig lasts when neither an end time nor a running order is known.
+const DEFAULT_GIG_HOURS: i64 = 3;
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum EventTime {
+    AllDay(NaiveDate),
+    /// Floating local time, which calendar apps show in the user's timezone.
+    At(NaiveDateTime),
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CalendarEvent {
+    pub uid: String,
+    pub summary: String,
+    pub location: Option<String>,
+    pub description: String,
+    pub url: String,
+    pub start: EventTime,
+    pub end: EventTime,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct CalendarQuery {
+    token: Option<String>,
+}
+
+/// Serves all gigs and rehearsals as an RFC 5545 calendar at `/calendar.ics`.
+pub async fn calendar_feed(
+    repos: Option<Extension<Repositories>>,
+    Host(host): Host,
+    headers: HeaderMap,
+    Query(query): Query<CalendarQuery>,
+) -> Response {
+    let band_id = repos.map(|Extension(repos)| repos.band_id);
+    let repos = match CalendarToken::band_for(query.token.as_deref(), band_id).await {
+        Ok(Some(band_id)) => Repositories::postgres(band_id),
+        Ok(None) => return (StatusCode::FORBIDDEN, "Invalid calendar token").into_response(),
+        Err(e) => return (e.status_code(), e.to_string()).into_response(),
+    };
+
+    let scheme = headers
+        .get("x-forwarded-proto")
+        .and_then(|v| v.to_str().ok())
+        .unwrap_or("http");
+    let base_url = format!("{}://{}", scheme, host);
+
+    let events = match (gig_events(&repos, &base_url).await, RehearsalSession::get_all(repos.band_id).await) {
+        (Ok(mut events), Ok(rehearsals)) => {
+            events.extend(rehearsals.iter().map(|r| rehearsal_event(r, &base_url)));
+            Ok(events)
+        }
+        (Err(e), _) | (_, Err(e)) => Err(e),
+    };
+    match events {
+        Ok(events) => (
+            [(header::CONTENT_TYPE, "text/calendar; charset=utf-8")],
+            render_calendar(&events, Utc::now().naive_utc()),
+        )
+            .into_response(),
+        Err(e) => (e.status_code(), e.to_string()).into_response(),
+    }
+}
+
+async fn gig_events(
+    repos: &Repositories,
+    base_url: &str,
+) -> Result<Vec<CalendarEvent>, AppError> {
+    let venues = Venue::get_all(repos.band_id).await?;
+    let songs = repos.songs.get_all().await?;
+
+    Ok(repos
+        .gigs
+        .get_all()
+        .await?
+        .into_iter()
+        .map(|gig| {
+            let venue = venues.iter().find(|v| Some(v.id) == gig.venue_id);
+            let playing_seconds = gig
+                .songs
+                .iter()
+                .filter_map(|id| songs.iter().find(|s| s.id == *id))
+                .map(|s| s.length_seconds().unwrap_or(DEFAULT_SONG_SECONDS) as i64)
+                .sum();
+            gig_event(&gig, venue, playing_seconds, base_url)
+        })
+        .collect())
+}
+
+pub fn gig_event(
+    gig: &GigModel,
+    venue: Option<&Venue>,
+    playing_seconds: i64,
+    base_url: &str,
+) -> CalendarEvent {
+    let url = format!("{}/gig/{}", base_url, gig.id);
+    let (start, end) = match gig.time.as_deref().map(parse_time_range) {
+        Some((Some(start), end)) => {
+            let start = gig.date.and_time(start);
+            let end = match end {
+                Some(end) if end > start.time() => gig.date.and_time(end),
+                Some(end) => (gig.date + Duration::days(1)).and_time(end),
+                None if playing_seconds > 0 => start + Duration::seconds(playing_seconds),
+                None => start + Duration::hours(DEFAULT_GIG_HOURS),
+            };
+            (EventTime::At(start), EventTime::At(end))
+        }
+        _ => (
+            EventTime::AllDay(gig.date),
+            EventTime::AllDay(gig.date + Duration::days(1)),
+        ),
+    };
+    let location = venue
+        .map(|v| match v.address.is_empty() {
+            true => v.name.clone(),
+            false => format!("{}, {}", v.name, v.address),
+        })
+        .or_else(|| Some(gig.venue.clone()).filter(|v| !v.is_empty()));
+
+    CalendarEvent {
+        uid: format!("gig-{}@my-dad-rocks", gig.id),
+        summary: format!("My Dad Rocks @ {}", gig.venue),
+        location,
+        description: format!("Setlist: {}", url),
+        url,
+        start,
+        end,
+    }
+}
+
+/// A rehearsal spans from starting to closing it. One still in progress is
+/// shown with the default length of a gig.
+pub fn rehearsal_event(rehearsal: &RehearsalModel, base_url: &str) -> CalendarEvent {
+    let url = format!("{}/rehearsal/{}", base_url, rehearsal.id);
+    let end = rehearsal
+        .closed_at
+        .unwrap_or(rehearsal.started_at + Duration::hours(DEFAULT_GIG_HOURS));
+
+    CalendarEvent {
+        uid: format!("rehearsal-{}@my-dad-rocks", rehearsal.id),
+        summary: "My Dad Rocks repetitie".to_string(),
+        location: Some(rehearsal.location.clone()).filter(|l| !l.is_empty()),
+        description: match rehearsal.notes.is_empty() {
+            true => url.clone(),
+            false => format!("{}\n{}", rehearsal.notes, url),
+        },
+        url,
+        start: EventTime::At(rehearsal.started_at),
+        end: EventTime::At(end),
+    }
+}
+
+/// Parses the free-text gig time, e.g. "20:00", "20.30", "21u" or "20:00 - 23:30".
+pub fn parse_time_range(time: &str) -> (Option<NaiveTime>, Option<NaiveTime>) {
+    let mut parts = time.splitn(2, '-').map(parse_time);
+    (parts.next().flatten(), parts.next().flatten())
+}
+
+fn parse_time(time: &str) -> Option<NaiveTime> {
+    let time = time.trim().trim_end_matches(['u', 'h']);
+    let (hours, minutes) = time
+        .split_once([':', '.'])
+        .unwrap_or((time, "0"));
+    NaiveTime::from_hms_opt(hours.trim().parse().ok()?, minutes.trim().parse().ok()?, 0)
+}
+
+pub fn render_calendar(events: &[CalendarEvent], now: NaiveDateTime) -> String {
+    let mut lines: Vec<String> = vec![
+        "BEGIN:VCALENDAR".to_string(),
+        "VERSION:2.0".to_string(),
+        "PRODID:-//My Dad Rocks//Gigs//NL".to_string(),
+        "CALSCALE:GREGORIAN".to_string(),
+        "METHOD:PUBLISH".to_string(),
+        "X-WR-CALNAME:My Dad Rocks".to_string(),
+    ];
+    for event in events {
+        lines.push("BEGIN:VEVENT".to_string());
+        lines.push(format!("UID:{}", event.uid));
+        lines.push(format!("DTSTAMP:{}", now.format("%Y%m%dT%H%M%SZ")));
+        lines.push(format_time("DTSTART", &event.start));
+        lines.push(format_time("DTEND", &event.end));
+        lines.push(format!("SUMMARY:{}", escape(&event.summary)));
+        if let Some(location) = &event.location {
+            lines.push(format!("LOCATION:{}", escape(location)));
+        }
+        lines.push(format!("DESCRIPTION:{}", escape(&event.description)));
+        lines.push(format!("URL:{}", event.url));
+        lines.push("END:VEVENT".to_string());
+    }
+    lines.push("END:VCALENDAR".to_string());
+
+    lines.iter().map(|line| fold(line)).collect::<String>()
+}
+
+fn format_time(property: &str, time: &EventTime) -> String {
+    match time {
+        EventTime::AllDay(date) => format!("{};VALUE=DATE:{}", property, date.format("%Y%m%d")),
+        EventTime::At(at) => format!("{}:{}", property, at.format("%Y%m%dT%H%M%S")),
+    }
+}
+
+/// Escapes TEXT values (RFC 5545, 3.3.11).
+pub fn escape(text: &str) -> String {
+    text.replace('\\', "\\\\")
+        .replace(';', "\\;")
+        .replace(',', "\\,")
+        .replace("\r\n", "\\n")
+        .replace('\n', "\\n")
+}
+
+/// Folds a content line at 75 octets, without splitting UTF-8 characters, and
+/// terminates it with CRLF (RFC 5545, 3.1).
+pub fn fold(line: &str) -> String {
+    let mut folded = String::with_capacity(line.len() + 8);
+    let mut line_octets = 0;
+    for c in line.chars() {
+        if line_octets + c.len_utf8() > 75 {
+            folded.push_str("\r\n ");
+            line_octets = 1;
+        }
+        folded.push(c);
+        line_octets += c.len_utf8();
+    }
+    folded.push_str("\r\n");
+    folded
+}
@@ -1,11 +1,25 @@
+pub mod arrangement;
+pub mod audit;
+pub mod bands;
 pub(crate) mod edit_song;
+pub mod finances;
 pub mod gig;
 pub mod gigs;
 pub mod home;
 pub mod homepage_songs;
+pub mod language;
+pub mod members;
+pub mod new_gig;
 pub mod player;
 pub mod promo;
 pub mod random_selection;
+pub mod rehearsal;
+pub mod setlist_generator;
+pub mod share;
 pub mod shared;
 pub mod song_item;
+pub mod song_revisions;
+pub mod song_roles;
 pub mod song_text;
+pub mod tags;
+pub mod venues;
@@ -0,0 +1,51 @@
+use leptos::*;
+
+use crate::i18n::use_locale;
+use crate::models::section::Section;
+
+/// The sections of a song as one bar, each part as wide as its number of
+/// bars, with the length at the song's bpm.
+#[component]
+pub fn ArrangementTimeline(sections: Vec<Section>, bpm: Option<i32>) -> impl IntoView {
+    if sections.is_empty() {
+        return view! {}.into_view();
+    }
+    let locale = use_locale();
+    let length = Section::playing_seconds(&sections, bpm)
+        .map(|seconds| format!("{}:{:02}", seconds / 60, seconds % 60));
+
+    view! {
+      <div class="my-2">
+        <div class="flex w-full h-6 rounded-md overflow-hidden text-xs text-ctp-mantle">
+          {sections
+              .into_iter()
+              .map(|section| {
+                  let kind = section.kind.label(locale);
+                  let label = match section.repeats {
+                      1 => format!("{} {}", kind, section.bars),
+                      repeats => format!("{} {}×{}", kind, section.bars, repeats),
+                  };
+                  view! {
+                    <div
+                      class=format!(
+                          "{} px-1 truncate border-r border-ctp-base",
+                          section.kind.color(),
+                      )
+
+                      style=format!("flex-grow: {}", section.total_bars())
+                      title=label.clone()
+                    >
+                      {label.clone()}
+                    </div>
+                  }
+              })
+              .collect_view()}
+        </div>
+        {length
+            .map(|length| {
+                view! { <div class="text-xs text-ctp-overlay0 mt-1">"Berekende duur " {length}</div> }
+            })}
+      </div>
+    }
+    .into_view()
+}
@@ -0,0 +1,225 @@
+use leptos::*;
+use leptos_router::ActionForm;
+
+use crate::error::AppError;
+use crate::i18n::use_locale;
+use crate::models::audit::AuditEntry;
+#[cfg(feature = "ssr")]
+use crate::models::gig::Gig;
+use crate::models::gig::GigModel;
+#[cfg(feature = "ssr")]
+use crate::repository::use_repositories;
+
+#[server(GetAuditLog, "/api", "GetJson")]
+pub async fn get_audit_log() -> Result<Vec<AuditEntry>, ServerFnError<AppError>> {
+    use_repositories()?
+        .audit
+        .get_recent(200)
+        .await
+        .map_err(ServerFnError::from)
+}
+
+#[server(GetLastUndoable, "/api", "GetJson")]
+pub async fn get_last_undoable() -> Result<Option<AuditEntry>, ServerFnError<AppError>> {
+    use_repositories()?
+        .audit
+        .last_undoable()
+        .await
+        .map_err(ServerFnError::from)
+}
+
+/// Reverts the newest deletion, clearing or replacement.
+#[server(UndoLast)]
+pub async fn undo_last() -> Result<(), ServerFnError<AppError>> {
+    match AuditEntry::undo_last(&use_repositories()?).await? {
+        Some(_) => Ok(()),
+        None => Err(AppError::validation("niets om ongedaan te maken").into()),
+    }
+}
+
+#[server(GetDeletedGigs, "/api", "GetJson")]
+pub async fn get_deleted_gigs() -> Result<Vec<GigModel>, ServerFnError<AppError>> {
+    use_repositories()?
+        .gigs
+        .get_deleted()
+        .await
+        .map_err(ServerFnError::from)
+}
+
+#[server(RestoreGig)]
+pub async fn restore_gig(gig_id: i32) -> Result<(), ServerFnError<AppError>> {
+    Gig::restore(&use_repositories()?, gig_id)
+        .await
+        .map_err(ServerFnError::from)
+}
+
+/// Offers to undo the last destructive change. `changes` should change
+/// whenever the page did something that may be undone.
+#[component]
+pub fn UndoButton(
+    undo: Action<UndoLast, Result<(), ServerFnError<AppError>>>,
+    #[prop(into, optional)] changes: MaybeSignal<usize>,
+) -> impl IntoView {
+    let locale = use_locale();
+    let last = create_resource(
+        move || (undo.version().get(), changes.get()),
+        |_| get_last_undoable(),
+    );
+
+    view! {
+      <Transition>
+        {move || {
+            last.get()
+                .and_then(Result::ok)
+                .flatten()
+                .map(|entry| {
+                    view! {
+                      <ActionForm
+                        action=undo
+                        class="flex justify-between items-center m-3 px-3 py-2 rounded-lg shadow-md bg-ctp-peach text-ctp-mantle"
+                      >
+                        <span>
+                          {locale
+                              .t_with(
+                                  "audit-by",
+                                  &[
+                                      ("summary", entry.summary(locale).into()),
+                                      ("actor", entry.actor.clone().into()),
+                                  ],
+                              )}
+                        </span>
+                        <button
+                          type="submit"
+                          class="border-0 rounded-full px-3 py-1 shadow-md bg-ctp-mantle text-ctp-text"
+                        >
+                          <i class="fa-solid fa-rotate-left"></i>
+                          " "
+                          {locale.t("audit-undo")}
+                        </button>
+                      </ActionForm>
+                    }
+                })
+        }}
+
+      </Transition>
+    }
+}
+
+#[component]
+pub fn DeletedGigs() -> impl IntoView {
+    let locale = use_locale();
+    let restore = create_server_action::<RestoreGig>();
+    let gigs = create_resource(move || restore.version().get(), |_| get_deleted_gigs());
+
+    view! {
+      <div class="p-4">
+        <div class="font-bold text-xl mb-4">{locale.t("gigs-deleted")}</div>
+        <Transition>
+          {move || {
+              let gigs = gigs.get().and_then(Result::ok).unwrap_or_default();
+              if gigs.is_empty() {
+                  return view! { <p class="text-ctp-overlay0">{locale.t("gigs-none-deleted")}</p> }
+                      .into_view();
+              }
+              gigs.into_iter()
+                  .map(|gig| {
+                      view! {
+                        <div class="flex justify-between items-center py-2">
+                          <div>
+                            <div class="font-semibold">{gig.venue}</div>
+                            <div class="text-sm text-ctp-overlay0">
+                              {locale.format_date(gig.date)}
+                            </div>
+                          </div>
+                          <button
+                            type="button"
+                            class="border-0 rounded-full px-3 py-1 shadow-md bg-ctp-teal text-ctp-mantle"
+                            on:click=move |_| restore.dispatch(RestoreGig { gig_id: gig.id })
+                          >
+                            <i class="fa-solid fa-trash-arrow-up"></i>
+                            " "
+                            {locale.t("gigs-restore")}
+                          </button>
+                        </div>
+                      }
+                  })
+                  .collect_view()
+          }}
+
+        </Transition>
+      </div>
+    }
+}
+
+#[component]
+pub fn AuditLogView() -> impl IntoView {
+    let locale = use_locale();
+    let undo = create_server_action::<UndoLast>();
+    let entries = create_resource(move || undo.version().get(), |_| get_audit_log());
+
+    view! {
+      <div class="p-4">
+        <div class="font-bold text-xl mb-2">{locale.t("nav-log")}</div>
+        <UndoButton undo/>
+        <Transition>
+          {move || {
+              entries
+                  .get()
+                  .and_then(Result::ok)
+                  .unwrap_or_default()
+                  .into_iter()
+                  .map(|entry| view! { <AuditLogEntry entry/> })
+                  .collect_view()
+          }}
+
+        </Transition>
+      </div>
+    }
+}
+
+#[component]
+fn AuditLogEntry(entry: AuditEntry) -> impl IntoView {
+    let locale = use_locale();
+    let fields = match entry.action.is_destructive() {
+        // The whole running order, not worth listing field by field.
+        true => vec![],
+        false => entry.changed_fields(),
+    };
+    let show = |value: Option<serde_json::Value>| {
+        value
+            .map(|v| v.to_string())
+            .unwrap_or_else(|| "-".to_string())
+    };
+
+    view! {
+      <div class="py-2 border-b border-ctp-surface0">
+        <div class="flex justify-between">
+          <span class:line-through=entry.undone_at.is_some()>{entry.summary(locale)}</span>
+          <span class="text-sm text-ctp-overlay0">
+            {locale.format_date_time(entry.created_at)} " · " {entry.actor.clone()}
+          </span>
+        </div>
+        {entry
+            .undone_at
+            .map(|at| {
+                view! {
+                  <div class="text-sm text-ctp-peach">
+                    {locale
+                        .t_with(
+                            "audit-undone-at",
+                            &[("at", locale.format_date_time(at).into())],
+                        )}
+                  </div>
+                }
+            })}
+        <ul class="text-sm text-ctp-subtext1">
+          {fields
+              .into_iter()
+              .map(|(field, before, after)| {
+                  view! { <li>{field} ": " {show(before)} " → " {show(after)}</li> }
+              })
+              .collect_view()}
+        </ul>
+      </div>
+    }
+}
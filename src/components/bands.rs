@@ -0,0 +1,227 @@
+use leptos::*;
+use leptos_router::ActionForm;
+
+#[cfg(feature = "ssr")]
+use crate::components::shared::set_cookie;
+use crate::error::AppError;
+use crate::i18n::use_locale;
+#[cfg(feature = "ssr")]
+use crate::models::band::{current_user, Band, BAND_COOKIE};
+use crate::models::band::{BandChoice, BandRole, Membership};
+#[cfg(feature = "ssr")]
+use crate::repository::use_repositories;
+
+#[server(GetBands, "/api", "GetJson")]
+pub async fn get_bands() -> Result<BandChoice, ServerFnError<AppError>> {
+    let bands = Band::get_accessible(current_user().as_deref()).await?;
+    let current = use_repositories()
+        .ok()
+        .and_then(|repos| bands.iter().find(|b| b.id == repos.band_id).cloned());
+    Ok(BandChoice { current, bands })
+}
+
+#[server(SwitchBand)]
+pub async fn switch_band(band_id: i32) -> Result<(), ServerFnError<AppError>> {
+    let bands = Band::get_accessible(current_user().as_deref()).await?;
+    if !bands.iter().any(|b| b.id == band_id) {
+        return Err(AppError::NotFound("geen band waar je lid van bent".to_string()).into());
+    }
+    set_cookie(BAND_COOKIE, &band_id.to_string());
+    leptos_axum::redirect("/");
+    Ok(())
+}
+
+#[server(CreateBand)]
+pub async fn create_band(name: String) -> Result<(), ServerFnError<AppError>> {
+    let band_id = Band::create(name, current_user().as_deref()).await?;
+    set_cookie(BAND_COOKIE, &band_id.to_string());
+    leptos_axum::redirect("/");
+    Ok(())
+}
+
+#[server(GetMemberships, "/api", "GetJson")]
+pub async fn get_memberships() -> Result<Vec<Membership>, ServerFnError<AppError>> {
+    Band::get_memberships(use_repositories()?.band_id)
+        .await
+        .map_err(ServerFnError::from)
+}
+
+#[server(SetMembership)]
+pub async fn set_membership(
+    user_name: String,
+    role: BandRole,
+) -> Result<(), ServerFnError<AppError>> {
+    Band::set_membership(
+        use_repositories()?.band_id,
+        current_user().as_deref(),
+        user_name,
+        role,
+    )
+    .await
+    .map_err(ServerFnError::from)
+}
+
+#[server(RemoveMembership)]
+pub async fn remove_membership(user_name: String) -> Result<(), ServerFnError<AppError>> {
+    Band::remove_membership(
+        use_repositories()?.band_id,
+        current_user().as_deref(),
+        user_name,
+    )
+    .await
+    .map_err(ServerFnError::from)
+}
+
+/// Picks the band to work in, shown in the nav.
+#[component]
+pub fn BandSwitcher() -> impl IntoView {
+    let switch_band = create_server_action::<SwitchBand>();
+    let choice = create_resource(|| (), |_| get_bands());
+
+    view! {
+      <Transition>
+        {move || {
+            let choice = choice.get().and_then(Result::ok).unwrap_or_default();
+            let current = choice.current.as_ref().map(|b| b.id);
+            view! {
+              <select
+                class="rounded-md border-0 py-2 pl-2 text-ctp-mantle text-sm"
+                on:change=move |ev| {
+                    if let Ok(band_id) = event_target_value(&ev).parse() {
+                        switch_band.dispatch(SwitchBand { band_id });
+                    }
+                }
+              >
+
+                {choice
+                    .bands
+                    .into_iter()
+                    .map(|band| {
+                        view! {
+                          <option value=band.id selected=Some(band.id) == current>
+                            {band.name}
+                          </option>
+                        }
+                    })
+                    .collect_view()}
+              </select>
+            }
+        }}
+
+      </Transition>
+    }
+}
+
+/// New bands and who may open the current one.
+#[component]
+pub fn Bands() -> impl IntoView {
+    let locale = use_locale();
+    let create_band = create_server_action::<CreateBand>();
+    let set_membership = create_server_action::<SetMembership>();
+    let remove_membership = create_server_action::<RemoveMembership>();
+    let choice = create_resource(|| (), |_| get_bands());
+    let memberships = create_resource(
+        move || {
+            (
+                set_membership.version().get(),
+                remove_membership.version().get(),
+            )
+        },
+        |_| get_memberships(),
+    );
+    let input_class = "input block w-full rounded-md border-0 py-2 pl-2 text-ctp-mantle text-sm";
+    let error = move || {
+        [
+            create_band.value().get(),
+            set_membership.value().get(),
+            remove_membership.value().get(),
+        ]
+            .into_iter()
+            .flatten()
+            .find_map(Result::err)
+            .map(|e| {
+                view! { <div class="text-xs text-ctp-red mb-2">{AppError::from_server_fn(&e).to_string()}</div> }
+            })
+    };
+
+    view! {
+      <div class="m-4">
+        <ActionForm action=create_band class="flex gap-2 mb-4">
+          <input type="text" name="name" class=input_class placeholder=locale.t("bands-new")/>
+          <button
+            type="submit"
+            class="border-0 rounded-full px-3 py-2 shadow-md bg-ctp-teal text-ctp-mantle"
+          >
+            <i class="fa-solid fa-plus"></i>
+          </button>
+        </ActionForm>
+        <Transition>
+          <div class="font-bold mb-2">
+            {move || {
+                choice
+                    .get()
+                    .and_then(Result::ok)
+                    .and_then(|choice| choice.current)
+                    .map(|band| locale.t_with("bands-members-of", &[("band", band.name.into())]))
+            }}
+
+          </div>
+          {move || {
+              let memberships = memberships.get().and_then(Result::ok).unwrap_or_default();
+              if memberships.is_empty() {
+                  return view! {
+                    <div class="text-sm text-ctp-overlay0 mb-2">
+                      {locale.t("bands-open-to-everyone")}
+                    </div>
+                  }
+                      .into_view();
+              }
+              memberships
+                  .into_iter()
+                  .map(|membership| {
+                      let user_name = membership.user_name.clone();
+                      view! {
+                        <div class="bg-ctp-crust rounded-lg shadow-md p-2 mb-2 flex justify-between items-center">
+                          <div class="flex-1 font-bold text-sm">{membership.user_name}</div>
+                          <div class="text-xs mr-2">
+                            {locale.t(&format!("role-{}", membership.role.as_str()))}
+                          </div>
+                          <button
+                            type="button"
+                            class="border-0 rounded-full px-3 py-2 shadow-md bg-ctp-maroon text-ctp-mantle text-xs"
+                            on:click=move |_| {
+                                remove_membership
+                                    .dispatch(RemoveMembership {
+                                        user_name: user_name.clone(),
+                                    })
+                            }
+                          >
+
+                            <i class="fa-solid fa-trash"></i>
+                          </button>
+                        </div>
+                      }
+                  })
+                  .collect_view()
+          }}
+
+        </Transition>
+        {error}
+        <ActionForm action=set_membership class="flex gap-2">
+          <input type="text" name="user_name" class=input_class placeholder=locale.t("bands-user-name")/>
+          <select name="role" class="rounded-md border-0 py-2 pl-2 text-ctp-mantle text-sm">
+            {BandRole::ALL
+                .into_iter()
+                .map(|role| view! { <option value=role.as_str()>{locale.t(&format!("role-{}", role.as_str()))}</option> })
+                .collect_view()}
+          </select>
+          <button
+            type="submit"
+            class="border-0 rounded-md px-2 shadow-md bg-ctp-teal text-ctp-mantle text-xs whitespace-nowrap"
+          >
+            {locale.t("bands-add")}
+          </button>
+        </ActionForm>
+      </div>
+    }
+}
@@ -2,14 +2,24 @@ use leptos::*;
 use leptos_router::*;
 use serde::{Deserialize, Serialize};
 
+use crate::components::arrangement::ArrangementTimeline;
 use crate::components::shared::get_song;
-use crate::models::song::Song;
+use crate::components::song_revisions::{RestoreSongRevision, SongRevisions};
+use crate::components::song_roles::SongRolesEditor;
+use crate::components::tags::TagEditor;
+use crate::error::AppError;
+use crate::error_template::ErrorTemplate;
+use crate::i18n::use_locale;
+use crate::models::section::Section;
+use crate::models::song::{Readiness, Song};
+#[cfg(feature = "ssr")]
+use crate::repository::use_repositories;
 
 #[server(EditSong)]
-pub async fn edit_song(song: EditSongData) -> Result<(), ServerFnError> {
-    let res = Song::update(song).await?;
+pub async fn edit_song(song: EditSongData) -> Result<(), ServerFnError<AppError>> {
+    Song::update(&use_repositories()?, song).await?;
     leptos_axum::redirect("/");
-    Ok(res)
+    Ok(())
 }
 
 #[derive(Serialize, Deserialize, Debug, PartialEq, Clone, Default)]
@@ -17,8 +27,18 @@ pub struct EditSongData {
     pub id: i32,
     pub artist: String,
     pub title: String,
+    /// 0 when unknown.
     pub bpm: i32,
     pub lyrics: String,
+    pub readiness: Readiness,
+    /// In seconds, 0 when unknown.
+    pub duration: i32,
+    /// Comma separated, see [`crate::models::tag::Tag::parse_list`].
+    pub tags: String,
+    /// See [`crate::models::section::Section::parse_list`].
+    pub arrangement: String,
+    /// Like "Am" or "F#", empty when unknown.
+    pub key: String,
 }
 
 #[derive(Params, PartialEq)]
@@ -38,17 +58,31 @@ pub fn EditSongView() -> impl IntoView {
         })
     };
 
-    let song_resource = create_resource(id, get_song);
+    let restore = create_server_action::<RestoreSongRevision>();
+    let song_resource = create_resource(
+        move || (id(), restore.version().get()),
+        |(id, _)| get_song(id),
+    );
 
     view! {
       <Suspense>
+        <ErrorBoundary fallback=|errors| view! { <ErrorTemplate errors/> }>
+          {move || {
+              song_resource
+                  .get()
+                  .map(|r| {
+                      r.map(|song| {
+                          let song_id = song.id;
+                          view! {
+                            <EditSongForm song/>
+                            <SongRolesEditor song_id/>
+                            <SongRevisions song_id restore/>
+                          }
+                      })
+                  })
+          }}
 
-        {if let Some(Ok(song)) = song_resource.get() {
-            view! { <EditSongForm song/> }.into_view()
-        } else {
-            view! { <div>"Tits"</div> }.into_view()
-        }}
-
+        </ErrorBoundary>
       </Suspense>
     }
 }
@@ -56,21 +90,44 @@ pub fn EditSongView() -> impl IntoView {
 #[component]
 pub fn EditSongForm(song: Song) -> impl IntoView {
     let action = create_server_action::<EditSong>();
+    let locale = use_locale();
     view! {
       <ActionForm action=action class="mx-2 mt-2">
-        <button type="submit" class="border-0 rounded-md px-3 py-2 shadow-lg bg-ctp-lavender text-ctp-mantle">Opslaan</button>
+        <button type="submit" class="border-0 rounded-md px-3 py-2 shadow-lg bg-ctp-lavender text-ctp-mantle">{locale.t("save")}</button>
 
         <input type="hidden" name="song[id]" value={song.id}/>
 
         <div class="grid md:grid-cols-2 md:gap-6 mt-6">
-          <Input title="Artiest".to_string() value=song.artist entity="song[artist]".to_string()/>
-          <Input title="Titel".to_string() value=song.title entity="song[title]".to_string()/>
+          <Input title=locale.t("song-form-artist") value=song.artist entity="song[artist]".to_string()/>
+          <Input title=locale.t("song-form-title") value=song.title entity="song[title]".to_string()/>
+        </div>
+        <div class="grid md:grid-cols-2 md:gap-6">
+          <InputNumber title=locale.t("song-form-bpm") value=song.bpm.unwrap_or_default() entity="song[bpm]".to_string()/>
+          <ReadinessSelect value=song.readiness entity="song[readiness]".to_string()/>
+        </div>
+        <div class="grid md:grid-cols-2 md:gap-6">
+          <InputNumber
+            title=locale.t("song-form-duration")
+            value=song.duration.unwrap_or_default()
+            entity="song[duration]".to_string()
+          />
+          <Input
+            title=locale.t("song-form-key")
+            value=song.key.as_ref().map(ToString::to_string).unwrap_or_default()
+            entity="song[key]".to_string()
+          />
         </div>
-        <InputNumber title="BPM".to_string() value=song.bpm.unwrap() entity="song[bpm]".to_string()/>
+        <TagEditor tags=song.tags entity="song[tags]".to_string()/>
+        <Input
+          title=locale.t("song-form-arrangement")
+          value=Section::format_list(&song.sections, locale)
+          entity="song[arrangement]".to_string()
+        />
+        <ArrangementTimeline sections=song.sections bpm=song.bpm/>
         <textarea
           type="text"
           class="textarea textarea-bordered w-full max-w p-2 h-screen white-space:pre;"
-          placeholder="Edit lyrics"
+          placeholder=locale.t("song-form-lyrics")
           name="song[lyrics]"
           value={song.lyrics.to_string()}
         >
@@ -122,6 +179,37 @@ pub fn InputNumber(title: String, value: i32, entity: String) -> impl IntoView {
     }
 }
 
+#[component]
+pub fn ReadinessSelect(value: Readiness, entity: String) -> impl IntoView {
+    let locale = use_locale();
+    view! {
+      <div class="relative z-0 w-full mb-5 group">
+        <select
+          name=entity.clone()
+          id=entity.clone()
+          class="block py-2.5 px-0 w-full text-sm bg-transparent border-0 border-b-2 border-gray-300 appearance-none focus:outline-none focus:ring-0 focus:border-blue-600 peer"
+        >
+          {Readiness::ALL
+              .into_iter()
+              .map(|r| {
+                  view! {
+                    <option value=r.as_str() selected=r == value>
+                      {locale.t(&format!("readiness-{}", r.as_str()))}
+                    </option>
+                  }
+              })
+              .collect_view()}
+        </select>
+        <label
+          for=entity
+          class="absolute text-sm duration-300 transform -translate-y-6 scale-75 top-3 -z-10 origin-[0]"
+        >
+          {locale.t("song-form-readiness")}
+        </label>
+      </div>
+    }
+}
+
 #[component]
 fn ViewLyric(song: Song) -> impl IntoView {
     view! {
@@ -0,0 +1,353 @@
+use chrono::Datelike;
+use leptos::*;
+use leptos_router::{use_query_map, ActionForm, Form};
+
+use crate::error::AppError;
+use crate::i18n::use_locale;
+use crate::models::finance::{ExpenseCategory, GigFinances, YearOverview};
+#[cfg(feature = "ssr")]
+use crate::models::money::Euros;
+#[cfg(feature = "ssr")]
+use crate::repository::use_repositories;
+
+#[cfg(feature = "ssr")]
+fn parse_money(value: &str) -> Result<Euros, ServerFnError<AppError>> {
+    match value.trim().is_empty() {
+        true => Ok(Euros::ZERO),
+        false => value.parse().map_err(|_| {
+            AppError::validation(format!("\"{}\" is geen geldig bedrag", value.trim())).into()
+        }),
+    }
+}
+
+#[server(GetGigFinances, "/api", "GetJson")]
+pub async fn get_gig_finances(gig_id: i32) -> Result<GigFinances, ServerFnError<AppError>> {
+    GigFinances::get(use_repositories()?.band_id, gig_id)
+        .await
+        .map_err(ServerFnError::from)
+}
+
+#[server(SetGigFee)]
+pub async fn set_gig_fee(gig_id: i32, fee: String, deposit: String) -> Result<(), ServerFnError<AppError>> {
+    let band_id = use_repositories()?.band_id;
+    GigFinances::set_fee(band_id, gig_id, parse_money(&fee)?, parse_money(&deposit)?)
+        .await
+        .map_err(ServerFnError::from)
+}
+
+#[server(AddGigExpense)]
+pub async fn add_gig_expense(
+    gig_id: i32,
+    category: ExpenseCategory,
+    description: String,
+    amount: String,
+) -> Result<(), ServerFnError<AppError>> {
+    GigFinances::add_expense(
+        use_repositories()?.band_id,
+        gig_id,
+        category,
+        description.trim().to_string(),
+        parse_money(&amount)?,
+    )
+    .await
+    .map_err(ServerFnError::from)
+}
+
+#[server(RemoveGigExpense)]
+pub async fn remove_gig_expense(expense_id: i32) -> Result<(), ServerFnError<AppError>> {
+    GigFinances::remove_expense(use_repositories()?.band_id, expense_id)
+        .await
+        .map_err(ServerFnError::from)
+}
+
+#[server(SetPayoutShares)]
+pub async fn set_payout_shares(
+    gig_id: i32,
+    member_id: i32,
+    shares: u32,
+) -> Result<(), ServerFnError<AppError>> {
+    GigFinances::set_shares(use_repositories()?.band_id, gig_id, member_id, shares)
+        .await
+        .map_err(ServerFnError::from)
+}
+
+#[server(GetYearOverview, "/api", "GetJson")]
+pub async fn get_year_overview(year: i32) -> Result<YearOverview, ServerFnError<AppError>> {
+    YearOverview::get(use_repositories()?.band_id, year)
+        .await
+        .map_err(ServerFnError::from)
+}
+
+/// Fee, deposit, expenses and the payout per member of a gig.
+#[component]
+pub fn GigFinancesPanel(gig_id: i32) -> impl IntoView {
+    let locale = use_locale();
+    let set_fee = create_server_action::<SetGigFee>();
+    let add_expense = create_server_action::<AddGigExpense>();
+    let remove_expense = create_server_action::<RemoveGigExpense>();
+    let set_shares = create_server_action::<SetPayoutShares>();
+    let finances = create_resource(
+        move || {
+            (
+                set_fee.version().get(),
+                add_expense.version().get(),
+                remove_expense.version().get(),
+                set_shares.version().get(),
+                gig_id,
+            )
+        },
+        |(.., gig_id)| get_gig_finances(gig_id),
+    );
+    let input_class = "input rounded-md border-0 py-1 pl-2 text-ctp-mantle text-sm w-full";
+    let error = move || {
+        [set_fee.value().get(), add_expense.value().get()]
+            .into_iter()
+            .flatten()
+            .find_map(Result::err)
+            .map(|e| e.to_string())
+    };
+
+    view! {
+      <details class="mx-2 mt-4">
+        <summary class="font-bold cursor-pointer">
+          <i class="fa-solid fa-euro-sign"></i>
+          " "
+          {locale.t("nav-finances")}
+        </summary>
+        <div class="text-sm text-ctp-red">{error}</div>
+        <Transition>
+          {move || {
+              let finances = finances.get().and_then(Result::ok).unwrap_or_default();
+              view! {
+                <ActionForm action=set_fee class="flex gap-2 items-end mt-2">
+                  <input type="hidden" name="gig_id" value=gig_id/>
+                  <label class="text-xs flex-1">
+                    {locale.t("finance-fee")}
+                    <input
+                      type="text"
+                      name="fee"
+                      class=input_class
+                      value=finances.fee.to_string()
+                    />
+                  </label>
+                  <label class="text-xs flex-1">
+                    {locale.t("finance-deposit")}
+                    <input
+                      type="text"
+                      name="deposit"
+                      class=input_class
+                      value=finances.deposit.to_string()
+                    />
+                  </label>
+                  <button
+                    type="submit"
+                    class="border-0 rounded-md px-3 py-1 shadow-md bg-ctp-lavender text-ctp-mantle text-sm"
+                  >
+                    {locale.t("save")}
+                  </button>
+                </ActionForm>
+                <div class="text-xs opacity-70 mt-1">
+                  {locale
+                      .t_with(
+                          "finance-outstanding",
+                          &[("amount", finances.outstanding().to_string().into())],
+                      )}
+                </div>
+
+                <div class="font-bold mt-4">{locale.t("finance-expenses")}</div>
+                {finances
+                    .expenses
+                    .iter()
+                    .cloned()
+                    .map(|expense| {
+                        view! {
+                          <div class="flex justify-between items-center text-sm py-1">
+                            <div>
+                              {locale.t(&format!("expense-{}", expense.category.as_str()))} " " <span class="opacity-70">
+                                {expense.description}
+                              </span>
+                            </div>
+                            <div class="flex items-center gap-2">
+                              {expense.amount.to_string()}
+                              <button
+                                type="button"
+                                class="border-0 rounded-full px-2 py-1 shadow-md bg-ctp-maroon text-ctp-mantle text-xs"
+                                on:click=move |_| {
+                                    remove_expense
+                                        .dispatch(RemoveGigExpense {
+                                            expense_id: expense.id,
+                                        })
+                                }
+                              >
+
+                                <i class="fa-solid fa-trash"></i>
+                              </button>
+                            </div>
+                          </div>
+                        }
+                    })
+                    .collect_view()}
+                <ActionForm action=add_expense class="flex gap-2 mt-1">
+                  <input type="hidden" name="gig_id" value=gig_id/>
+                  <select name="category" class=input_class>
+                    {ExpenseCategory::ALL
+                        .into_iter()
+                        .map(|c| view! { <option value=c.as_str()>{locale.t(&format!("expense-{}", c.as_str()))}</option> })
+                        .collect_view()}
+                  </select>
+                  <input
+                    type="text"
+                    name="description"
+                    class=input_class
+                    placeholder=locale.t("finance-description")
+                  />
+                  <input type="text" name="amount" class=input_class placeholder=locale.t("finance-amount")/>
+                  <button
+                    type="submit"
+                    class="border-0 rounded-full px-3 py-1 shadow-md bg-ctp-teal text-ctp-mantle"
+                  >
+                    <i class="fa-solid fa-plus"></i>
+                  </button>
+                </ActionForm>
+                <div class="flex justify-between text-sm mt-2">
+                  <div>{locale.t("finance-net")}</div>
+                  <div class="font-bold">{finances.net().to_string()}</div>
+                </div>
+
+                <div class="font-bold mt-4">{locale.t("finance-payouts")}</div>
+                {finances
+                    .payouts()
+                    .into_iter()
+                    .map(|(share, amount)| {
+                        view! {
+                          <div class="flex justify-between items-center text-sm py-1">
+                            <div class="flex-1">{share.name}</div>
+                            <input
+                              type="number"
+                              min="0"
+                              class="input rounded-md border-0 py-1 pl-2 text-ctp-mantle text-sm w-16 mr-2"
+                              value=share.shares
+                              on:change=move |ev| {
+                                  if let Ok(shares) = event_target_value(&ev).parse() {
+                                      set_shares
+                                          .dispatch(SetPayoutShares {
+                                              gig_id,
+                                              member_id: share.member_id,
+                                              shares,
+                                          });
+                                  }
+                              }
+                            />
+
+                            <div class="w-24 text-right">{amount.to_string()}</div>
+                          </div>
+                        }
+                    })
+                    .collect_view()}
+              }
+          }}
+
+        </Transition>
+      </details>
+    }
+}
+
+#[component]
+pub fn FinanceOverview() -> impl IntoView {
+    let locale = use_locale();
+    let query = use_query_map();
+    let current_year = chrono::Local::now().year();
+    let year = create_memo(move |_| {
+        query.with(|q| {
+            q.get("year")
+                .and_then(|y| y.parse().ok())
+                .unwrap_or(current_year)
+        })
+    });
+    let overview = create_resource(move || year.get(), get_year_overview);
+
+    view! {
+      <div class="m-4">
+        <Form method="GET" action="" class="flex gap-2 items-center mb-4">
+          <select
+            name="year"
+            class="rounded-md border-0 py-1 pl-2 text-ctp-mantle text-sm"
+            onchange="this.form.requestSubmit()"
+          >
+            {move || {
+                (current_year - 5..=current_year + 1)
+                    .rev()
+                    .map(|y| view! { <option value=y selected=y == year.get()>{y}</option> })
+                    .collect_view()
+            }}
+
+          </select>
+          <a href=move || format!("/finances.csv?year={}", year.get()) class="text-sm underline">
+            <i class="fa-solid fa-file-csv"></i>
+            " CSV"
+          </a>
+        </Form>
+        <Transition>
+          {move || {
+              let overview = overview.get().and_then(Result::ok).unwrap_or_default();
+              view! {
+                <table class="w-full text-sm">
+                  <thead>
+                    <tr class="text-left">
+                      <th>{locale.t("gig-date")}</th>
+                      <th>{locale.t("gig-venue")}</th>
+                      <th class="text-right">{locale.t("finance-fee")}</th>
+                      <th class="text-right">{locale.t("finance-expenses")}</th>
+                      <th class="text-right">{locale.t("finance-net")}</th>
+                    </tr>
+                  </thead>
+                  <tbody>
+                    {overview
+                        .gigs
+                        .iter()
+                        .map(|gig| {
+                            view! {
+                              <tr>
+                                <td>
+                                  <a href=format!("/gig/{}", gig.gig_id) class="underline">
+                                    {locale.format_date(gig.date)}
+                                  </a>
+                                </td>
+                                <td>{gig.venue.clone()}</td>
+                                <td class="text-right">{gig.finances.fee.to_string()}</td>
+                                <td class="text-right">
+                                  {gig.finances.total_expenses().to_string()}
+                                </td>
+                                <td class="text-right">{gig.finances.net().to_string()}</td>
+                              </tr>
+                            }
+                        })
+                        .collect_view()}
+                    <tr class="font-bold border-t border-ctp-surface1">
+                      <td colspan="2">{locale.t("finance-total")}</td>
+                      <td class="text-right">{overview.total_fees().to_string()}</td>
+                      <td class="text-right">{overview.total_expenses().to_string()}</td>
+                      <td class="text-right">{overview.total_net().to_string()}</td>
+                    </tr>
+                  </tbody>
+                </table>
+                <div class="font-bold text-lg mt-6 mb-2">{locale.t("finance-per-member")}</div>
+                {overview
+                    .payouts_per_member()
+                    .into_iter()
+                    .map(|(name, total)| {
+                        view! {
+                          <div class="flex justify-between text-sm py-1">
+                            <div>{name}</div>
+                            <div>{total.to_string()}</div>
+                          </div>
+                        }
+                    })
+                    .collect_view()}
+              }
+          }}
+
+        </Transition>
+      </div>
+    }
+}
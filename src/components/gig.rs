@@ -4,64 +4,96 @@ use leptos_router::*;
 use crate::{
     components::song_item::SongItem,
     models::gig::{Gig, GigSong, MoveKind},
+    models::key::{smoother_order, KeyTransition},
+    models::song_role::Changeover,
 };
 use crate::components::player::{Player, PlayerData};
+use crate::components::finances::GigFinancesPanel;
+use crate::components::members::{AttendanceWarnings, GigAttendance};
+#[cfg(feature = "ssr")]
+use crate::components::new_gig::get_date_warnings;
+use crate::components::new_gig::GigReuseButtons;
+use crate::components::setlist_generator::{GenerateGigSetlist, SetlistGenerator};
+use crate::components::share::GigSharePanel;
 use crate::components::shared::{Horizontal, LyricsButton, PlayButton};
+use crate::components::venues::VenuePicker;
+use crate::error::AppError;
+use crate::error_template::ErrorTemplate;
+use crate::i18n::use_locale;
 use crate::models::song::Song;
+#[cfg(feature = "ssr")]
+use crate::repository::use_repositories;
 
 #[server(GetGig, "/api", "GetJson")]
-pub async fn get_gig(gig_id: Option<i32>) -> Result<Gig, ServerFnError> {
+pub async fn get_gig(gig_id: Option<i32>) -> Result<Gig, ServerFnError<AppError>> {
     match gig_id {
-        Some(id) => Ok(Gig::get_by_id(id).await?),
+        Some(id) => Ok(Gig::get_by_id(&use_repositories()?, id).await?),
         None => Err(ServerFnError::MissingArg("Missing gig_id".to_string())),
     }
 }
 
 #[server(AddSongToGig)]
-pub async fn add_song_to_gig(gig_id: i32, song_id: i32) -> Result<(), ServerFnError> {
-    Gig::add_song(gig_id, song_id)
+pub async fn add_song_to_gig(gig_id: i32, song_id: i32) -> Result<(), ServerFnError<AppError>> {
+    Gig::add_song(&use_repositories()?, gig_id, song_id)
         .await
         .map_err(ServerFnError::from)
 }
 
 #[server(RemoveSongFromGig)]
-pub async fn remove_song_from_gig(gig_id: i32, song_id: i32) -> Result<(), ServerFnError> {
-    Gig::remove_song(gig_id, song_id)
+pub async fn remove_song_from_gig(gig_id: i32, song_id: i32) -> Result<(), ServerFnError<AppError>> {
+    Gig::remove_song(&use_repositories()?, gig_id, song_id)
         .await
         .map_err(ServerFnError::from)
 }
 
 #[server(MoveSongInGig)]
-pub async fn move_song_gig(gig_id: i32, song_id: i32, kind: MoveKind) -> Result<(), ServerFnError> {
-    Gig::move_song(gig_id, song_id, kind)
+pub async fn move_song_gig(gig_id: i32, song_id: i32, kind: MoveKind) -> Result<(), ServerFnError<AppError>> {
+    Gig::move_song(&use_repositories()?, gig_id, song_id, kind)
         .await
         .map_err(ServerFnError::from)
 }
 
+/// Reorders the gig for smoother key changes, see [`smoother_order`].
+#[server(ApplySmootherOrder)]
+pub async fn apply_smoother_order(gig_id: i32) -> Result<(), ServerFnError<AppError>> {
+    let repos = use_repositories()?;
+    let gig = Gig::get_by_id(&repos, gig_id).await?;
+    match smoother_order(&gig.songs) {
+        Some(songs) => Gig::replace_songs(&repos, gig_id, &songs)
+            .await
+            .map_err(ServerFnError::from),
+        None => Ok(()),
+    }
+}
+
 #[server(SetGigVenue)]
-async fn set_gig_venue(gig_id: i32, venue: String) -> Result<(), ServerFnError> {
-    Gig::set_venue(gig_id, venue)
+async fn set_gig_venue(gig_id: i32, venue: String) -> Result<(), ServerFnError<AppError>> {
+    Gig::set_venue(&use_repositories()?, gig_id, venue)
         .await
         .map_err(ServerFnError::from)
 }
 
 #[server(SetGigTime)]
-async fn set_gig_time(gig_id: i32, time: String) -> Result<(), ServerFnError> {
-    Gig::set_time(gig_id, time)
+async fn set_gig_time(gig_id: i32, time: String) -> Result<(), ServerFnError<AppError>> {
+    Gig::set_time(&use_repositories()?, gig_id, time)
         .await
         .map_err(ServerFnError::from)
 }
 
+/// Moves the gig to another date. The gig is moved regardless, but the
+/// required members who marked the new date as unavailable are returned as
+/// warnings.
 #[server(SetGigDate)]
-async fn set_gig_date(gig_id: i32, date: String) -> Result<(), ServerFnError> {
-    Gig::set_date(gig_id, date)
-        .await
-        .map_err(ServerFnError::from)
+async fn set_gig_date(gig_id: i32, date: String) -> Result<Vec<String>, ServerFnError<AppError>> {
+    Gig::set_date(&use_repositories()?, gig_id, &date).await?;
+    get_date_warnings(date).await
 }
 
 #[server(RemoveGig)]
-pub async fn remove_gig(gig_id: i32) -> Result<(), ServerFnError> {
-    let res = Gig::remove(gig_id).await.map_err(ServerFnError::from);
+pub async fn remove_gig(gig_id: i32) -> Result<(), ServerFnError<AppError>> {
+    let res = Gig::remove(&use_repositories()?, gig_id)
+        .await
+        .map_err(ServerFnError::from);
     leptos_axum::redirect("/gigs");
     res
 }
@@ -91,9 +123,14 @@ pub fn Gig() -> impl IntoView {
         })
     };
 
+    let tag_filter = create_rw_signal::<Option<String>>(None);
+    let locale = use_locale();
+
     let remove_song = create_server_action::<RemoveSongFromGig>();
     let add_song = create_server_action::<AddSongToGig>();
     let move_song = create_server_action::<MoveSongInGig>();
+    let generate = create_server_action::<GenerateGigSetlist>();
+    let reorder = create_server_action::<ApplySmootherOrder>();
 
     let gig_resource = create_resource(
         move || {
@@ -101,14 +138,21 @@ pub fn Gig() -> impl IntoView {
                 remove_song.version().get(),
                 add_song.version().get(),
                 move_song.version().get(),
+                generate.version().get(),
+                reorder.version().get(),
                 Some(gig_id()),
             )
         },
-        |args| get_gig(args.3),
+        |args| get_gig(args.5),
     );
 
     view! {
       <Player/>
+      <Transition>
+        <ErrorBoundary fallback=|errors| view! { <ErrorTemplate errors/> }>
+          {move || gig_resource.get().map(|r| r.map(|_| ()))}
+        </ErrorBoundary>
+      </Transition>
       <div class="flex flex-col mt-2">
         <div class="flex gap-2 mx-2">
           <Transition>
@@ -128,6 +172,23 @@ pub fn Gig() -> impl IntoView {
         </div>
       </div>
 
+      {move || view! { <GigReuseButtons gig_id=gig_id()/> }}
+      {move || view! { <GigAttendance gig_id=gig_id()/> }}
+      {move || view! { <GigFinancesPanel gig_id=gig_id()/> }}
+      {move || view! { <GigSharePanel gig_id=gig_id()/> }}
+      {move || view! { <SetlistGenerator gig_id=gig_id() generate/> }}
+      <Transition>
+        {move || {
+            let songs = gig_resource
+                .get()
+                .unwrap_or_else(|| Ok(Gig::default()))
+                .unwrap_or_default()
+                .songs;
+            view! { <KeyOrderSuggestion songs gig_id=gig_id() reorder/> }
+        }}
+
+      </Transition>
+
       <Horizontal/>
 
       <div class="grid grid-flow-row auto-rows-max gap-2">
@@ -146,6 +207,27 @@ pub fn Gig() -> impl IntoView {
             let:song
           >
             <SelectedGigSong
+              changeover=Changeover::find(
+                      &gig_resource
+                          .get()
+                          .unwrap_or_else(|| Ok(Gig::default()))
+                          .unwrap_or_default()
+                          .songs,
+                      locale,
+                  )
+                  .into_iter()
+                  .find(|c| c.song_id == song.id)
+                  .map(|c| c.changes)
+                  .unwrap_or_default()
+              key_transition=KeyTransition::find(
+                      &gig_resource
+                          .get()
+                          .unwrap_or_else(|| Ok(Gig::default()))
+                          .unwrap_or_default()
+                          .songs,
+                  )
+                  .into_iter()
+                  .find(|t| t.song_id == song.id)
               selected_song=song
               all_songs=gig_resource
                   .get()
@@ -165,7 +247,23 @@ pub fn Gig() -> impl IntoView {
       </div>
 
       <Horizontal/>
-      <div class="flex justify-end mr-2 mt-4 mb-2">
+      <div class="flex justify-between items-center mx-2 mt-4 mb-2">
+        <Transition>
+          {move || {
+              let mut tags: Vec<String> = gig_resource
+                  .get()
+                  .unwrap_or_else(|| Ok(Gig::default()))
+                  .unwrap_or_default()
+                  .unselected_songs
+                  .into_iter()
+                  .flat_map(|s| s.tags)
+                  .collect();
+              tags.sort();
+              tags.dedup();
+              view! { <TagFilter tags tag_filter/> }
+          }}
+
+        </Transition>
         <PauseButton gig_id=gig_id() add_song/>
       </div>
 
@@ -179,6 +277,9 @@ pub fn Gig() -> impl IntoView {
                     .unwrap_or_default()
                     .unselected_songs
                     .into_iter()
+                    .filter(move |s| {
+                        tag_filter.with(|t| t.as_ref().is_none_or(|t| s.tags.contains(t)))
+                    })
             }
 
             key=|state| state.clone()
@@ -198,9 +299,12 @@ pub fn MetaDataButtons(gig: Gig) -> impl IntoView {
     let set_gig_time = create_server_action::<SetGigTime>();
     let set_gig_date = create_server_action::<SetGigDate>();
 
+    let locale = use_locale();
+
     view! {
+      <VenuePicker gig_id=gig.id venue_id=gig.venue_id/>
       <InputWithLabel
-        label="Venue".to_string()
+        label=locale.t("gig-venue")
         value=gig.venue
         on:input=move |ev| {
             set_gig_venue
@@ -212,7 +316,7 @@ pub fn MetaDataButtons(gig: Gig) -> impl IntoView {
       />
 
       <InputWithLabel
-        label="Tijd".to_string()
+        label=locale.t("gig-time")
         value=gig.time.unwrap_or("".to_string())
         on:input=move |ev| {
             set_gig_time
@@ -224,7 +328,7 @@ pub fn MetaDataButtons(gig: Gig) -> impl IntoView {
       />
 
       <InputWithLabel
-        label="Datum".to_string()
+        label=locale.t("gig-date")
         value=gig.date.to_string()
         on:input=move |ev| {
             set_gig_date
@@ -234,6 +338,11 @@ pub fn MetaDataButtons(gig: Gig) -> impl IntoView {
                 });
         }
       />
+
+      {move || {
+          let warnings = set_gig_date.value().get().and_then(Result::ok).unwrap_or_default();
+          view! { <AttendanceWarnings warnings/> }
+      }}
     }
 }
 
@@ -258,7 +367,7 @@ pub fn DeleteGigButton(gig_id: i32) -> impl IntoView {
     }
 }
 
-type Act<T> = Action<T, Result<(), ServerFnError>>;
+type Act<T> = Action<T, Result<(), ServerFnError<AppError>>>;
 
 #[component]
 pub fn PauseButton(gig_id: i32, add_song: Act<AddSongToGig>) -> impl IntoView {
@@ -276,7 +385,7 @@ pub fn PauseButton(gig_id: i32, add_song: Act<AddSongToGig>) -> impl IntoView {
       >
 
         <i class="fa-solid fa-pause"></i>
-        Pauze
+        {use_locale().t("gig-add-break")}
       </button>
     }
 }
@@ -311,6 +420,51 @@ pub fn MoveSongInSet(
     }
 }
 
+/// Offers a running order with smoother key changes when the current one has
+/// awkward ones.
+#[component]
+pub fn KeyOrderSuggestion(
+    songs: Vec<GigSong>,
+    gig_id: i32,
+    reorder: Act<ApplySmootherOrder>,
+) -> impl IntoView {
+    let awkward = KeyTransition::find(&songs).len();
+    let Some(order) = smoother_order(&songs).filter(|_| awkward > 0) else {
+        return view! {}.into_view();
+    };
+    let locale = use_locale();
+    let titles = order
+        .iter()
+        .filter_map(|id| songs.iter().find(|s| s.id == *id))
+        .map(|s| match &s.song {
+            Some(song) => song.title.clone(),
+            None => locale.t("gig-break"),
+        })
+        .collect::<Vec<_>>()
+        .join(", ");
+    view! {
+      <div class="mx-2 my-2 p-2 rounded-lg bg-ctp-surface0 text-sm">
+        <div>
+          <i class="fa-solid fa-music"></i>
+          " "
+          {locale.t_with("gig-awkward-keys", &[("count", awkward.into())])}
+          " "
+          <span class="text-ctp-subtext0">{titles}</span>
+        </div>
+        <ActionForm action=reorder class="mt-2">
+          <input type="hidden" name="gig_id" value=gig_id/>
+          <button
+            type="submit"
+            class="border-0 rounded-full px-3 py-1 shadow-md bg-ctp-yellow text-ctp-mantle"
+          >
+            {locale.t("gig-apply-order")}
+          </button>
+        </ActionForm>
+      </div>
+    }
+    .into_view()
+}
+
 #[component]
 pub fn SelectedGigSong(
     selected_song: GigSong,
@@ -318,11 +472,18 @@ pub fn SelectedGigSong(
     gig_id: i32,
     remove_song: Act<RemoveSongFromGig>,
     move_song: Act<MoveSongInGig>,
+    /// Tuning and instrument swaps needed after the previous song.
+    #[prop(optional)]
+    changeover: Vec<String>,
+    /// Set when the key lies far from the previous song's.
+    #[prop(optional_no_strip)]
+    key_transition: Option<KeyTransition>,
 ) -> impl IntoView {
     let get_selected_song = use_context::<ReadSignal<Option<i32>>>()
         .expect("Expected to have a selected song signal provided");
     let set_selected_song = use_context::<WriteSignal<Option<i32>>>()
         .expect("Expected to have a selected song signal provided");
+    let locale = use_locale();
     view! {
       {if selected_song.id < 0 {
           view! {
@@ -341,7 +502,9 @@ pub fn SelectedGigSong(
 
                 class="flex-1"
               >
-                <div class="place-self-center font-bold text-sm ml-6 my-3">pauze</div>
+                <div class="place-self-center font-bold text-sm ml-6 my-3">
+                  {locale.t("gig-break")}
+                </div>
               </button>
             </div>
             <Show when=move || get_selected_song.get() == Some(selected_song.id)>
@@ -364,6 +527,35 @@ pub fn SelectedGigSong(
       } else {
           let song = selected_song.song.unwrap();
           view! {
+            {changeover
+                .into_iter()
+                .map(|change| {
+                    view! {
+                      <div class="mx-2 px-2 rounded-md text-xs bg-ctp-peach text-ctp-mantle">
+                        <i class="fa-solid fa-right-left"></i>
+                        " "
+                        {change}
+                      </div>
+                    }
+                })
+                .collect_view()}
+            {key_transition
+                .map(|transition| {
+                    view! {
+                      <div class="mx-2 px-2 rounded-md text-xs bg-ctp-yellow text-ctp-mantle">
+                        <i class="fa-solid fa-music"></i>
+                        " "
+                        {locale
+                            .t_with(
+                                "gig-key-change",
+                                &[
+                                    ("from", transition.from.to_string().into()),
+                                    ("to", transition.to.to_string().into()),
+                                ],
+                            )}
+                      </div>
+                    }
+                })}
             <div class="bg-ctp-crust py-2 rounded-lg border-0 shadow-md">
               <div class="ml-2 flex">
                 <div class="flex">
@@ -457,17 +649,56 @@ pub fn UnSelectedGigSong(song: Song, gig_id: i32, add_song: Act<AddSongToGig>) -
     }
 }
 
+/// Narrows the songs that can be added to the gig down to a single tag.
+#[component]
+pub fn TagFilter(tags: Vec<String>, tag_filter: RwSignal<Option<String>>) -> impl IntoView {
+    view! {
+      <div class="flex flex-wrap gap-1">
+        {tags
+            .into_iter()
+            .map(|tag| {
+                let is_selected = {
+                    let tag = tag.clone();
+                    move || tag_filter.with(|t| t.as_ref() == Some(&tag))
+                };
+                let toggle = {
+                    let tag = tag.clone();
+                    move |_| {
+                        tag_filter
+                            .update(|t| {
+                                *t = if t.as_ref() == Some(&tag) { None } else { Some(tag.clone()) };
+                            })
+                    }
+                };
+                view! {
+                  <button
+                    type="button"
+                    class="px-2 py-1 rounded-md text-xs"
+                    class=("bg-ctp-lavender", is_selected.clone())
+                    class=("text-ctp-mantle", is_selected.clone())
+                    class=("bg-ctp-surface1", move || !is_selected())
+                    on:click=toggle
+                  >
+                    {tag}
+                  </button>
+                }
+            })
+            .collect_view()}
+      </div>
+    }
+}
+
 #[component]
 pub fn InputWithLabel(label: String, value: String) -> impl IntoView {
     view! {
       <div>
-        <label class="block text-sm font-medium leading-6">{label}</label>
+        <label class="block text-sm font-medium leading-6">{label.clone()}</label>
         <div class="relative mt-2 rounded-md shadow-sm text-sm">
           <input
             type="text"
             class="input block w-full rounded-md border-0 py-2 pl-2 text-ctp-mantle ring-1 ring-inset ring-ctp-surface0 focus:ring-2 focus:ring-inset focus:ring-ctp-flamingo"
             prop:value=value
-            placeholder="Venue"
+            placeholder=label
           />
         </div>
       </div>
@@ -1,54 +1,218 @@
 use leptos::*;
+use leptos_router::ActionForm;
 
-use crate::models::gig::{Gig, GigModel};
+use crate::components::audit::{UndoButton, UndoLast};
+use crate::components::members::{get_gigs_attendance, AttendanceChips};
+use crate::components::venues::get_venues;
+use crate::error::AppError;
+use crate::i18n::use_locale;
+use crate::models::calendar_token::CalendarToken;
+use crate::models::gig::{self, GigModel};
+use crate::models::member::Attendance;
+#[cfg(feature = "ssr")]
+use crate::repository::use_repositories;
 
 #[server(GetGigs, "/api", "GetJson")]
-pub async fn get_gigs() -> Result<Vec<GigModel>, ServerFnError> {
-    Gig::get_all().await.map_err(ServerFnError::from)
+pub async fn get_gigs() -> Result<Vec<GigModel>, ServerFnError<AppError>> {
+    use_repositories()?
+        .gigs
+        .get_all()
+        .await
+        .map_err(ServerFnError::from)
 }
 
-#[server(CreateGig)]
-pub async fn create_gig() -> Result<(), ServerFnError> {
-    Gig::create().await.map_err(ServerFnError::from)
+#[server(GetCalendarTokens, "/api", "GetJson")]
+pub async fn get_calendar_tokens() -> Result<Vec<CalendarToken>, ServerFnError<AppError>> {
+    CalendarToken::get_all(use_repositories()?.band_id)
+        .await
+        .map_err(ServerFnError::from)
+}
+
+#[server(CreateCalendarToken)]
+pub async fn create_calendar_token(name: String) -> Result<(), ServerFnError<AppError>> {
+    let name = name.trim().to_string();
+    if name.is_empty() {
+        return Err(AppError::validation("Vul een naam in").into());
+    }
+    CalendarToken::create(use_repositories()?.band_id, name)
+        .await
+        .map_err(ServerFnError::from)
+}
+
+#[server(RevokeCalendarToken)]
+pub async fn revoke_calendar_token(token_id: i32) -> Result<(), ServerFnError<AppError>> {
+    CalendarToken::revoke(use_repositories()?.band_id, token_id)
+        .await
+        .map_err(ServerFnError::from)
 }
 
 #[component]
 pub fn Gigs() -> impl IntoView {
-    let create_gig = create_server_action::<CreateGig>();
-    let gigs_resource = create_resource(move || create_gig.version().get(), |_| get_gigs());
+    let undo = create_server_action::<UndoLast>();
+    let gigs_resource = create_resource(move || undo.version().get(), |_| get_gigs());
+    let attendance_resource = create_resource(|| (), |_| get_gigs_attendance());
+    let venues_resource = create_resource(|| (), |_| get_venues());
+    let group_by_venue = create_rw_signal(false);
+    let locale = use_locale();
 
     view! {
       <div class="pt-4 pl-4">
-        <button
-          type="button"
-          class="border-0 rounded-full px-3 py-2 shadow-md bg-ctp-teal text-ctp-mantle"
-          on:click=move |_| { create_gig.dispatch(CreateGig {}) }
+        <a
+          href="/gigs/new"
+          class="inline-block border-0 rounded-full px-3 py-2 shadow-md bg-ctp-teal text-ctp-mantle"
         >
           <i class="fa-solid fa-plus"></i>
-          Nieuw optreden
-        </button>
-        <ol class="relative border-s border-gray-200 mx-2 mt-4">
-          <Transition>
-            {move || {
-                gigs_resource
-                    .get()
-                    .unwrap_or_else(|| Ok(vec![]))
-                    .unwrap_or_default()
-                    .into_iter()
-                    .map(move |gig| { view! { <Timeline gig/> }.into_view() })
-                    .collect_view()
-            }}
-
-          </Transition>
-        </ol>
+          {locale.t("gigs-new")}
+        </a>
+        <label class="ml-4 text-sm">
+          <input
+            type="checkbox"
+            class="mr-1"
+            prop:checked=move || group_by_venue.get()
+            on:change=move |ev| group_by_venue.set(event_target_checked(&ev))
+          />
+          {locale.t("gigs-group-by-venue")}
+        </label>
+        <a href="/gigs/verwijderd" class="ml-4 text-sm underline">
+          {locale.t("gigs-deleted")}
+        </a>
+        <UndoButton undo/>
+        <Transition>
+          {move || {
+              let gigs = gigs_resource.get().unwrap_or_else(|| Ok(vec![])).unwrap_or_default();
+              let attendance = attendance_resource
+                  .get()
+                  .and_then(Result::ok)
+                  .unwrap_or_default();
+              match group_by_venue.get() {
+                  false => view! { <TimelineList gigs attendance/> }.into_view(),
+                  true => {
+                      let venues = venues_resource.get().and_then(Result::ok).unwrap_or_default();
+                      gig::group_by_venue(gigs, &venues)
+                          .into_iter()
+                          .map(|(venue, gigs)| {
+                              view! {
+                                <div class="mt-6 font-bold">{venue}</div>
+                                <TimelineList gigs attendance=attendance.clone()/>
+                              }
+                          })
+                          .collect_view()
+                  }
+              }
+          }}
+
+        </Transition>
 
+        <CalendarSubscriptions/>
       </div>
     }
     .into_view()
 }
 
+/// Links to subscribe to the gigs in a calendar app, one per member. Without
+/// any member links the feed is public.
+#[component]
+pub fn CalendarSubscriptions() -> impl IntoView {
+    let create_token = create_server_action::<CreateCalendarToken>();
+    let revoke_token = create_server_action::<RevokeCalendarToken>();
+    let tokens = create_resource(
+        move || (create_token.version().get(), revoke_token.version().get()),
+        |_| get_calendar_tokens(),
+    );
+    let locale = use_locale();
+
+    view! {
+      <div class="mt-8 mr-4">
+        <div class="font-bold text-lg mb-2">
+          <i class="fa-solid fa-calendar-plus"></i>
+          " "
+          {locale.t("calendar")}
+        </div>
+        <Transition>
+          {move || {
+              let tokens = tokens.get().and_then(Result::ok).unwrap_or_default();
+              match tokens.is_empty() {
+                  true => {
+                      view! {
+                        <a href="/calendar.ics" class="text-sm underline">
+                          "/calendar.ics"
+                        </a>
+                      }
+                          .into_view()
+                  }
+                  false => {
+                      tokens
+                          .into_iter()
+                          .map(|token| {
+                              let link = format!("/calendar.ics?token={}", token.token);
+                              view! {
+                                <div class="flex justify-between items-center text-sm py-1">
+                                  <div>
+                                    {token.name} ": " <a href=link.clone() class="underline">
+                                      {link.clone()}
+                                    </a>
+                                  </div>
+                                  <button
+                                    type="button"
+                                    class="border-0 rounded-full px-2 py-1 shadow-md bg-ctp-maroon text-ctp-mantle text-xs"
+                                    on:click=move |_| {
+                                        revoke_token
+                                            .dispatch(RevokeCalendarToken {
+                                                token_id: token.id,
+                                            })
+                                    }
+                                  >
+
+                                    <i class="fa-solid fa-trash"></i>
+                                  </button>
+                                </div>
+                              }
+                          })
+                          .collect_view()
+                  }
+              }
+          }}
+
+        </Transition>
+        <ActionForm action=create_token class="flex gap-2 mt-2">
+          <input
+            type="text"
+            name="name"
+            class="input rounded-md border-0 py-1 pl-2 text-ctp-mantle text-sm"
+            placeholder=locale.t("calendar-member-name")
+          />
+          <button
+            type="submit"
+            class="border-0 rounded-full px-3 py-1 shadow-md bg-ctp-teal text-ctp-mantle text-sm"
+          >
+            {locale.t("calendar-personal-link")}
+          </button>
+        </ActionForm>
+      </div>
+    }
+}
+
+#[component]
+pub fn TimelineList(gigs: Vec<GigModel>, attendance: Vec<(i32, Attendance)>) -> impl IntoView {
+    view! {
+      <ol class="relative border-s border-gray-200 mx-2 mt-4">
+        {gigs
+            .into_iter()
+            .map(|gig| {
+                let attendance = attendance
+                    .iter()
+                    .filter(|(gig_id, _)| *gig_id == gig.id)
+                    .map(|(_, a)| a.clone())
+                    .collect();
+                view! { <Timeline gig attendance/> }
+            })
+            .collect_view()}
+      </ol>
+    }
+}
+
 #[component]
-pub fn Timeline(gig: GigModel) -> impl IntoView {
+pub fn Timeline(gig: GigModel, attendance: Vec<Attendance>) -> impl IntoView {
     view! {
       <li class="mb-10 ms-6">
 
@@ -58,10 +222,11 @@ pub fn Timeline(gig: GigModel) -> impl IntoView {
           </span>
           <h3 class="flex items-center mb-1 text-lg font-semibold text-ctp-text">{gig.venue}</h3>
           <time class="block mb-2 text-sm font-normal leading-none text-ctp-overlay0">
-            {gig.date.to_string()}
+            {use_locale().format_date(gig.date)}
           </time>
         // <p class="mb-4 text-base font-normal text-ctp-subtext1">Besloten feest</p>
         </a>
+        <AttendanceChips attendance/>
       </li>
     }
     .into_view()
@@ -1,66 +1,74 @@
 use leptos::*;
-use leptos_router::ActionForm;
+use leptos_router::{use_query_map, ActionForm, Form};
 
+use crate::components::audit::{UndoButton, UndoLast};
 use crate::components::shared::{EditButton, Horizontal, LyricsButton, PlayButton};
+use crate::components::rehearsal::{
+    get_open_rehearsal, CloseRehearsal, RehearsalPanel, SetRehearsalSongNotes, StartRehearsal,
+    TickRehearsalSong,
+};
 use crate::components::song_item::SongItem;
+use crate::components::tags::{get_tags, TagSelect};
+use crate::error::AppError;
+use crate::i18n::use_locale;
+use crate::models::rehearsal::RehearsalSession;
 use crate::models::setlist::Setlist;
-use crate::models::song::{Rehearsal, Song};
-
-type Result<T> = std::result::Result<T, ServerFnError>;
-
+use crate::models::tag::Tag;
+use crate::models::song::{Readiness, Rehearsal, Song, SongFilter, SongSort};
 #[cfg(feature = "ssr")]
-pub async fn find_unpracticed_unselected_random_song(already_selected: &[Song]) -> Result<Song> {
-    use super::random_selection::get_random_song;
-
-    while let Ok(song) = get_random_song().await {
-        if !already_selected.contains(&song) {
-            return Ok(song);
-        }
-    }
-    Err(ServerFnError::ServerError(
-        "Can't find unpracticed songs".to_string(),
-    ))
-}
+use crate::repository::use_repositories;
 
+/// Fills the practice setlist up to `max_n` songs, favouring songs that haven't
+/// been played in a while. With a `tag` only songs carrying that tag are picked.
 #[server(FillSetlist)]
-pub async fn fill_setlist(max_n: i32) -> Result<()> {
-    let setlist = Setlist::get().await?;
+pub async fn fill_setlist(max_n: i32, tag: Option<String>) -> Result<(), ServerFnError<AppError>> {
+    use super::random_selection::weighted_random_index;
+
+    let repos = use_repositories()?;
+    let setlist = repos.setlists.get_practice().await?;
+    let tag = tag.filter(|t| !t.is_empty());
+    let mut candidates: Vec<Song> = repos
+        .songs
+        .get_all()
+        .await?
+        .into_iter()
+        .filter(|s| !setlist.songs.contains(&s.id))
+        .filter(|s| tag.as_ref().is_none_or(|t| s.tags.contains(t)))
+        .collect();
+
     let songs_to_find = max_n - setlist.songs.len() as i32;
     let mut selected: Vec<Song> = Vec::default();
     for _ in 0..songs_to_find {
-        let song = find_unpracticed_unselected_random_song(&selected).await?;
-        selected.push(song);
+        if candidates.is_empty() {
+            break;
+        }
+        let index = weighted_random_index(&mut candidates)?;
+        selected.push(candidates.remove(index));
     }
 
-    Setlist::set_songs(selected.iter().map(|s| s.id).collect())
+    Setlist::set_songs(&repos, selected.iter().map(|s| s.id).collect())
         .await
         .map_err(ServerFnError::from)
 }
 
 #[server(GetSongs, "/api", "GetJson")]
-pub async fn get_songs() -> Result<Rehearsal> {
-    match Song::get_rehearsal().await {
+pub async fn get_songs(filter: SongFilter) -> Result<Rehearsal, ServerFnError<AppError>> {
+    match Song::get_rehearsal(&use_repositories()?, &filter).await {
         Ok(s) => Ok(s),
         Err(e) => Err(ServerFnError::from(e)),
     }
 }
 
 #[server(CleanSetlist)]
-pub async fn clean_setlist() -> Result<()> {
-    Setlist::clean().await.map_err(ServerFnError::from)
-}
-
-#[server(SetSongPlayed)]
-pub async fn set_song_played(song_id: i32) -> Result<()> {
-    match Song::set_played(song_id).await {
-        Ok(_) => Ok(()),
-        Err(e) => Err(ServerFnError::from(e)),
-    }
+pub async fn clean_setlist() -> Result<(), ServerFnError<AppError>> {
+    Setlist::clean(&use_repositories()?)
+        .await
+        .map_err(ServerFnError::from)
 }
 
 #[server(HandPickSong)]
-pub async fn pick_song(song_id: i32) -> Result<()> {
-    Setlist::set_songs(vec![song_id])
+pub async fn pick_song(song_id: i32) -> Result<(), ServerFnError<AppError>> {
+    Setlist::set_songs(&use_repositories()?, vec![song_id])
         .await
         .map_err(ServerFnError::from)
 }
@@ -70,34 +78,83 @@ pub fn Songs() -> impl IntoView {
     let empty_setlist = create_server_action::<CleanSetlist>();
     let fill = create_server_action::<FillSetlist>();
 
-    let set_song_played = create_server_action::<SetSongPlayed>();
+    let start_rehearsal = create_server_action::<StartRehearsal>();
+    let tick_song = create_server_action::<TickRehearsalSong>();
+    let set_song_notes = create_server_action::<SetRehearsalSongNotes>();
+    let close_rehearsal = create_server_action::<CloseRehearsal>();
     let pick_song = create_server_action::<HandPickSong>();
+    let undo = create_server_action::<UndoLast>();
 
     let (get_selected_song, set_selected_song) = create_signal::<Option<i32>>(None);
+    let locale = use_locale();
+
+    let tags = create_resource(|| (), |_| get_tags());
+    let query = use_query_map();
+    let filter = create_memo(move |_| query.with(SongFilter::from_query));
 
     let rehearsal = create_resource(
         move || {
             (
-                set_song_played.version().get(),
+                tick_song.version().get(),
                 pick_song.version().get(),
                 empty_setlist.version().get(),
                 fill.version().get(),
+                undo.version().get(),
+                filter.get(),
             )
         },
-        |_| get_songs(),
+        |(.., filter)| get_songs(filter),
     );
+    let open_rehearsal = create_resource(
+        move || {
+            (
+                start_rehearsal.version().get(),
+                tick_song.version().get(),
+                set_song_notes.version().get(),
+                close_rehearsal.version().get(),
+            )
+        },
+        |_| get_open_rehearsal(),
+    );
+    let open_rehearsal =
+        Signal::derive(move || open_rehearsal.get().and_then(Result::ok).flatten());
 
     view! {
+      <Transition>
+        {move || {
+            view! {
+              <RehearsalPanel
+                rehearsal=open_rehearsal.get()
+                start_rehearsal
+                set_song_notes
+                close_rehearsal
+              />
+            }
+        }}
+
+      </Transition>
       <div class="flex justify-between m-3 items-center">
-        <div class="font-bold text-xl flex">Setlist</div>
+        <div class="font-bold text-xl flex">{locale.t("songs-setlist")}</div>
         <div class="flex">
-          <FillButton fill_action=fill/>
+          <Transition>
+            {move || {
+                let tags = tags.get().and_then(|t| t.ok()).unwrap_or_default();
+                view! { <FillButton fill_action=fill tags/> }
+            }}
+
+          </Transition>
           <CleanButton clean_action=empty_setlist/>
         </div>
       </div>
+      <UndoButton
+        undo
+        changes=Signal::derive(move || {
+            empty_setlist.version().get() + fill.version().get() + pick_song.version().get()
+        })
+      />
       <div class="grid grid-flow-row auto-rows-max gap-2">
         <Transition fallback=move || {
-            view! { <p>"Loading..."</p> }
+            view! { <p>{locale.t("loading")}</p> }
         }>
 
           <For
@@ -122,7 +179,8 @@ pub fn Songs() -> impl IntoView {
                   .selected_songs
               pick_song
               in_rehearsal=true
-              set_song_played
+              open_rehearsal
+              tick_song
               get_selected_song
               set_selected_song
             />
@@ -134,11 +192,18 @@ pub fn Songs() -> impl IntoView {
       <Horizontal/>
 
       <div class="flex items-center justify-between mb-3 ml-3">
-        <div class="font-bold text-xl">Alle nummers</div>
+        <div class="font-bold text-xl">{locale.t("songs-all")}</div>
       </div>
+      <Transition>
+        {move || {
+            let tags = tags.get().and_then(|t| t.ok()).unwrap_or_default();
+            view! { <SongFilterForm filter=filter.get() tags/> }
+        }}
+
+      </Transition>
       <div class="grid grid-flow-row auto-rows-max gap-2">
         <Transition fallback=move || {
-            view! { <p>"Loading..."</p> }
+            view! { <p>{locale.t("loading")}</p> }
         }>
 
           <For
@@ -163,7 +228,8 @@ pub fn Songs() -> impl IntoView {
                   .unselected_songs
               pick_song
               in_rehearsal=false
-              set_song_played
+              open_rehearsal
+              tick_song
               get_selected_song
               set_selected_song
             />
@@ -174,24 +240,112 @@ pub fn Songs() -> impl IntoView {
 }
 
 #[component]
-pub fn FillButton(fill_action: Action<FillSetlist, Result<()>>) -> impl IntoView {
+pub fn SongFilterForm(filter: SongFilter, tags: Vec<Tag>) -> impl IntoView {
+    let input_class = "input block w-full rounded-md border-0 py-1 pl-2 text-ctp-mantle ring-1 ring-inset ring-ctp-surface0 focus:ring-2 focus:ring-inset focus:ring-ctp-flamingo text-sm";
+    let is_active = filter.is_active();
+    let locale = use_locale();
+
     view! {
-      <ActionForm action=fill_action>
+      <Form method="GET" action="" class="mx-3 mb-3 grid grid-cols-2 md:grid-cols-4 gap-2 items-end">
+        <input
+          type="search"
+          name="query"
+          class=format!("{} col-span-2", input_class)
+          placeholder=locale.t("filter-query")
+          value=filter.query.unwrap_or_default()
+        />
+        <select name="readiness" class=input_class>
+          <option value="" selected=filter.readiness.is_none()>
+            {locale.t("filter-all-readiness")}
+          </option>
+          {Readiness::ALL
+              .into_iter()
+              .map(|r| {
+                  view! {
+                    <option value=r.as_str() selected=filter.readiness == Some(r)>
+                      {locale.t(&format!("readiness-{}", r.as_str()))}
+                    </option>
+                  }
+              })
+              .collect_view()}
+        </select>
+        <TagSelect tags selected=filter.tag name="tag" class=input_class/>
+        <select name="sort" class=input_class>
+          {SongSort::ALL
+              .into_iter()
+              .map(|sort| {
+                  view! {
+                    <option value=sort.as_str() selected=filter.sort == sort>
+                      {locale.t(&format!("sort-{}", sort.as_str()))}
+                    </option>
+                  }
+              })
+              .collect_view()}
+        </select>
+        <input
+          type="number"
+          name="min_bpm"
+          class=input_class
+          placeholder=locale.t("filter-min-bpm")
+          value=filter.min_bpm
+        />
+        <input
+          type="number"
+          name="max_bpm"
+          class=input_class
+          placeholder=locale.t("filter-max-bpm")
+          value=filter.max_bpm
+        />
+        <input
+          type="number"
+          name="not_played_days"
+          class=input_class
+          placeholder=locale.t("filter-not-played-days")
+          value=filter.not_played_days
+        />
+        <div class="flex gap-2">
+          <button
+            type="submit"
+            class="border-0 rounded-md px-3 py-1 shadow-md bg-ctp-teal text-ctp-mantle"
+          >
+            <i class="fa-solid fa-magnifying-glass"></i>
+            {locale.t("filter-search")}
+          </button>
+          <Show when=move || is_active>
+            <a href="/" class="rounded-md px-3 py-1 shadow-md bg-ctp-surface1 text-ctp-text">
+              <i class="fa-solid fa-xmark"></i>
+            </a>
+          </Show>
+        </div>
+      </Form>
+    }
+}
+
+#[component]
+pub fn FillButton(fill_action: Action<FillSetlist, Result<(), ServerFnError<AppError>>>, tags: Vec<Tag>) -> impl IntoView {
+    view! {
+      <ActionForm action=fill_action class="flex">
         <input type="number" hidden=true name="max_n" value=4/>
+        <TagSelect
+          tags
+          selected=None
+          name="tag"
+          class="rounded-l-lg mr-1 px-1 text-sm bg-ctp-surface1 text-ctp-text"
+        />
 
         <button
           type="submit"
-          class="border-0 border-md rounded-l-lg mr-1 px-2 py-1 shadow-md bg-ctp-teal text-ctp-mantle"
+          class="border-0 border-md mr-1 px-2 py-1 shadow-md bg-ctp-teal text-ctp-mantle"
         >
           <i class="fa-solid fa-rotate-right"></i>
-          Vullen
+          {use_locale().t("setlist-fill")}
         </button>
       </ActionForm>
     }
 }
 
 #[component]
-pub fn CleanButton(clean_action: Action<CleanSetlist, Result<()>>) -> impl IntoView {
+pub fn CleanButton(clean_action: Action<CleanSetlist, Result<(), ServerFnError<AppError>>>) -> impl IntoView {
     view! {
       <ActionForm action=clean_action>
         <button
@@ -199,7 +353,7 @@ pub fn CleanButton(clean_action: Action<CleanSetlist, Result<()>>) -> impl IntoV
           class="border-0 border-md rounded-r-lg px-2 py-1 shadow-md bg-ctp-teal text-ctp-mantle"
         >
           <i class="fa-solid fa-trash"></i>
-          Legen
+          {use_locale().t("setlist-clear")}
         </button>
       </ActionForm>
     }
@@ -209,12 +363,14 @@ pub fn CleanButton(clean_action: Action<CleanSetlist, Result<()>>) -> impl IntoV
 pub fn SongView(
     song: Song,
     all_songs: Vec<Song>,
-    pick_song: Action<HandPickSong, Result<()>>,
+    pick_song: Action<HandPickSong, Result<(), ServerFnError<AppError>>>,
     in_rehearsal: bool,
-    set_song_played: Action<SetSongPlayed, Result<()>>,
+    open_rehearsal: Signal<Option<RehearsalSession>>,
+    tick_song: Action<TickRehearsalSong, Result<(), ServerFnError<AppError>>>,
     get_selected_song: ReadSignal<Option<i32>>,
     set_selected_song: WriteSignal<Option<i32>>,
 ) -> impl IntoView {
+    let locale = use_locale();
     view! {
       <div class="bg-ctp-crust py-2 rounded-lg border-0 shadow-md">
         <div class="ml-2 flex">
@@ -247,26 +403,56 @@ pub fn SongView(
                   >
 
                     <i class="fa-solid fa-arrow-up"></i>
-                    Oefen
+                    {locale.t("song-practise")}
                   </button>
                 </ActionForm>
               </Show>
             </div>
             <div class="flex justify-end mr-2 items-center">
-              <button
-                type="button"
-                class="border-0 rounded-md ml-2 px-3 py-2 shadow-md bg-ctp-flamingo text-ctp-mantle text-xs"
-                on:click=move |_| { set_song_played.dispatch(SetSongPlayed { song_id: song.id }) }
-              >
-
-                <i class="fa-solid fa-music"></i>
-                " "
-                {match song.last_played_at {
-                    Some(d) => d.format("%d-%m-%Y").to_string(),
-                    None => "Nooit".to_string(),
-                }}
-
-              </button>
+              {move || match open_rehearsal.get() {
+                  Some(rehearsal) => {
+                      let played = rehearsal.is_played(song.id);
+                      view! {
+                        <button
+                          type="button"
+                          class="border-0 rounded-md ml-2 px-3 py-2 shadow-md text-ctp-mantle text-xs"
+                          class=("bg-ctp-green", played)
+                          class=("bg-ctp-flamingo", !played)
+                          on:click=move |_| {
+                              tick_song
+                                  .dispatch(TickRehearsalSong {
+                                      rehearsal_id: rehearsal.id,
+                                      song_id: song.id,
+                                  })
+                          }
+                        >
+
+                          <i class=match played {
+                              true => "fa-solid fa-square-check",
+                              false => "fa-regular fa-square",
+                          }></i>
+                          " "
+                          {locale.t("song-played")}
+                        </button>
+                      }
+                          .into_view()
+                  }
+                  None => {
+                      view! {
+                        <div class="ml-2 px-3 py-2 text-xs opacity-70">
+                          <i class="fa-solid fa-music"></i>
+                          " "
+                          {match song.last_played_at {
+                              Some(d) => locale.format_date(d),
+                              None => locale.t("song-never-played"),
+                          }}
+
+                        </div>
+                      }
+                          .into_view()
+                  }
+              }}
+
             </div>
           </div>
         </Show>
@@ -0,0 +1,63 @@
+use leptos::*;
+use leptos_router::use_location;
+
+#[cfg(feature = "ssr")]
+use crate::components::shared::set_cookie;
+use crate::error::AppError;
+#[cfg(feature = "ssr")]
+use crate::i18n::LANG_COOKIE;
+#[cfg(feature = "ssr")]
+use crate::models::band::current_user;
+use crate::i18n::{use_locale, Locale};
+
+/// Remembers the language for the signed in user, or else in this browser,
+/// and reloads `path` in it.
+#[server(SetLanguage)]
+pub async fn set_language(locale: Locale, path: String) -> Result<(), ServerFnError<AppError>> {
+    match current_user() {
+        Some(user) => locale.save_for_user(&user).await?,
+        None => set_cookie(LANG_COOKIE, locale.code()),
+    }
+    // Only ever send the browser back to a page of this site.
+    match path.starts_with('/') && !path.starts_with("//") {
+        true => leptos_axum::redirect(&path),
+        false => leptos_axum::redirect("/"),
+    }
+    Ok(())
+}
+
+/// Picks the language of the UI, shown in the nav.
+#[component]
+pub fn LanguagePicker() -> impl IntoView {
+    let set_language = create_server_action::<SetLanguage>();
+    let current = use_locale();
+    let location = use_location();
+
+    view! {
+      <select
+        class="rounded-md border-0 py-2 pl-2 text-ctp-mantle text-sm"
+        title=current.t("language")
+        on:change=move |ev| {
+            if let Ok(locale) = event_target_value(&ev).parse() {
+                set_language
+                    .dispatch(SetLanguage {
+                        locale,
+                        path: location.pathname.get_untracked(),
+                    });
+            }
+        }
+      >
+
+        {Locale::ALL
+            .into_iter()
+            .map(|locale| {
+                view! {
+                  <option value=locale.code() selected=locale == current>
+                    {locale.label()}
+                  </option>
+                }
+            })
+            .collect_view()}
+      </select>
+    }
+}
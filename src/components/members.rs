@@ -0,0 +1,402 @@
+use chrono::NaiveDate;
+use leptos::*;
+use leptos_router::ActionForm;
+use serde::{Deserialize, Serialize};
+
+use crate::error::AppError;
+use crate::i18n::use_locale;
+use crate::models::member::{Attendance, Member, Rsvp, Unavailability};
+#[cfg(feature = "ssr")]
+use crate::repository::use_repositories;
+
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq, Eq, Default)]
+pub struct MembersOverview {
+    pub members: Vec<Member>,
+    pub unavailability: Vec<Unavailability>,
+}
+
+#[server(GetMembers, "/api", "GetJson")]
+pub async fn get_members() -> Result<MembersOverview, ServerFnError<AppError>> {
+    let band_id = use_repositories()?.band_id;
+    Ok(MembersOverview {
+        members: Member::get_all(band_id).await?,
+        unavailability: Member::get_unavailability(band_id, chrono::Local::now().date_naive()).await?,
+    })
+}
+
+#[server(CreateMember)]
+pub async fn create_member(name: String, instrument: String) -> Result<(), ServerFnError<AppError>> {
+    let band_id = use_repositories()?.band_id;
+    let name = name.trim().to_string();
+    if name.is_empty() {
+        return Err(AppError::validation("Vul een naam in").into());
+    }
+    Member::create(band_id, name, instrument.trim().to_string()).await?;
+    Ok(())
+}
+
+#[server(SetMemberRequired)]
+pub async fn set_member_required(member_id: i32, required: bool) -> Result<(), ServerFnError<AppError>> {
+    let band_id = use_repositories()?.band_id;
+    Member::set_required(band_id, member_id, required)
+        .await
+        .map_err(ServerFnError::from)
+}
+
+#[server(RemoveMember)]
+pub async fn remove_member(member_id: i32) -> Result<(), ServerFnError<AppError>> {
+    let band_id = use_repositories()?.band_id;
+    Member::remove(band_id, member_id).await.map_err(ServerFnError::from)
+}
+
+#[server(SetMemberUnavailable)]
+pub async fn set_member_unavailable(
+    member_id: i32,
+    date: String,
+    note: String,
+) -> Result<(), ServerFnError<AppError>> {
+    let band_id = use_repositories()?.band_id;
+    let date = NaiveDate::parse_from_str(&date, "%Y-%m-%d")
+        .map_err(|_| AppError::validation("Kies een datum".to_string()))?;
+    Member::set_unavailable(band_id, member_id, date, note.trim().to_string())
+        .await
+        .map_err(ServerFnError::from)
+}
+
+#[server(SetMemberAvailable)]
+pub async fn set_member_available(member_id: i32, date: NaiveDate) -> Result<(), ServerFnError<AppError>> {
+    let band_id = use_repositories()?.band_id;
+    Member::set_available(band_id, member_id, date)
+        .await
+        .map_err(ServerFnError::from)
+}
+
+#[server(GetGigsAttendance, "/api", "GetJson")]
+pub async fn get_gigs_attendance() -> Result<Vec<(i32, Attendance)>, ServerFnError<AppError>> {
+    let band_id = use_repositories()?.band_id;
+    Member::get_gig_attendance(band_id)
+        .await
+        .map_err(ServerFnError::from)
+}
+
+#[server(GetGigAttendance, "/api", "GetJson")]
+pub async fn get_gig_attendance(gig_id: i32) -> Result<Vec<Attendance>, ServerFnError<AppError>> {
+    let band_id = use_repositories()?.band_id;
+    Member::get_attendance_for_gig(band_id, gig_id)
+        .await
+        .map_err(ServerFnError::from)
+}
+
+#[server(GetRehearsalAttendance, "/api", "GetJson")]
+pub async fn get_rehearsal_attendance(rehearsal_id: i32) -> Result<Vec<Attendance>, ServerFnError<AppError>> {
+    let band_id = use_repositories()?.band_id;
+    Member::get_attendance_for_rehearsal(band_id, rehearsal_id)
+        .await
+        .map_err(ServerFnError::from)
+}
+
+#[server(SetGigRsvp)]
+pub async fn set_gig_rsvp(gig_id: i32, member_id: i32, rsvp: Rsvp) -> Result<(), ServerFnError<AppError>> {
+    let band_id = use_repositories()?.band_id;
+    Member::set_gig_rsvp(band_id, gig_id, member_id, rsvp)
+        .await
+        .map_err(ServerFnError::from)
+}
+
+#[server(SetRehearsalRsvp)]
+pub async fn set_rehearsal_rsvp(
+    rehearsal_id: i32,
+    member_id: i32,
+    rsvp: Rsvp,
+) -> Result<(), ServerFnError<AppError>> {
+    let band_id = use_repositories()?.band_id;
+    Member::set_rehearsal_rsvp(band_id, rehearsal_id, member_id, rsvp)
+        .await
+        .map_err(ServerFnError::from)
+}
+
+#[component]
+pub fn Members() -> impl IntoView {
+    let locale = use_locale();
+    let create_member = create_server_action::<CreateMember>();
+    let set_required = create_server_action::<SetMemberRequired>();
+    let remove_member = create_server_action::<RemoveMember>();
+    let set_unavailable = create_server_action::<SetMemberUnavailable>();
+    let set_available = create_server_action::<SetMemberAvailable>();
+    let overview = create_resource(
+        move || {
+            (
+                create_member.version().get(),
+                set_required.version().get(),
+                remove_member.version().get(),
+                set_unavailable.version().get(),
+                set_available.version().get(),
+            )
+        },
+        |_| get_members(),
+    );
+    let input_class = "input block w-full rounded-md border-0 py-2 pl-2 text-ctp-mantle text-sm";
+
+    view! {
+      <div class="m-4">
+        <ActionForm action=create_member class="flex gap-2 mb-4">
+          <input type="text" name="name" class=input_class placeholder=locale.t("member-name")/>
+          <input type="text" name="instrument" class=input_class placeholder=locale.t("member-instrument")/>
+          <button
+            type="submit"
+            class="border-0 rounded-full px-3 py-2 shadow-md bg-ctp-teal text-ctp-mantle"
+          >
+            <i class="fa-solid fa-plus"></i>
+          </button>
+        </ActionForm>
+        <Transition>
+          {move || {
+              let overview = overview.get().and_then(Result::ok).unwrap_or_default();
+              overview
+                  .members
+                  .into_iter()
+                  .map(|member| {
+                      let dates: Vec<Unavailability> = overview
+                          .unavailability
+                          .iter()
+                          .filter(|u| u.member_id == member.id)
+                          .cloned()
+                          .collect();
+                      view! {
+                        <div class="bg-ctp-crust rounded-lg shadow-md p-2 mb-2">
+                          <div class="flex justify-between items-center">
+                            <div class="flex-1">
+                              <div class="font-bold text-sm">{member.name}</div>
+                              <div class="text-xs opacity-70">{member.instrument}</div>
+                            </div>
+                            <label class="text-xs mr-2">
+                              <input
+                                type="checkbox"
+                                class="mr-1"
+                                prop:checked=member.required
+                                on:change=move |ev| {
+                                    set_required
+                                        .dispatch(SetMemberRequired {
+                                            member_id: member.id,
+                                            required: event_target_checked(&ev),
+                                        })
+                                }
+                              />
+                              {locale.t("member-required")}
+                            </label>
+                            <button
+                              type="button"
+                              class="border-0 rounded-full px-3 py-2 shadow-md bg-ctp-maroon text-ctp-mantle text-xs"
+                              on:click=move |_| {
+                                  remove_member.dispatch(RemoveMember { member_id: member.id })
+                              }
+                            >
+
+                              <i class="fa-solid fa-trash"></i>
+                            </button>
+                          </div>
+                          {dates
+                              .into_iter()
+                              .map(|u| {
+                                  view! {
+                                    <div class="flex justify-between items-center text-xs mt-1">
+                                      <div>
+                                        <i class="fa-solid fa-calendar-xmark"></i>
+                                        " "
+                                        {locale.format_date(u.date)}
+                                        " "
+                                        {u.note}
+                                      </div>
+                                      <button
+                                        type="button"
+                                        class="underline"
+                                        on:click=move |_| {
+                                            set_available
+                                                .dispatch(SetMemberAvailable {
+                                                    member_id: u.member_id,
+                                                    date: u.date,
+                                                })
+                                        }
+                                      >
+
+                                        {locale.t("member-available-after-all")}
+                                      </button>
+                                    </div>
+                                  }
+                              })
+                              .collect_view()}
+                          <ActionForm action=set_unavailable class="flex gap-2 mt-2">
+                            <input type="hidden" name="member_id" value=member.id/>
+                            <input type="date" name="date" class=input_class required/>
+                            <input type="text" name="note" class=input_class placeholder=locale.t("member-reason")/>
+                            <button
+                              type="submit"
+                              class="border-0 rounded-md px-2 shadow-md bg-ctp-peach text-ctp-mantle text-xs whitespace-nowrap"
+                            >
+                              {locale.t("member-unavailable")}
+                            </button>
+                          </ActionForm>
+                        </div>
+                      }
+                  })
+                  .collect_view()
+          }}
+
+        </Transition>
+      </div>
+    }
+}
+
+/// Who is in or out for a gig or rehearsal, with buttons for each member to
+/// answer and warnings when a required member can't make it.
+#[component]
+pub fn AttendancePanel(
+    attendance: Vec<Attendance>,
+    on_rsvp: Callback<(i32, Rsvp)>,
+) -> impl IntoView {
+    let locale = use_locale();
+    let warnings = Attendance::warnings(&attendance, locale);
+
+    view! {
+      <div class="mx-2 mt-2">
+        <AttendanceWarnings warnings/>
+        {attendance
+            .into_iter()
+            .map(|a| {
+                let member_id = a.member.id;
+                view! {
+                  <div class="flex justify-between items-center text-sm py-1">
+                    <div>
+                      {a.member.name} <span class="text-xs opacity-70">" " {a.member.instrument}</span>
+                      <Show when=move || a.unavailable.is_some()>
+                        <i class="fa-solid fa-calendar-xmark ml-1" title=locale.t("member-not-available")></i>
+                      </Show>
+                    </div>
+                    <div class="flex gap-1">
+                      {Rsvp::ALL
+                          .into_iter()
+                          .map(|rsvp| {
+                              view! {
+                                <button
+                                  type="button"
+                                  class="border-0 rounded-md px-2 py-1 shadow-md text-xs"
+                                  class=("bg-ctp-green", a.rsvp == Some(rsvp) && rsvp == Rsvp::Yes)
+                                  class=("bg-ctp-yellow", a.rsvp == Some(rsvp) && rsvp == Rsvp::Maybe)
+                                  class=("bg-ctp-red", a.rsvp == Some(rsvp) && rsvp == Rsvp::No)
+                                  class=("text-ctp-mantle", a.rsvp == Some(rsvp))
+                                  class=("bg-ctp-surface1", a.rsvp != Some(rsvp))
+                                  on:click=move |_| on_rsvp.call((member_id, rsvp))
+                                >
+                                  {locale.t(&format!("rsvp-{}", rsvp.as_str()))}
+                                </button>
+                              }
+                          })
+                          .collect_view()}
+                    </div>
+                  </div>
+                }
+            })
+            .collect_view()}
+      </div>
+    }
+}
+
+/// The warnings of [`Attendance::warnings`], e.g. after picking a date.
+#[component]
+pub fn AttendanceWarnings(warnings: Vec<String>) -> impl IntoView {
+    warnings
+        .into_iter()
+        .map(|warning| {
+            view! {
+              <div class="rounded-md px-2 py-1 mb-1 text-sm bg-ctp-peach text-ctp-mantle">
+                <i class="fa-solid fa-triangle-exclamation"></i>
+                " "
+                {warning}
+              </div>
+            }
+        })
+        .collect_view()
+}
+
+#[component]
+pub fn GigAttendance(gig_id: i32) -> impl IntoView {
+    let set_rsvp = create_server_action::<SetGigRsvp>();
+    let attendance = create_resource(
+        move || (set_rsvp.version().get(), gig_id),
+        |(_, gig_id)| get_gig_attendance(gig_id),
+    );
+    let on_rsvp = Callback::new(move |(member_id, rsvp)| {
+        set_rsvp.dispatch(SetGigRsvp {
+            gig_id,
+            member_id,
+            rsvp,
+        })
+    });
+
+    view! {
+      <Transition>
+        {move || {
+            let attendance = attendance.get().and_then(Result::ok).unwrap_or_default();
+            view! { <AttendancePanel attendance on_rsvp/> }
+        }}
+
+      </Transition>
+    }
+}
+
+#[component]
+pub fn RehearsalAttendance(rehearsal_id: i32) -> impl IntoView {
+    let set_rsvp = create_server_action::<SetRehearsalRsvp>();
+    let attendance = create_resource(
+        move || (set_rsvp.version().get(), rehearsal_id),
+        |(_, rehearsal_id)| get_rehearsal_attendance(rehearsal_id),
+    );
+    let on_rsvp = Callback::new(move |(member_id, rsvp)| {
+        set_rsvp.dispatch(SetRehearsalRsvp {
+            rehearsal_id,
+            member_id,
+            rsvp,
+        })
+    });
+
+    view! {
+      <Transition>
+        {move || {
+            let attendance = attendance.get().and_then(Result::ok).unwrap_or_default();
+            view! { <AttendancePanel attendance on_rsvp/> }
+        }}
+
+      </Transition>
+    }
+}
+
+/// Compact in/out overview of the members for the gig timeline.
+#[component]
+pub fn AttendanceChips(attendance: Vec<Attendance>) -> impl IntoView {
+    let locale = use_locale();
+    let has_warnings = !Attendance::warnings(&attendance, locale).is_empty();
+
+    view! {
+      <div class="flex flex-wrap gap-1 text-xs">
+        <Show when=move || has_warnings>
+          <i class="fa-solid fa-triangle-exclamation text-ctp-peach" title=locale.t("member-required-out")></i>
+        </Show>
+        {attendance
+            .into_iter()
+            .map(|a| {
+                view! {
+                  <span
+                    class="rounded-full px-2"
+                    class=("bg-ctp-green", a.is_in())
+                    class=("bg-ctp-red", a.is_out())
+                    class=("bg-ctp-surface1", !a.is_in() && !a.is_out())
+                    class=("text-ctp-mantle", a.is_in() || a.is_out())
+                  >
+                    {a.member.name}
+                  </span>
+                }
+            })
+            .collect_view()}
+      </div>
+    }
+}
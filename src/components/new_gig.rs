@@ -0,0 +1,330 @@
+#[cfg(feature = "ssr")]
+use chrono::NaiveDate;
+use leptos::*;
+use leptos_router::*;
+use serde::{Deserialize, Serialize};
+
+use crate::components::gigs::get_gigs;
+use crate::components::members::AttendanceWarnings;
+use crate::components::venues::get_venues;
+use crate::error::AppError;
+use crate::i18n::use_locale;
+#[cfg(feature = "ssr")]
+use crate::models::gig::Gig;
+use crate::models::gig::GigSource;
+use crate::models::gig_template::GigTemplate;
+#[cfg(feature = "ssr")]
+use crate::repository::use_repositories;
+
+#[derive(Serialize, Deserialize, Debug, PartialEq, Clone, Default)]
+pub struct NewGigData {
+    pub venue: String,
+    pub date: String,
+    pub time: String,
+    /// Value of the source `<select>`, see [`GigSource::from_value`].
+    pub source: String,
+}
+
+/// Warnings for the required members who marked `date` as unavailable, so
+/// they show up while picking a date for a gig, not only on the gig page.
+#[server(GetDateWarnings, "/api", "GetJson")]
+pub async fn get_date_warnings(date: String) -> Result<Vec<String>, ServerFnError<AppError>> {
+    use crate::i18n::Locale;
+    use crate::models::member::{Attendance, Member};
+
+    match NaiveDate::parse_from_str(&date, "%Y-%m-%d") {
+        Ok(date) => Ok(Attendance::warnings(
+            &Member::get_unavailable_on(use_repositories()?.band_id, date).await?,
+            Locale::detect(),
+        )),
+        Err(_) => Ok(vec![]),
+    }
+}
+
+/// Creates the gig and opens it. Conflicts with the members' availability are
+/// shown on the form while picking the date, and on the gig page afterwards.
+#[server(CreateGig)]
+pub async fn create_gig(gig: NewGigData) -> Result<(), ServerFnError<AppError>> {
+    let venue = gig.venue.trim().to_string();
+    if venue.is_empty() {
+        return Err(AppError::validation("Vul een locatie in").into());
+    }
+    let date = NaiveDate::parse_from_str(&gig.date, "%Y-%m-%d")
+        .map_err(|_| AppError::validation("Ongeldige datum".to_string()))?;
+    let time = Some(gig.time.trim().to_string()).filter(|t| !t.is_empty());
+
+    let id = Gig::create(&use_repositories()?, venue, date, time, GigSource::from_value(&gig.source)).await?;
+    leptos_axum::redirect(&format!("/gig/{}", id));
+    Ok(())
+}
+
+#[server(GetGigTemplates, "/api", "GetJson")]
+pub async fn get_gig_templates() -> Result<Vec<GigTemplate>, ServerFnError<AppError>> {
+    GigTemplate::get_all(use_repositories()?.band_id)
+        .await
+        .map_err(ServerFnError::from)
+}
+
+#[server(SaveGigTemplate)]
+pub async fn save_gig_template(gig_id: i32, name: String) -> Result<(), ServerFnError<AppError>> {
+    let name = name.trim().to_string();
+    if name.is_empty() {
+        return Err(AppError::validation("Geef de template een naam").into());
+    }
+    GigTemplate::create_from_gig(use_repositories()?.band_id, gig_id, name)
+        .await
+        .map_err(ServerFnError::from)
+}
+
+#[server(RemoveGigTemplate)]
+pub async fn remove_gig_template(template_id: i32) -> Result<(), ServerFnError<AppError>> {
+    GigTemplate::remove(use_repositories()?.band_id, template_id)
+        .await
+        .map_err(ServerFnError::from)
+}
+
+#[derive(Params, PartialEq, Clone)]
+struct NewGigParams {
+    from: Option<String>,
+}
+
+/// Form to create a gig with venue, date and time, starting from an empty
+/// running order, a template or a previous gig. Without a `?from=` query the
+/// most recent gig is preselected, as most gigs are a variation on it.
+#[component]
+pub fn NewGig() -> impl IntoView {
+    let locale = use_locale();
+    let create_gig = create_server_action::<CreateGig>();
+    let remove_template = create_server_action::<RemoveGigTemplate>();
+    // Removing takes a second click on the same button.
+    let (confirm_remove, set_confirm_remove) = create_signal(None::<i32>);
+    let query = use_query::<NewGigParams>();
+    let from = move || {
+        query.with(|q| q.as_ref().ok().and_then(|q| q.from.clone()))
+    };
+
+    let gigs = create_resource(|| (), |_| get_gigs());
+    let venues = create_resource(|| (), |_| get_venues());
+    let templates = create_resource(move || remove_template.version().get(), |_| get_gig_templates());
+    let (date, set_date) = create_signal(String::new());
+    let date_warnings = create_resource(move || date.get(), get_date_warnings);
+
+    let input_class = "input block w-full rounded-md border-0 py-2 pl-2 text-ctp-mantle ring-1 ring-inset ring-ctp-surface0 focus:ring-2 focus:ring-inset focus:ring-ctp-flamingo text-sm";
+
+    view! {
+      <div class="m-4">
+        <div class="font-bold text-xl mb-4">{locale.t("gigs-new")}</div>
+        <ActionForm action=create_gig class="grid gap-3">
+          <label class="text-sm">
+            {locale.t("gig-venue")}
+            <input type="text" name="gig[venue]" class=input_class list="venues" required/>
+            <datalist id="venues">
+              <Transition>
+                {move || {
+                    venues
+                        .get()
+                        .and_then(Result::ok)
+                        .unwrap_or_default()
+                        .into_iter()
+                        .map(|venue| view! { <option value=venue.name></option> })
+                        .collect_view()
+                }}
+
+              </Transition>
+            </datalist>
+          </label>
+          <div class="grid grid-cols-2 gap-3">
+            <label class="text-sm">
+              {locale.t("gig-date")}
+              <input
+                type="date"
+                name="gig[date]"
+                class=input_class
+                required
+                on:input=move |ev| set_date.set(event_target_value(&ev))
+              />
+            </label>
+            <label class="text-sm">
+              {locale.t("gig-time")}
+              <input type="text" name="gig[time]" class=input_class placeholder="20:00"/>
+            </label>
+          </div>
+          <Transition>
+            {move || {
+                let warnings = date_warnings.get().and_then(Result::ok).unwrap_or_default();
+                view! { <AttendanceWarnings warnings/> }
+            }}
+
+          </Transition>
+          <label class="text-sm">
+            {locale.t("songs-setlist")}
+            <Transition>
+              {move || {
+                  let mut gigs = gigs.get().and_then(Result::ok).unwrap_or_default();
+                  gigs.reverse();
+                  let templates = templates.get().and_then(Result::ok).unwrap_or_default();
+                  let selected = from()
+                      .map(|f| GigSource::from_value(&f))
+                      .or_else(|| gigs.first().map(|g| GigSource::Gig(g.id)))
+                      .unwrap_or_default();
+                  view! {
+                    <select name="gig[source]" class=input_class>
+                      <option value="" selected=selected == GigSource::Empty>
+                        {locale.t("new-gig-empty-setlist")}
+                      </option>
+                      <optgroup label=locale.t("new-gig-templates")>
+                        {templates
+                            .into_iter()
+                            .map(|t| {
+                                let source = GigSource::Template(t.id);
+                                view! {
+                                  <option value=source.to_value() selected=selected == source>
+                                    {t.name}
+                                  </option>
+                                }
+                            })
+                            .collect_view()}
+                      </optgroup>
+                      <optgroup label=locale.t("new-gig-copy-of")>
+                        {gigs
+                            .into_iter()
+                            .map(|g| {
+                                let source = GigSource::Gig(g.id);
+                                view! {
+                                  <option value=source.to_value() selected=selected == source>
+                                    {format!("{} ({})", g.venue, locale.format_date(g.date))}
+                                  </option>
+                                }
+                            })
+                            .collect_view()}
+                      </optgroup>
+                    </select>
+                  }
+              }}
+
+            </Transition>
+          </label>
+          <div>
+            <button
+              type="submit"
+              class="border-0 rounded-md px-3 py-2 shadow-md bg-ctp-teal text-ctp-mantle"
+            >
+              <i class="fa-solid fa-plus"></i>
+              " "
+              {locale.t("new-gig-create")}
+            </button>
+          </div>
+          {move || {
+              create_gig
+                  .value()
+                  .get()
+                  .and_then(Result::err)
+                  .map(|e| view! { <div class="text-sm text-ctp-red">{e.to_string()}</div> })
+          }}
+
+        </ActionForm>
+
+        <div class="font-bold text-lg mt-8 mb-2">{locale.t("new-gig-templates")}</div>
+        <Transition>
+          {move || {
+              templates
+                  .get()
+                  .and_then(Result::ok)
+                  .unwrap_or_default()
+                  .into_iter()
+                  .map(|t| {
+                      let confirming = move || confirm_remove.get() == Some(t.id);
+                      view! {
+                        <div class="flex justify-between items-center py-1">
+                          <div class="text-sm">
+                            {t.name} <span class="opacity-60">
+                              {format!(
+                                  " ({})",
+                                  locale
+                                      .t_with(
+                                          "song-count",
+                                          &[("count", t.songs.iter().filter(|id| **id > 0).count().into())],
+                                      ),
+                              )}
+                            </span>
+                          </div>
+                          <button
+                            type="button"
+                            class="border-0 rounded-full px-2 py-1 shadow-md text-ctp-mantle text-xs"
+                            class=("bg-ctp-maroon", move || !confirming())
+                            class=("bg-ctp-red", confirming)
+                            on:click=move |_| {
+                                if confirming() {
+                                    set_confirm_remove.set(None);
+                                    remove_template.dispatch(RemoveGigTemplate { template_id: t.id });
+                                } else {
+                                    set_confirm_remove.set(Some(t.id));
+                                }
+                            }
+                          >
+
+                            <i class="fa-solid fa-trash"></i>
+                            {move || confirming().then(|| format!(" {}", locale.t("confirm-sure")))}
+                          </button>
+                        </div>
+                      }
+                  })
+                  .collect_view()
+          }}
+
+        </Transition>
+      </div>
+    }
+}
+
+/// Buttons on the gig page to start a new gig from this one, either directly
+/// or by saving its running order as a named template.
+#[component]
+pub fn GigReuseButtons(gig_id: i32) -> impl IntoView {
+    let locale = use_locale();
+    let save_template = create_server_action::<SaveGigTemplate>();
+    let (name, set_name) = create_signal(String::new());
+
+    view! {
+      <div class="flex flex-wrap gap-2 items-center mx-2 mt-2">
+        <a
+          href=format!("/gigs/new?from={}", GigSource::Gig(gig_id).to_value())
+          class="border-0 rounded-full px-3 py-2 shadow-md bg-ctp-lavender text-ctp-mantle text-sm"
+        >
+          <i class="fa-solid fa-copy"></i>
+          " "
+          {locale.t("new-gig-duplicate")}
+        </a>
+        <input
+          type="text"
+          class="input rounded-md border-0 py-2 pl-2 text-ctp-mantle text-sm"
+          placeholder=locale.t("new-gig-template-name")
+          prop:value=name
+          on:input=move |ev| set_name.set(event_target_value(&ev))
+        />
+        <button
+          type="button"
+          class="border-0 rounded-full px-3 py-2 shadow-md bg-ctp-lavender text-ctp-mantle text-sm"
+          on:click=move |_| {
+              save_template
+                  .dispatch(SaveGigTemplate {
+                      gig_id,
+                      name: name.get_untracked(),
+                  });
+              set_name.set(String::new());
+          }
+        >
+
+          <i class="fa-solid fa-floppy-disk"></i>
+          " "
+          {locale.t("new-gig-save-template")}
+        </button>
+        {move || match save_template.value().get() {
+            Some(Ok(())) => view! { <span class="text-xs">{locale.t("new-gig-template-saved")}</span> }.into_view(),
+            Some(Err(e)) => view! { <span class="text-xs text-ctp-red">{e.to_string()}</span> }.into_view(),
+            None => view! {}.into_view(),
+        }}
+
+      </div>
+    }
+}
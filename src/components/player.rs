@@ -11,14 +11,17 @@ pub struct PlayerData {
 }
 
 impl PlayerData {
-    pub fn next_song(&self) -> Song {
+    /// The next song with audio after the current one, wrapping around. Songs
+    /// without an audio file are skipped so playback doesn't stall on them.
+    pub fn next_song(&self) -> Option<Song> {
         self.all_songs
             .iter()
             .cycle()
             .skip_while(|&s| s.id != self.song.id)
-            .nth(1)
-            .unwrap()
-            .clone()
+            .skip(1)
+            .take(self.all_songs.len())
+            .find(|s| s.gs_url.is_some())
+            .cloned()
     }
 }
 
@@ -57,7 +60,7 @@ fn SelectedSongView(song: Song) -> impl IntoView {
     view! {
       <div class="flex grow">
         <figure class="flex w-20 h-20 mr-4">
-          <AlbumArt mid=song.release_mid.unwrap() width=80 height=80/>
+          <AlbumArt release_mid=song.release_mid title=song.title.clone() width=80 height=80/>
         </figure>
         <div class="flex-1 flex-cols">
           <div class="text-lg font-bold">{song.title}</div>
@@ -71,7 +74,10 @@ fn SelectedSongView(song: Song) -> impl IntoView {
 fn AudioPlayer(player_data: PlayerData) -> impl IntoView {
     let set_player_data =
         use_context::<WriteSignal<Option<PlayerData>>>().expect("set_song_id context expected");
-    let url = player_data.song.gs_url.clone();
+    let Some(url) = player_data.song.gs_url.clone() else {
+        return view! { <div class="flex mt-1 text-sm opacity-70">"Geen audio beschikbaar"</div> }
+            .into_view();
+    };
 
     view! {
       <div class="flex mt-1">
@@ -81,11 +87,13 @@ fn AudioPlayer(player_data: PlayerData) -> impl IntoView {
           autoplay
           preload="metadata"
           on:ended=move |_| {
-              let new_data = Some(PlayerData {
-                  song: player_data.next_song(),
-                  all_songs: player_data.all_songs.clone(),
-              });
-              set_player_data.update(|data| { *data = new_data })
+              if let Some(song) = player_data.next_song() {
+                  let new_data = Some(PlayerData {
+                      song,
+                      all_songs: player_data.all_songs.clone(),
+                  });
+                  set_player_data.update(|data| { *data = new_data })
+              }
           }
         >
 
@@ -93,4 +101,5 @@ fn AudioPlayer(player_data: PlayerData) -> impl IntoView {
         </audio>
       </div>
     }
+    .into_view()
 }
@@ -1,19 +1,22 @@
 use leptos::*;
 
+use crate::i18n::use_locale;
+
 #[component]
 pub fn Promo() -> impl IntoView {
+    let locale = use_locale();
     view! {
       <div class="grid grid-flow-row auto-rows-max gap-2 mt-4">
-        <PromoImage source="/promo/logo_pop.png" title="Pop"/>
-        <PromoImage source="/promo/logo_round.png" title="Rond"/>
-        <PromoImage source="/promo/logo_square.png" title="Vierkant"/>
-        <PromoImage source="/promo/logo_transparent.png" title="Transparant"/>
+        <PromoImage source="/promo/logo_pop.png" title=locale.t("promo-pop")/>
+        <PromoImage source="/promo/logo_round.png" title=locale.t("promo-round")/>
+        <PromoImage source="/promo/logo_square.png" title=locale.t("promo-square")/>
+        <PromoImage source="/promo/logo_transparent.png" title=locale.t("promo-transparent")/>
       </div>
     }
 }
 
 #[component]
-pub fn PromoImage(source: &'static str, title: &'static str) -> impl IntoView {
+pub fn PromoImage(source: &'static str, title: String) -> impl IntoView {
     view! {
       <div class="grid grid-cols-1 justify-items-center mt-2">
         <img src=source class="size-60"/>
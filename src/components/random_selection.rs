@@ -1,27 +1,40 @@
 use leptos::*;
 
+use crate::error::AppError;
+use crate::i18n::use_locale;
 use crate::models::song::Song;
+#[cfg(feature = "ssr")]
+use crate::repository::use_repositories;
 
 #[server(GetRandomSong, "/api", "GetJson")]
-pub async fn get_random_song() -> Result<Song, ServerFnError> {
+pub async fn get_random_song() -> Result<Song, ServerFnError<AppError>> {
+    let mut songs = use_repositories()?.songs.get_all().await?;
+    let index = weighted_random_index(&mut songs)?;
+    Ok(songs[index].clone())
+}
+
+/// Picks a random song, favouring the ones that haven't been played for the
+/// longest time. Sorts `songs` by last played date as a side effect.
+#[cfg(feature = "ssr")]
+pub fn weighted_random_index(songs: &mut [Song]) -> Result<usize, AppError> {
     use rand::distributions::{Distribution, WeightedIndex};
-    let mut songs = Song::get_all().await?;
 
-    songs.sort_by(|a, b| a.last_played_at.cmp(&b.last_played_at));
+    songs.sort_by_key(|s| s.last_played_at);
     let weights: Vec<_> = songs
         .iter()
         .enumerate()
         .map(|(i, _)| (songs.len() - i) as u32)
         .collect();
-    let dist = WeightedIndex::new(weights)?;
+    let dist = WeightedIndex::new(weights)
+        .map_err(|_| AppError::validation("geen nummers om uit te kiezen"))?;
     let mut rng = rand::thread_rng();
-    Ok(songs[dist.sample(&mut rng)].clone())
+    Ok(dist.sample(&mut rng))
 }
 
 #[server(GetSong, "/api", "GetJson")]
-pub async fn get_song(song_id: Option<i32>) -> Result<Song, ServerFnError> {
+pub async fn get_song(song_id: Option<i32>) -> Result<Song, ServerFnError<AppError>> {
     match song_id {
-        Some(id) => match Song::get(id).await {
+        Some(id) => match use_repositories()?.songs.get(id).await {
             Ok(s) => Ok(s),
             Err(e) => Err(ServerFnError::from(e)),
         },
@@ -48,7 +61,7 @@ pub fn RandomSongView() -> impl IntoView {
           on:click=move |_| { song_action.dispatch(()) }
         >
           <span class="fa-solid fa-shuffle"></span>
-          Willekeurig
+          {use_locale().t("song-random")}
         </button>
       </div>
     }
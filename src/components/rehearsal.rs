@@ -0,0 +1,311 @@
+use leptos::*;
+use leptos_router::*;
+
+use crate::components::members::RehearsalAttendance;
+use crate::error::AppError;
+use crate::error_template::ErrorTemplate;
+use crate::i18n::use_locale;
+use crate::models::rehearsal::{RehearsalModel, RehearsalSession, RehearsalSong};
+#[cfg(feature = "ssr")]
+use crate::repository::use_repositories;
+
+#[server(GetOpenRehearsal, "/api", "GetJson")]
+pub async fn get_open_rehearsal() -> Result<Option<RehearsalSession>, ServerFnError<AppError>> {
+    RehearsalSession::get_open(use_repositories()?.band_id)
+        .await
+        .map_err(ServerFnError::from)
+}
+
+#[server(GetRehearsals, "/api", "GetJson")]
+pub async fn get_rehearsals() -> Result<Vec<RehearsalModel>, ServerFnError<AppError>> {
+    RehearsalSession::get_all(use_repositories()?.band_id)
+        .await
+        .map_err(ServerFnError::from)
+}
+
+#[server(GetRehearsal, "/api", "GetJson")]
+pub async fn get_rehearsal(rehearsal_id: i32) -> Result<RehearsalSession, ServerFnError<AppError>> {
+    RehearsalSession::get_by_id(use_repositories()?.band_id, rehearsal_id)
+        .await
+        .map_err(ServerFnError::from)
+}
+
+#[server(StartRehearsal)]
+pub async fn start_rehearsal(location: String, attendees: String) -> Result<(), ServerFnError<AppError>> {
+    RehearsalSession::start(
+        use_repositories()?.band_id,
+        location.trim().to_string(),
+        RehearsalSession::parse_attendees(&attendees),
+    )
+    .await?;
+    Ok(())
+}
+
+/// Ticks a song off as played in the open rehearsal, or unticks it.
+#[server(TickRehearsalSong)]
+pub async fn tick_rehearsal_song(rehearsal_id: i32, song_id: i32) -> Result<(), ServerFnError<AppError>> {
+    RehearsalSession::toggle_song(&use_repositories()?, rehearsal_id, song_id)
+        .await
+        .map_err(ServerFnError::from)
+}
+
+#[server(SetRehearsalSongNotes)]
+pub async fn set_rehearsal_song_notes(
+    rehearsal_id: i32,
+    song_id: i32,
+    notes: String,
+) -> Result<(), ServerFnError<AppError>> {
+    RehearsalSession::set_song_notes(use_repositories()?.band_id, rehearsal_id, song_id, notes)
+        .await
+        .map_err(ServerFnError::from)
+}
+
+#[server(CloseRehearsal)]
+pub async fn close_rehearsal(rehearsal_id: i32, notes: String) -> Result<(), ServerFnError<AppError>> {
+    RehearsalSession::close(use_repositories()?.band_id, rehearsal_id, notes).await?;
+    leptos_axum::redirect(&format!("/rehearsal/{}", rehearsal_id));
+    Ok(())
+}
+
+/// Starts a rehearsal, or, while one is in progress, shows what has been played
+/// so far with notes per song and a form to close it.
+#[component]
+pub fn RehearsalPanel(
+    rehearsal: Option<RehearsalSession>,
+    start_rehearsal: Action<StartRehearsal, Result<(), ServerFnError<AppError>>>,
+    set_song_notes: Action<SetRehearsalSongNotes, Result<(), ServerFnError<AppError>>>,
+    close_rehearsal: Action<CloseRehearsal, Result<(), ServerFnError<AppError>>>,
+) -> impl IntoView {
+    let locale = use_locale();
+    let input_class = "input block w-full rounded-md border-0 py-1 pl-2 text-ctp-mantle text-sm";
+
+    match rehearsal {
+        None => view! {
+          <ActionForm action=start_rehearsal class="mx-3 mb-3 flex gap-2 items-center">
+            <input type="text" name="location" class=input_class placeholder=locale.t("gig-venue")/>
+            <input
+              type="text"
+              name="attendees"
+              class=input_class
+              placeholder=locale.t("rehearsal-attendees")
+            />
+            <button
+              type="submit"
+              class="border-0 rounded-md px-3 py-1 shadow-md bg-ctp-green text-ctp-mantle whitespace-nowrap"
+            >
+              <i class="fa-solid fa-play"></i>
+              " "
+              {locale.t("rehearsal-start")}
+            </button>
+          </ActionForm>
+        }
+        .into_view(),
+        Some(rehearsal) => {
+            let rehearsal_id = rehearsal.id;
+            view! {
+              <div class="mx-3 mb-3 p-2 rounded-lg shadow-md bg-ctp-surface0">
+                <div class="flex justify-between items-center mb-2">
+                  <div class="font-bold">
+                    <i class="fa-solid fa-drum"></i>
+                    " "
+                    {locale.t("rehearsal-in-progress")}
+                  </div>
+                  <div class="text-xs opacity-70">
+                    {locale.format_date_time(rehearsal.started_at)}
+                    " "
+                    {rehearsal.location}
+                  </div>
+                </div>
+                <div class="text-xs opacity-70 mb-2">{rehearsal.attendees.join(", ")}</div>
+                {match rehearsal.songs.is_empty() {
+                    true => {
+                        view! {
+                          <div class="text-sm opacity-70 mb-2">
+                            {locale.t("rehearsal-nothing-played")}
+                          </div>
+                        }
+                            .into_view()
+                    }
+                    false => {
+                        rehearsal
+                            .songs
+                            .into_iter()
+                            .map(|song| {
+                                view! {
+                                  <ActionForm action=set_song_notes class="flex gap-2 items-center mb-1">
+                                    <input type="hidden" name="rehearsal_id" value=rehearsal_id/>
+                                    <input type="hidden" name="song_id" value=song.song_id/>
+                                    <div class="text-sm flex-1 truncate">{song.title}</div>
+                                    <input
+                                      type="text"
+                                      name="notes"
+                                      class=input_class
+                                      placeholder=locale.t("rehearsal-notes")
+                                      value=song.notes
+                                    />
+                                    <button
+                                      type="submit"
+                                      class="border-0 rounded-md px-2 py-1 shadow-md bg-ctp-lavender text-ctp-mantle text-xs"
+                                    >
+                                      <i class="fa-solid fa-floppy-disk"></i>
+                                    </button>
+                                  </ActionForm>
+                                }
+                            })
+                            .collect_view()
+                    }
+                }}
+
+                <ActionForm action=close_rehearsal class="flex gap-2 items-center mt-2">
+                  <input type="hidden" name="rehearsal_id" value=rehearsal_id/>
+                  <input
+                    type="text"
+                    name="notes"
+                    class=input_class
+                    placeholder=locale.t("rehearsal-summary")
+                    value=rehearsal.notes
+                  />
+                  <button
+                    type="submit"
+                    class="border-0 rounded-md px-3 py-1 shadow-md bg-ctp-maroon text-ctp-mantle whitespace-nowrap"
+                  >
+                    <i class="fa-solid fa-stop"></i>
+                    " "
+                    {locale.t("rehearsal-close")}
+                  </button>
+                </ActionForm>
+              </div>
+            }
+            .into_view()
+        }
+    }
+}
+
+#[component]
+pub fn Rehearsals() -> impl IntoView {
+    let locale = use_locale();
+    let rehearsals = create_resource(|| (), |_| get_rehearsals());
+
+    view! {
+      <div class="m-4">
+        <Transition>
+          {move || {
+              rehearsals
+                  .get()
+                  .and_then(Result::ok)
+                  .unwrap_or_default()
+                  .into_iter()
+                  .map(|rehearsal| {
+                      view! {
+                        <a
+                          href=format!("/rehearsal/{}", rehearsal.id)
+                          class="flex justify-between items-center bg-ctp-crust rounded-lg shadow-md p-2 mb-2"
+                        >
+                          <div>
+                            <div class="font-bold text-sm">
+                              {locale.format_date(rehearsal.date)}
+                            </div>
+                            <div class="text-xs opacity-70">{rehearsal.location}</div>
+                          </div>
+                          <div class="text-xs opacity-70">
+                            {match rehearsal.closed_at {
+                                Some(_) => rehearsal.attendees.join(", "),
+                                None => locale.t("rehearsal-busy"),
+                            }}
+
+                          </div>
+                        </a>
+                      }
+                  })
+                  .collect_view()
+          }}
+
+        </Transition>
+      </div>
+    }
+}
+
+#[derive(Params, PartialEq)]
+struct RehearsalParams {
+    id: Option<i32>,
+}
+
+#[component]
+pub fn RehearsalView() -> impl IntoView {
+    let params = use_params::<RehearsalParams>();
+    let id = move || {
+        params.with(|params| {
+            params
+                .as_ref()
+                .map(|params| params.id.unwrap_or_default())
+                .unwrap_or_default()
+        })
+    };
+    let rehearsal = create_resource(id, get_rehearsal);
+
+    view! {
+      <Suspense>
+        <ErrorBoundary fallback=|errors| view! { <ErrorTemplate errors/> }>
+          {move || {
+              rehearsal
+                  .get()
+                  .map(|r| r.map(|rehearsal| view! { <RehearsalSummary rehearsal/> }))
+          }}
+
+        </ErrorBoundary>
+      </Suspense>
+    }
+}
+
+#[component]
+pub fn RehearsalSummary(rehearsal: RehearsalSession) -> impl IntoView {
+    let locale = use_locale();
+    let minutes = rehearsal.duration_minutes(chrono::Local::now().naive_local());
+
+    view! {
+      <div class="m-4">
+        <div class="font-bold text-xl">
+          {locale
+              .t_with(
+                  "rehearsal-title",
+                  &[("date", locale.format_date(rehearsal.date).into())],
+              )}
+        </div>
+        <div class="text-sm opacity-70">
+          {rehearsal.location} " · " {locale
+              .t_with(
+                  "rehearsal-duration",
+                  &[("duration", format!("{}:{:02}", minutes / 60, minutes % 60).into())],
+              )} " · " {locale.t_with("song-count", &[("count", rehearsal.songs.len().into())])}
+        </div>
+        <div class="text-sm mt-2">{rehearsal.attendees.join(", ")}</div>
+        <Show when={
+            let notes = rehearsal.notes.clone();
+            move || !notes.is_empty()
+        }>
+          <div class="bg-ctp-surface0 rounded-lg p-2 mt-2 text-sm">{rehearsal.notes.clone()}</div>
+        </Show>
+        <div class="font-bold text-lg mt-4">{locale.t("rehearsal-attendance")}</div>
+        <RehearsalAttendance rehearsal_id=rehearsal.id/>
+        <div class="font-bold text-lg mt-4 mb-2">{locale.t("rehearsal-played")}</div>
+        {rehearsal
+            .songs
+            .into_iter()
+            .map(|song: RehearsalSong| {
+                view! {
+                  <div class="bg-ctp-crust rounded-lg shadow-md p-2 mb-2">
+                    <div class="flex justify-between">
+                      <div class="font-bold text-sm">{song.title}</div>
+                      <div class="text-xs opacity-70">
+                        {song.played_at.format("%H:%M").to_string()}
+                      </div>
+                    </div>
+                    <div class="text-xs opacity-70">{song.artist}</div>
+                    <div class="text-sm">{song.notes}</div>
+                  </div>
+                }
+            })
+            .collect_view()}
+      </div>
+    }
+}
@@ -0,0 +1,191 @@
+use leptos::*;
+
+use crate::components::tags::get_tags;
+use crate::error::AppError;
+use crate::i18n::use_locale;
+use crate::models::setlist_generator::{GeneratedSetlist, GeneratorSettings};
+#[cfg(feature = "ssr")]
+use crate::repository::use_repositories;
+
+#[server(GenerateGigSetlist)]
+pub async fn generate_gig_setlist(
+    gig_id: i32,
+    settings: GeneratorSettings,
+) -> Result<GeneratedSetlist, ServerFnError<AppError>> {
+    use crate::i18n::Locale;
+    use crate::models::gig::Gig;
+    use crate::models::setlist_generator::generate;
+
+    let repos = use_repositories()?;
+    let songs = repos.songs.get_all().await?;
+    let generated = generate(&songs, &settings, Locale::detect());
+    Gig::replace_songs(&repos, gig_id, &generated.songs).await?;
+    Ok(generated)
+}
+
+/// Form to generate the running order of a gig. Generating replaces the
+/// current order; shuffling generates again with the next seed.
+#[component]
+pub fn SetlistGenerator(
+    gig_id: i32,
+    generate: Action<GenerateGigSetlist, Result<GeneratedSetlist, ServerFnError<AppError>>>,
+) -> impl IntoView {
+    let locale = use_locale();
+    let defaults = GeneratorSettings {
+        seed: gig_id as u64,
+        ..Default::default()
+    };
+    let target_minutes = create_rw_signal(defaults.target_minutes);
+    let sets = create_rw_signal(defaults.sets);
+    let pause_minutes = create_rw_signal(defaults.pause_minutes);
+    let opener_tag = create_rw_signal(defaults.opener_tag.clone());
+    let closer_tag = create_rw_signal(defaults.closer_tag.clone());
+    let avoid_same_artist = create_rw_signal(defaults.avoid_same_artist);
+    let seed = create_rw_signal(defaults.seed);
+    let open = create_rw_signal(false);
+
+    let tags = create_resource(|| (), |_| get_tags());
+
+    let dispatch = move || {
+        generate.dispatch(GenerateGigSetlist {
+            gig_id,
+            settings: GeneratorSettings {
+                target_minutes: target_minutes.get_untracked(),
+                sets: sets.get_untracked(),
+                pause_minutes: pause_minutes.get_untracked(),
+                opener_tag: opener_tag.get_untracked(),
+                closer_tag: closer_tag.get_untracked(),
+                avoid_same_artist: avoid_same_artist.get_untracked(),
+                seed: seed.get_untracked(),
+            },
+        })
+    };
+
+    let input_class = "input block w-full rounded-md border-0 py-1 pl-2 text-ctp-mantle ring-1 ring-inset ring-ctp-surface0 text-sm";
+    let number_input = move |label: String, signal: RwSignal<u32>| {
+        view! {
+          <label class="text-sm">
+            {label}
+            <input
+              type="number"
+              min="0"
+              class=input_class
+              prop:value=move || signal.get()
+              on:input=move |ev| {
+                  if let Ok(value) = event_target_value(&ev).parse() {
+                      signal.set(value)
+                  }
+              }
+            />
+
+          </label>
+        }
+    };
+    let tag_select = move |label: String, signal: RwSignal<Option<String>>| {
+        view! {
+          <label class="text-sm">
+            {label}
+            <select
+              class=input_class
+              on:change=move |ev| {
+                  let value = event_target_value(&ev);
+                  signal.set(Some(value).filter(|v| !v.is_empty()))
+              }
+            >
+              <option value="" selected=move || signal.get().is_none()>
+                {locale.t("generator-no-tag")}
+              </option>
+              <Transition>
+                {move || {
+                    tags.get()
+                        .and_then(Result::ok)
+                        .unwrap_or_default()
+                        .into_iter()
+                        .map(|tag| {
+                            let name = tag.name.clone();
+                            view! {
+                              <option
+                                value=tag.name.clone()
+                                selected=move || signal.get().as_ref() == Some(&name)
+                              >
+                                {tag.name}
+                              </option>
+                            }
+                        })
+                        .collect_view()
+                }}
+
+              </Transition>
+            </select>
+          </label>
+        }
+    };
+
+    view! {
+      <div class="mx-2 mt-2">
+        <button
+          type="button"
+          class="border-0 rounded-full px-3 py-2 shadow-md bg-ctp-lavender text-ctp-mantle"
+          on:click=move |_| open.update(|o| *o = !*o)
+        >
+          <i class="fa-solid fa-wand-magic-sparkles"></i>
+          " "
+          {locale.t("generator-open")}
+        </button>
+        <Show when=move || open.get()>
+          <div class="grid grid-cols-2 md:grid-cols-3 gap-2 mt-2 p-2 rounded-lg bg-ctp-crust">
+            {number_input(locale.t("generator-target-minutes"), target_minutes)}
+            {number_input(locale.t("generator-sets"), sets)}
+            {number_input(locale.t("generator-pause-minutes"), pause_minutes)}
+            {tag_select(locale.t("generator-opener-tag"), opener_tag)}
+            {tag_select(locale.t("generator-closer-tag"), closer_tag)}
+            <label class="text-sm flex items-center gap-2">
+              <input
+                type="checkbox"
+                prop:checked=move || avoid_same_artist.get()
+                on:change=move |ev| avoid_same_artist.set(event_target_checked(&ev))
+              />
+              {locale.t("generator-avoid-same-artist")}
+            </label>
+            <div class="col-span-2 md:col-span-3 flex gap-2 items-center">
+              <button
+                type="button"
+                class="border-0 rounded-md px-3 py-2 shadow-md bg-ctp-teal text-ctp-mantle"
+                on:click=move |_| dispatch()
+              >
+                {locale.t("generator-generate")}
+              </button>
+              <button
+                type="button"
+                class="border-0 rounded-md px-3 py-2 shadow-md bg-ctp-surface1 text-ctp-text"
+                on:click=move |_| {
+                    seed.update(|s| *s += 1);
+                    dispatch()
+                }
+              >
+
+                <i class="fa-solid fa-shuffle"></i>
+                " "
+                {locale.t("generator-shuffle")}
+              </button>
+              <span class="text-xs opacity-70">{locale.t("generator-replaces")}</span>
+            </div>
+            <ul class="col-span-2 md:col-span-3 text-xs list-disc ml-4">
+              {move || match generate.value().get() {
+                  Some(Ok(generated)) => {
+                      generated
+                          .explanations
+                          .into_iter()
+                          .map(|line| view! { <li>{line}</li> })
+                          .collect_view()
+                  }
+                  Some(Err(e)) => view! { <li>{e.to_string()}</li> }.into_view(),
+                  None => view! {}.into_view(),
+              }}
+
+            </ul>
+          </div>
+        </Show>
+      </div>
+    }
+}
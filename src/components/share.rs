@@ -0,0 +1,261 @@
+use leptos::*;
+use leptos_router::*;
+
+use crate::error::AppError;
+use crate::error_template::ErrorTemplate;
+use crate::i18n::use_locale;
+use crate::models::gig_share::{GigShare, SharedGig, SharedSong};
+#[cfg(feature = "ssr")]
+use crate::repository::use_repositories;
+
+#[server(GetGigShares, "/api", "GetJson")]
+pub async fn get_gig_shares(gig_id: i32) -> Result<Vec<GigShare>, ServerFnError<AppError>> {
+    GigShare::get_for_gig(use_repositories()?.band_id, gig_id)
+        .await
+        .map_err(ServerFnError::from)
+}
+
+/// Makes a new link for the gig. An empty `expires_on` means
+/// [`GigShare::default_expiry`].
+#[server(CreateGigShare)]
+pub async fn create_gig_share(
+    gig_id: i32,
+    include_lyrics: bool,
+    expires_on: String,
+) -> Result<(), ServerFnError<AppError>> {
+    let repos = use_repositories()?;
+    let gig = repos.gigs.get(gig_id).await?;
+    let expires_on =
+        GigShare::parse_expiry(&expires_on, gig.date, chrono::Local::now().date_naive())?;
+    GigShare::create(repos.band_id, gig_id, include_lyrics, expires_on)
+        .await
+        .map_err(ServerFnError::from)
+}
+
+#[server(RevokeGigShare)]
+pub async fn revoke_gig_share(share_id: i32) -> Result<(), ServerFnError<AppError>> {
+    GigShare::revoke(use_repositories()?.band_id, share_id)
+        .await
+        .map_err(ServerFnError::from)
+}
+
+#[server(GetSharedGig, "/api", "GetJson")]
+pub async fn get_shared_gig(token: String) -> Result<SharedGig, ServerFnError<AppError>> {
+    SharedGig::get(&token, chrono::Local::now().date_naive())
+        .await
+        .map_err(ServerFnError::from)
+}
+
+/// The share links of a gig, with a form to make another.
+#[component]
+pub fn GigSharePanel(gig_id: i32) -> impl IntoView {
+    let locale = use_locale();
+    let create_share = create_server_action::<CreateGigShare>();
+    let revoke_share = create_server_action::<RevokeGigShare>();
+    let shares = create_resource(
+        move || (create_share.version().get(), revoke_share.version().get()),
+        move |_| get_gig_shares(gig_id),
+    );
+    let include_lyrics = create_rw_signal(false);
+    let expires_on = create_rw_signal(String::default());
+    let today = chrono::Local::now().date_naive();
+
+    view! {
+      <div class="mx-2 mt-2">
+        <div class="font-bold text-sm mb-1">
+          <i class="fa-solid fa-share-nodes"></i>
+          " "
+          {locale.t("share")}
+        </div>
+        <Transition>
+          {move || {
+              shares
+                  .get()
+                  .and_then(Result::ok)
+                  .unwrap_or_default()
+                  .into_iter()
+                  .map(|share| {
+                      let link = format!("/gedeeld/{}", share.token);
+                      let expired = share.is_expired(today);
+                      view! {
+                        <div class="flex justify-between items-center text-sm py-1">
+                          <div class:line-through=expired>
+                            <a href=link.clone() class="underline">
+                              {link.clone()}
+                            </a>
+                            <a href=format!("{}/setlist.json", link) class="ml-2 text-xs underline">
+                              "JSON"
+                            </a>
+                            <span class="ml-2 text-xs text-ctp-overlay0">
+                              {share.include_lyrics.then(|| locale.t("share-with-lyrics"))}
+                              {locale
+                                  .t_with(
+                                      "share-valid-until",
+                                      &[("date", locale.format_date(share.expires_on).into())],
+                                  )}
+                            </span>
+                          </div>
+                          <button
+                            type="button"
+                            class="border-0 rounded-full px-2 py-1 shadow-md bg-ctp-maroon text-ctp-mantle text-xs"
+                            on:click=move |_| {
+                                revoke_share.dispatch(RevokeGigShare { share_id: share.id })
+                            }
+                          >
+
+                            <i class="fa-solid fa-trash"></i>
+                          </button>
+                        </div>
+                      }
+                  })
+                  .collect_view()
+          }}
+
+        </Transition>
+        <div class="flex gap-2 items-center mt-1 text-sm">
+          <label class="flex items-center gap-1">
+            <input
+              type="checkbox"
+              prop:checked=move || include_lyrics.get()
+              on:change=move |ev| include_lyrics.set(event_target_checked(&ev))
+            />
+            {locale.t("share-include-lyrics")}
+          </label>
+          <label class="flex items-center gap-1">
+            {locale.t("share-expires-on")}
+            <input
+              type="date"
+              class="rounded-md border-0 py-1 text-ctp-mantle"
+              prop:value=move || expires_on.get()
+              on:change=move |ev| expires_on.set(event_target_value(&ev))
+            />
+          </label>
+          <button
+            type="button"
+            class="border-0 rounded-full px-3 py-1 shadow-md bg-ctp-teal text-ctp-mantle"
+            on:click=move |_| {
+                create_share
+                    .dispatch(CreateGigShare {
+                        gig_id,
+                        include_lyrics: include_lyrics.get_untracked(),
+                        expires_on: expires_on.get_untracked(),
+                    })
+            }
+          >
+
+            {locale.t("share-create")}
+          </button>
+        </div>
+        {move || {
+            create_share
+                .value()
+                .get()
+                .and_then(Result::err)
+                .map(|e| {
+                    view! {
+                      <div class="text-xs text-ctp-red mt-1">
+                        {AppError::from_server_fn(&e).to_string()}
+                      </div>
+                    }
+                })
+        }}
+
+      </div>
+    }
+}
+
+#[derive(Params, PartialEq)]
+struct ShareParams {
+    token: Option<String>,
+}
+
+/// The read-only setlist behind a share link, for people outside the band.
+#[component]
+pub fn SharedGigView() -> impl IntoView {
+    let locale = use_locale();
+    let params = use_params::<ShareParams>();
+    let token = move || {
+        params.with(|params| {
+            params
+                .as_ref()
+                .ok()
+                .and_then(|params| params.token.clone())
+                .unwrap_or_default()
+        })
+    };
+    let gig = create_resource(token, get_shared_gig);
+
+    view! {
+      <Suspense>
+        <ErrorBoundary fallback=|errors| view! { <ErrorTemplate errors/> }>
+          {move || {
+              gig.get()
+                  .map(|r| {
+                      r.map(|gig| {
+                          view! {
+                            <div class="m-2">
+                              <div class="text-xl font-bold">{gig.venue}</div>
+                              <div class="text-sm text-ctp-subtext0">
+                                {locale.format_date(gig.date)}
+                                {gig.time.map(|time| format!(", {}", time))}
+                              </div>
+                              {gig
+                                  .sets
+                                  .into_iter()
+                                  .enumerate()
+                                  .map(|(index, set)| {
+                                      view! {
+                                        <div class="font-bold mt-4">{format!("Set {}", index + 1)}</div>
+                                        <ol class="list-decimal ml-6">
+                                          {set
+                                              .into_iter()
+                                              .map(|song| view! { <SharedSongItem song/> })
+                                              .collect_view()}
+                                        </ol>
+                                      }
+                                  })
+                                  .collect_view()}
+                            </div>
+                          }
+                      })
+                  })
+          }}
+
+        </ErrorBoundary>
+      </Suspense>
+    }
+}
+
+#[component]
+fn SharedSongItem(song: SharedSong) -> impl IntoView {
+    let locale = use_locale();
+    let details = [
+        song.key.map(|key| key.to_string()),
+        song.bpm.map(|bpm| format!("{} bpm", bpm)),
+    ]
+    .into_iter()
+    .flatten()
+    .collect::<Vec<_>>()
+    .join(", ");
+
+    view! {
+      <li class="py-1">
+        <span class="font-bold">{song.title}</span>
+        " — "
+        {song.artist}
+        <span class="ml-2 text-xs text-ctp-overlay0">{details}</span>
+        {song
+            .lyrics
+            .map(|lyrics| {
+                view! {
+                  <details class="text-sm">
+                    <summary class="cursor-pointer text-ctp-overlay0">
+                      {locale.t("song-lyrics")}
+                    </summary>
+                    <div class="whitespace-pre-line ml-2">{lyrics}</div>
+                  </details>
+                }
+            })}
+      </li>
+    }
+}
@@ -1,7 +1,26 @@
 use leptos::*;
 
 use crate::components::player::PlayerData;
+use crate::error::AppError;
+use crate::i18n::use_locale;
 use crate::models::song::Song;
+#[cfg(feature = "ssr")]
+use crate::repository::use_repositories;
+
+/// Has the browser keep a cookie for a year, for settings like the band or
+/// language picked in the nav.
+#[cfg(feature = "ssr")]
+pub fn set_cookie(name: &str, value: &str) {
+    use http::header::{HeaderValue, SET_COOKIE};
+
+    let cookie = format!("{}={}; Path=/; SameSite=Lax; Max-Age=31536000", name, value);
+    if let (Some(response), Ok(cookie)) = (
+        use_context::<leptos_axum::ResponseOptions>(),
+        HeaderValue::from_str(&cookie),
+    ) {
+        response.append_header(SET_COOKIE, cookie);
+    }
+}
 
 #[component]
 pub fn Horizontal() -> impl IntoView {
@@ -9,12 +28,59 @@ pub fn Horizontal() -> impl IntoView {
 }
 
 #[component]
-pub fn AlbumArt(mid: String, width: u32, height: u32) -> impl IntoView {
-    let mut art = mid;
-    art.push_str(".jpeg");
-    let path = format!("/coverart/{}", art);
+pub fn AlbumArt(release_mid: Option<String>, title: String, width: u32, height: u32) -> impl IntoView {
+    match release_mid {
+        Some(mid) => {
+            let path = format!("/coverart/{}.jpeg", mid);
+            view! { <img src=path width=width height=height/> }.into_view()
+        }
+        None => view! { <FallbackArt title width height/> }.into_view(),
+    }
+}
+
+/// Stand-in for songs without a MusicBrainz release: the initials of the title
+/// on a background colour derived from that same title.
+#[component]
+pub fn FallbackArt(title: String, width: u32, height: u32) -> impl IntoView {
+    let style = format!(
+        "width: {}px; height: {}px; background-color: {}; font-size: {}px;",
+        width,
+        height,
+        fallback_colour(&title),
+        height * 2 / 5
+    );
 
-    view! { <img src=path width=width height=height/> }
+    view! {
+      <div
+        class="flex items-center justify-center rounded-sm font-bold text-ctp-mantle select-none"
+        style=style
+      >
+        {initials(&title)}
+      </div>
+    }
+}
+
+/// First letter of the first two words of a title, e.g. "Black Betty" -> "BB".
+pub fn initials(title: &str) -> String {
+    let initials: String = title
+        .split_whitespace()
+        .filter_map(|word| word.chars().find(|c| c.is_alphanumeric()))
+        .take(2)
+        .flat_map(char::to_uppercase)
+        .collect();
+    match initials.is_empty() {
+        true => "?".to_string(),
+        false => initials,
+    }
+}
+
+/// Stable colour per title. Uses FNV-1a rather than `DefaultHasher` so the
+/// server and the wasm client agree on the colour during hydration.
+pub fn fallback_colour(title: &str) -> String {
+    let hash = title.bytes().fold(0x811c9dc5u32, |hash, byte| {
+        (hash ^ byte as u32).wrapping_mul(0x01000193)
+    });
+    format!("hsl({}, 55%, 65%)", hash % 360)
 }
 
 #[component]
@@ -22,10 +88,19 @@ pub fn PlayButton(song: Song, all_songs: Vec<Song>) -> impl IntoView {
     let set_player_data = use_context::<WriteSignal<Option<PlayerData>>>()
         .expect("Expected to have a set_played signal provided");
 
+    let has_audio = song.gs_url.is_some();
+    let locale = use_locale();
+    let class = match has_audio {
+        true => "border-0 rounded-lg py-3 w-16 shadow-lg bg-ctp-green text-ctp-mantle",
+        false => "border-0 rounded-lg py-3 w-16 shadow-lg bg-ctp-surface2 text-ctp-overlay0 cursor-not-allowed",
+    };
+
     view! {
       <button
         type="button"
-        class="border-0 rounded-lg py-3 w-16 shadow-lg bg-ctp-green text-ctp-mantle"
+        class=class
+        disabled=!has_audio
+        title=locale.t(if has_audio { "song-play" } else { "song-no-audio" })
         on:click=move |_| {
             set_player_data
                 .update(|data| {
@@ -51,7 +126,7 @@ pub fn LyricsButton(song_id: i32) -> impl IntoView {
           class="border-0 rounded-md px-2 py-2 shadow-lg bg-ctp-lavender text-ctp-mantle"
         >
           <i class="fa-solid fa-align-left"></i>
-          Lyrics
+          {use_locale().t("song-lyrics")}
         </button>
       </a>
     }
@@ -66,13 +141,17 @@ pub fn EditButton(song_id: i32) -> impl IntoView {
           class="border-0 rounded-md px-2 ml-2 py-2 shadow-lg bg-ctp-lavender text-ctp-mantle"
         >
           <i class="fa-solid fa-gear"></i>
-          Edit
+          {use_locale().t("song-edit")}
         </button>
       </a>
     }
 }
 
 #[server(GetSong, "/api", "GetJson")]
-pub async fn get_song(id: usize) -> Result<Song, ServerFnError> {
-    Song::get(id as i32).await.map_err(ServerFnError::from)
+pub async fn get_song(id: usize) -> Result<Song, ServerFnError<AppError>> {
+    use_repositories()?
+        .songs
+        .get(id as i32)
+        .await
+        .map_err(ServerFnError::from)
 }
@@ -1,21 +1,37 @@
 use leptos::*;
 
 use crate::components::shared::AlbumArt;
-use crate::models::song::Song;
+use crate::components::song_roles::RoleChips;
+use crate::components::tags::TagChips;
+use crate::i18n::use_locale;
+use crate::models::song::{Readiness, Song};
 
 #[component]
 pub fn SongItem(song: Song) -> impl IntoView {
+    let locale = use_locale();
     view! {
       <div class="mr-4">
       <div class="flex items-center gap-1">
         <div class="w-12 h-12">
-          <AlbumArt mid=song.release_mid.unwrap() width=48 height=48/>
+          <AlbumArt release_mid=song.release_mid title=song.title.clone() width=48 height=48/>
         </div>
         <div class="grow">
           <div class="font-bold text-sm text-left">{song.title}</div>
-          <div class="text-xs opacity-70 text-left">{song.artist}</div>
+          <div class="text-xs opacity-70 text-left">
+            {song.artist}
+            <Show when=move || song.readiness != Readiness::Ready>
+              <span class="ml-2 px-1 rounded-sm bg-ctp-peach text-ctp-mantle">
+                {locale.t(&format!("readiness-{}", song.readiness.as_str()))}
+              </span>
+            </Show>
+          </div>
+          <TagChips tags=song.tags/>
+          <RoleChips roles=song.roles/>
         </div>
-      <div class="text-xs opacity-80 text-right mx-4">{song.bpm} bpm</div>
+      <div class="text-xs opacity-80 text-right mx-4">
+        {song.bpm} bpm
+        {song.key.map(|key| view! { <div class="font-bold">{key.to_string()}</div> })}
+      </div>
       </div>
       </div>
     }
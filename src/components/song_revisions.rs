@@ -0,0 +1,160 @@
+use leptos::*;
+
+use crate::error::AppError;
+use crate::i18n::use_locale;
+use crate::models::song_revision::{DiffLine, SongRevision};
+#[cfg(feature = "ssr")]
+use crate::repository::use_repositories;
+
+#[server(GetSongRevisions, "/api", "GetJson")]
+pub async fn get_song_revisions(
+    song_id: i32,
+) -> Result<Vec<SongRevision>, ServerFnError<AppError>> {
+    use_repositories()?
+        .revisions
+        .get_for_song(song_id)
+        .await
+        .map_err(ServerFnError::from)
+}
+
+#[server(RestoreSongRevision)]
+pub async fn restore_song_revision(revision_id: i32) -> Result<(), ServerFnError<AppError>> {
+    SongRevision::restore(&use_repositories()?, revision_id)
+        .await
+        .map_err(ServerFnError::from)
+}
+
+/// The saved versions of a song, with what each one changed compared to the
+/// version before it.
+#[component]
+pub fn SongRevisions(
+    song_id: i32,
+    restore: Action<RestoreSongRevision, Result<(), ServerFnError<AppError>>>,
+) -> impl IntoView {
+    let locale = use_locale();
+    let revisions = create_resource(
+        move || restore.version().get(),
+        move |_| get_song_revisions(song_id),
+    );
+    let selected = create_rw_signal::<Option<i32>>(None);
+
+    view! {
+      <div class="mx-2 mt-6">
+        <div class="font-bold text-lg mb-2">
+          <i class="fa-solid fa-clock-rotate-left"></i>
+          " "
+          {locale.t("revisions")}
+        </div>
+        <Transition>
+          {move || {
+              let revisions = revisions.get().and_then(Result::ok).unwrap_or_default();
+              if revisions.is_empty() {
+                  return view! { <p class="text-sm text-ctp-overlay0">{locale.t("revisions-none")}</p> }
+                      .into_view();
+              }
+              let current = revisions[0].id;
+              let shown = selected.get().unwrap_or(current);
+              let list = revisions
+                  .iter()
+                  .map(|revision| {
+                      let id = revision.id;
+                      view! {
+                        <button
+                          type="button"
+                          class="block w-full text-left text-sm px-2 py-1 rounded-md"
+                          class:bg-ctp-surface0=id == shown
+                          on:click=move |_| selected.set(Some(id))
+                        >
+                          {locale.format_date_time(revision.created_at)}
+                          " · "
+                          {revision.author.clone()}
+                          {(id == current).then(|| format!(" ({})", locale.t("revisions-current")))}
+                        </button>
+                      }
+                  })
+                  .collect_view();
+              let position = revisions.iter().position(|r| r.id == shown).unwrap_or_default();
+              let revision = revisions[position].clone();
+              let older = revisions.get(position + 1).cloned();
+              view! {
+                <div class="grid md:grid-cols-3 gap-4">
+                  <div>{list}</div>
+                  <div class="md:col-span-2">
+                    {(revision.id != current)
+                        .then(|| {
+                            view! {
+                              <button
+                                type="button"
+                                class="border-0 rounded-full px-3 py-1 mb-2 shadow-md bg-ctp-teal text-ctp-mantle text-sm"
+                                on:click=move |_| {
+                                    selected.set(None);
+                                    restore.dispatch(RestoreSongRevision { revision_id: revision.id });
+                                }
+                              >
+                                <i class="fa-solid fa-rotate-left"></i>
+                                " "
+                                {locale.t("revisions-restore")}
+                              </button>
+                            }
+                        })}
+                    {match older {
+                        Some(older) => view! { <RevisionDiff revision older/> }.into_view(),
+                        None => {
+                            view! {
+                              <p class="text-sm text-ctp-overlay0">{locale.t("revisions-first")}</p>
+                            }
+                                .into_view()
+                        }
+                    }}
+
+                  </div>
+                </div>
+              }
+                  .into_view()
+          }}
+
+        </Transition>
+      </div>
+    }
+}
+
+#[component]
+fn RevisionDiff(revision: SongRevision, older: SongRevision) -> impl IntoView {
+    let locale = use_locale();
+    let fields = revision.changed_fields(&older, locale);
+    let lyrics = revision.lyrics_diff(&older);
+    let lyrics_changed = lyrics.iter().any(|line| !matches!(line, DiffLine::Same(_)));
+
+    view! {
+      <ul class="text-sm mb-2">
+        {fields
+            .into_iter()
+            .map(|(label, old, new)| {
+                view! { <li>{label} ": " {old} " → " {new}</li> }
+            })
+            .collect_view()}
+      </ul>
+      {match lyrics_changed {
+          false => view! { <p class="text-sm text-ctp-overlay0">{locale.t("revisions-lyrics-unchanged")}</p> }.into_view(),
+          true => {
+              view! {
+                <pre class="text-sm whitespace-pre-wrap">
+                  {lyrics
+                      .into_iter()
+                      .map(|line| match line {
+                          DiffLine::Same(text) => view! { <div>"  " {text}</div> },
+                          DiffLine::Added(text) => {
+                              view! { <div class="bg-ctp-green text-ctp-mantle">"+ " {text}</div> }
+                          }
+                          DiffLine::Removed(text) => {
+                              view! { <div class="bg-ctp-red text-ctp-mantle">"- " {text}</div> }
+                          }
+                      })
+                      .collect_view()}
+                </pre>
+              }
+                  .into_view()
+          }
+      }}
+    }
+}
@@ -0,0 +1,144 @@
+use leptos::*;
+use leptos_router::ActionForm;
+
+use crate::components::members::get_members;
+use crate::error::AppError;
+use crate::i18n::use_locale;
+use crate::models::song_role::{RoleKind, SongRole};
+#[cfg(feature = "ssr")]
+use crate::repository::use_repositories;
+
+#[server(GetSongRoles, "/api", "GetJson")]
+pub async fn get_song_roles(song_id: i32) -> Result<Vec<SongRole>, ServerFnError<AppError>> {
+    Ok(use_repositories()?.songs.get(song_id).await?.roles)
+}
+
+#[server(AddSongRole)]
+pub async fn add_song_role(
+    song_id: i32,
+    member_id: i32,
+    kind: RoleKind,
+    value: String,
+) -> Result<(), ServerFnError<AppError>> {
+    let band_id = use_repositories()?.band_id;
+    SongRole::add(band_id, song_id, member_id, kind, value.trim().to_string())
+        .await
+        .map_err(ServerFnError::from)
+}
+
+#[server(RemoveSongRole)]
+pub async fn remove_song_role(role_id: i32) -> Result<(), ServerFnError<AppError>> {
+    SongRole::remove(use_repositories()?.band_id, role_id)
+        .await
+        .map_err(ServerFnError::from)
+}
+
+#[component]
+pub fn RoleChips(roles: Vec<SongRole>) -> impl IntoView {
+    let locale = use_locale();
+    view! {
+      <div class="flex flex-wrap gap-1 mt-1">
+        {roles
+            .into_iter()
+            .map(|role| {
+                view! {
+                  <span
+                    class="px-1 rounded-sm text-xs bg-ctp-surface1 text-ctp-text"
+                    class=("bg-ctp-mauve", RoleKind::SETUP.contains(&role.kind))
+                    class=("text-ctp-mantle", RoleKind::SETUP.contains(&role.kind))
+                  >
+                    {role.describe(locale)}
+                  </span>
+                }
+            })
+            .collect_view()}
+      </div>
+    }
+}
+
+/// Who sings, which tunings and capos are used and who swaps instruments in
+/// a song.
+#[component]
+pub fn SongRolesEditor(song_id: i32) -> impl IntoView {
+    let add_role = create_server_action::<AddSongRole>();
+    let remove_role = create_server_action::<RemoveSongRole>();
+    let roles = create_resource(
+        move || (add_role.version().get(), remove_role.version().get()),
+        move |_| get_song_roles(song_id),
+    );
+    let members = create_resource(|| (), |_| get_members());
+    let input_class = "rounded-md border-0 py-1 pl-2 text-ctp-mantle text-sm";
+    let locale = use_locale();
+
+    view! {
+      <div class="mx-2 mt-6">
+        <div class="font-bold text-lg mb-2">{locale.t("song-roles")}</div>
+        <Transition>
+          {move || {
+              roles
+                  .get()
+                  .and_then(Result::ok)
+                  .unwrap_or_default()
+                  .into_iter()
+                  .map(|role| {
+                      view! {
+                        <div class="flex justify-between items-center text-sm py-1">
+                          <div>{role.describe(locale)}</div>
+                          <button
+                            type="button"
+                            class="border-0 rounded-full px-2 py-1 shadow-md bg-ctp-maroon text-ctp-mantle text-xs"
+                            on:click=move |_| {
+                                remove_role.dispatch(RemoveSongRole { role_id: role.id })
+                            }
+                          >
+
+                            <i class="fa-solid fa-trash"></i>
+                          </button>
+                        </div>
+                      }
+                  })
+                  .collect_view()
+          }}
+
+        </Transition>
+        <ActionForm action=add_role class="flex gap-2 mt-2">
+          <input type="hidden" name="song_id" value=song_id/>
+          <select name="member_id" class=input_class>
+            <Transition>
+              {move || {
+                  members
+                      .get()
+                      .and_then(Result::ok)
+                      .unwrap_or_default()
+                      .members
+                      .into_iter()
+                      .map(|member| {
+                          view! { <option value=member.id>{member.name}</option> }
+                      })
+                      .collect_view()
+              }}
+
+            </Transition>
+          </select>
+          <select name="kind" class=input_class>
+            {RoleKind::ALL
+                .into_iter()
+                .map(|kind| view! { <option value=kind.as_str()>{kind.label(locale)}</option> })
+                .collect_view()}
+          </select>
+          <input
+            type="text"
+            name="value"
+            class=input_class
+            placeholder="Drop D, 2, keys..."
+          />
+          <button
+            type="submit"
+            class="border-0 rounded-full px-3 py-1 shadow-md bg-ctp-teal text-ctp-mantle"
+          >
+            <i class="fa-solid fa-plus"></i>
+          </button>
+        </ActionForm>
+      </div>
+    }
+}
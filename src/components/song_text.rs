@@ -1,8 +1,47 @@
 use leptos::*;
 use leptos_router::*;
 
+use crate::components::arrangement::ArrangementTimeline;
+use crate::components::members::get_members;
 use crate::components::shared::get_song;
+use crate::error::AppError;
+use crate::i18n::use_locale;
+use crate::models::annotation::Annotation;
+use crate::models::member::Member;
 use crate::models::song::Song;
+#[cfg(feature = "ssr")]
+use crate::repository::use_repositories;
+
+type Act<T> = Action<T, Result<(), ServerFnError<AppError>>>;
+
+#[server(GetAnnotations, "/api", "GetJson")]
+pub async fn get_annotations(song_id: i32) -> Result<Vec<Annotation>, ServerFnError<AppError>> {
+    use_repositories()?
+        .annotations
+        .get_for_song(song_id)
+        .await
+        .map_err(ServerFnError::from)
+}
+
+#[server(AddAnnotation)]
+pub async fn add_annotation(
+    song_id: i32,
+    member_id: i32,
+    line: i32,
+    body: String,
+) -> Result<(), ServerFnError<AppError>> {
+    Annotation::create(&use_repositories()?, song_id, member_id, line, body).await?;
+    Ok(())
+}
+
+#[server(RemoveAnnotation)]
+pub async fn remove_annotation(annotation_id: i32) -> Result<(), ServerFnError<AppError>> {
+    use_repositories()?
+        .annotations
+        .remove(annotation_id)
+        .await
+        .map_err(ServerFnError::from)
+}
 
 #[derive(Params, PartialEq)]
 struct LyricParams {
@@ -15,6 +54,10 @@ pub fn SongText() -> impl IntoView {
 
     let edit_mode = create_rw_signal(false);
     provide_context(edit_mode);
+    // Big text without editing, for on stage.
+    let stage_mode = create_rw_signal(false);
+    let show_notes = create_rw_signal(true);
+    let locale = use_locale();
 
     let id = move || {
         params.with(|params| {
@@ -25,10 +68,18 @@ pub fn SongText() -> impl IntoView {
         })
     };
 
+    let add = create_server_action::<AddAnnotation>();
+    let remove = create_server_action::<RemoveAnnotation>();
+
     let song_resource = create_resource(id, get_song);
+    let annotations = create_resource(
+        move || (id(), add.version().get(), remove.version().get()),
+        |(id, ..)| get_annotations(id as i32),
+    );
+    let members = create_resource(|| (), |_| get_members());
 
     view! {
-      <div class="m-2">
+      <div class="m-2" class:text-2xl=stage_mode>
         <Suspense fallback=|| {
             view! {}.into_view()
         }>
@@ -39,10 +90,37 @@ pub fn SongText() -> impl IntoView {
                       <div>
                         <div class="text-xl font-bold text-nowrap">{song.title.to_string()}</div>
                       </div>
+                      <div class="flex gap-2 text-sm">
+                        <ToggleButton value=show_notes icon="fa-note-sticky" label=locale.t("notes-show")/>
+                        <ToggleButton value=stage_mode icon="fa-microphone" label=locale.t("notes-stage")/>
+                      </div>
                     </div>
+                    <ArrangementTimeline sections=song.sections.clone() bpm=song.bpm/>
                     {move || match edit_mode.get() {
                         true => view! {}.into_view(),
-                        false => view! { <ViewLyric song=song.clone()/> }.into_view(),
+                        false => {
+                            let annotations = annotations
+                                .get()
+                                .and_then(Result::ok)
+                                .unwrap_or_default();
+                            let members = members
+                                .get()
+                                .and_then(Result::ok)
+                                .map(|m| m.members)
+                                .unwrap_or_default();
+                            view! {
+                              <ViewLyric
+                                song=song.clone()
+                                annotations
+                                members
+                                show_notes=show_notes.get()
+                                editable=!stage_mode.get()
+                                add
+                                remove
+                              />
+                            }
+                                .into_view()
+                        }
                     }}
                   }
                       .into_view()
@@ -57,21 +135,152 @@ pub fn SongText() -> impl IntoView {
 }
 
 #[component]
-fn ViewLyric(song: Song) -> impl IntoView {
+fn ToggleButton(value: RwSignal<bool>, icon: &'static str, label: String) -> impl IntoView {
+    view! {
+      <button
+        type="button"
+        class="border-0 rounded-full px-3 py-1 shadow-md"
+        class=("bg-ctp-teal", value)
+        class=("text-ctp-mantle", value)
+        class=("bg-ctp-surface1", move || !value.get())
+        on:click=move |_| value.update(|v| *v = !*v)
+      >
+        <i class=format!("fa-solid {}", icon)></i>
+        " "
+        {label}
+      </button>
+    }
+}
+
+#[component]
+fn ViewLyric(
+    song: Song,
+    annotations: Vec<Annotation>,
+    members: Vec<Member>,
+    show_notes: bool,
+    /// Whether notes can be added and removed.
+    editable: bool,
+    add: Act<AddAnnotation>,
+    remove: Act<RemoveAnnotation>,
+) -> impl IntoView {
+    let (placed, loose) = Annotation::place(annotations, &song.lyrics);
+    let selected_line = create_rw_signal::<Option<i32>>(None);
+    let song_id = song.id;
+
     view! {
       <div class="white-space:pre; mt-2">
         {song
             .lyrics
-            .split("\n")
-            .into_iter()
-            .map(|line| {
+            .split('\n')
+            .zip(placed)
+            .enumerate()
+            .map(|(index, (line, notes))| {
                 if line.is_empty() {
-                    view! { <br/> }.into_view()
-                } else {
-                    view! { <p class="m-1">{line.to_string()}</p> }.into_view()
+                    return view! { <br/> }.into_view();
+                }
+                let index = index as i32;
+                let members = members.clone();
+                view! {
+                  <p
+                    class="m-1"
+                    class:cursor-pointer=editable
+                    on:click=move |_| {
+                        if editable {
+                            selected_line.update(|s| {
+                                *s = (*s != Some(index)).then_some(index);
+                            });
+                        }
+                    }
+                  >
+
+                    {line.to_string()}
+                  </p>
+                  {show_notes.then(|| view! { <Notes notes editable remove/> })}
+                  {move || {
+                      (selected_line.get() == Some(index))
+                          .then(|| {
+                              view! {
+                                <AnnotationForm song_id line=index members=members.clone() add/>
+                              }
+                          })
+                  }}
                 }
+                    .into_view()
             })
             .collect_view()}
+        {(show_notes && !loose.is_empty())
+            .then(|| {
+                view! {
+                  <div class="mt-4 text-sm text-ctp-overlay0">
+                    {use_locale().t("notes-loose")}
+                  </div>
+                  <Notes notes=loose editable remove/>
+                }
+            })}
       </div>
     }
 }
+
+#[component]
+fn Notes(notes: Vec<Annotation>, editable: bool, remove: Act<RemoveAnnotation>) -> impl IntoView {
+    notes
+        .into_iter()
+        .map(|note| {
+            let annotation_id = note.id;
+            view! {
+              <div class="ml-4 mb-1 text-ctp-peach italic">
+                <i class="fa-solid fa-note-sticky"></i>
+                " "
+                {note.body}
+                <span class="text-xs text-ctp-overlay0 not-italic">" — " {note.member}</span>
+                {editable
+                    .then(|| {
+                        view! {
+                          <button
+                            type="button"
+                            class="ml-2 text-xs text-ctp-maroon"
+                            on:click=move |_| remove.dispatch(RemoveAnnotation { annotation_id })
+                          >
+                            <i class="fa-solid fa-trash"></i>
+                          </button>
+                        }
+                    })}
+              </div>
+            }
+        })
+        .collect_view()
+}
+
+#[component]
+fn AnnotationForm(
+    song_id: i32,
+    line: i32,
+    members: Vec<Member>,
+    add: Act<AddAnnotation>,
+) -> impl IntoView {
+    let locale = use_locale();
+    view! {
+      <ActionForm action=add class="flex gap-2 ml-4 mb-2 text-sm">
+        <input type="hidden" name="song_id" value=song_id/>
+        <input type="hidden" name="line" value=line/>
+        <select name="member_id" class="rounded-md border-0 py-1 text-ctp-mantle">
+          {members
+              .into_iter()
+              .map(|member| view! { <option value=member.id>{member.name}</option> })
+              .collect_view()}
+        </select>
+        <input
+          type="text"
+          name="body"
+          class="input rounded-md border-0 py-1 pl-2 text-ctp-mantle flex-1"
+          placeholder=locale.t("notes-placeholder")
+        />
+        <button
+          type="submit"
+          class="border-0 rounded-full px-3 py-1 shadow-md bg-ctp-teal text-ctp-mantle"
+        >
+          {locale.t("notes-add")}
+        </button>
+      </ActionForm>
+    }
+}
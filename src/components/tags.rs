@@ -0,0 +1,147 @@
+use leptos::*;
+
+use crate::error::AppError;
+use crate::i18n::use_locale;
+use crate::models::tag::Tag;
+#[cfg(feature = "ssr")]
+use crate::repository::use_repositories;
+
+#[server(GetTags, "/api", "GetJson")]
+pub async fn get_tags() -> Result<Vec<Tag>, ServerFnError<AppError>> {
+    Tag::get_all(use_repositories()?.band_id)
+        .await
+        .map_err(ServerFnError::from)
+}
+
+#[component]
+pub fn TagChips(tags: Vec<String>) -> impl IntoView {
+    view! {
+      <div class="flex flex-wrap gap-1">
+        {tags
+            .into_iter()
+            .map(|tag| {
+                view! {
+                  <span class="px-1 rounded-sm text-xs bg-ctp-surface1 text-ctp-subtext1">{tag}</span>
+                }
+            })
+            .collect_view()}
+      </div>
+    }
+}
+
+/// Chip-style tag editor. The selected tags are submitted as a comma separated
+/// list in a hidden input named `entity`, so it can live inside an `ActionForm`.
+#[component]
+pub fn TagEditor(tags: Vec<String>, entity: String) -> impl IntoView {
+    let selected = create_rw_signal(tags);
+    let (new_tag, set_new_tag) = create_signal(String::new());
+    let known_tags = create_resource(|| (), |_| get_tags());
+    let locale = use_locale();
+
+    let add_tag = move |tag: String| {
+        let tag = tag.trim().to_lowercase();
+        if !tag.is_empty() {
+            selected.update(|tags| {
+                if !tags.contains(&tag) {
+                    tags.push(tag)
+                }
+            });
+        }
+        set_new_tag.set(String::new());
+    };
+
+    view! {
+      <div class="mb-5">
+        <label class="block text-sm mb-1">Tags</label>
+        <input type="hidden" name=entity prop:value=move || selected.get().join(",")/>
+        <div class="flex flex-wrap gap-1 items-center">
+          <For each=move || selected.get() key=|tag| tag.clone() let:tag>
+            <button
+              type="button"
+              class="px-2 py-1 rounded-md text-xs bg-ctp-lavender text-ctp-mantle"
+              on:click={
+                  let tag = tag.clone();
+                  move |_| selected.update(|tags| tags.retain(|t| t != &tag))
+              }
+            >
+
+              {tag}
+              " "
+              <i class="fa-solid fa-xmark"></i>
+            </button>
+          </For>
+          <input
+            type="text"
+            class="text-sm bg-transparent border-0 border-b-2 border-gray-300 focus:outline-none focus:ring-0"
+            placeholder=locale.t("tag-new")
+            prop:value=new_tag
+            on:input=move |ev| set_new_tag.set(event_target_value(&ev))
+            on:keydown=move |ev| {
+                if ev.key() == "Enter" {
+                    ev.prevent_default();
+                    add_tag(new_tag.get_untracked());
+                }
+            }
+          />
+
+        </div>
+        <div class="flex flex-wrap gap-1 mt-2">
+          <Transition>
+            {move || {
+                known_tags
+                    .get()
+                    .and_then(Result::ok)
+                    .unwrap_or_default()
+                    .into_iter()
+                    .filter(|tag| !selected.get().contains(&tag.name))
+                    .map(|tag| {
+                        let name = tag.name.clone();
+                        view! {
+                          <button
+                            type="button"
+                            class="px-2 py-1 rounded-md text-xs bg-ctp-surface1 text-ctp-subtext1"
+                            on:click=move |_| add_tag(name.clone())
+                          >
+                            <i class="fa-solid fa-plus"></i>
+                            " "
+                            {tag.name}
+                          </button>
+                        }
+                    })
+                    .collect_view()
+            }}
+
+          </Transition>
+        </div>
+      </div>
+    }
+}
+
+/// Dropdown of all known tags, with an empty option meaning "any tag".
+#[component]
+pub fn TagSelect(
+    tags: Vec<Tag>,
+    selected: Option<String>,
+    #[prop(into)] name: String,
+    #[prop(into)] class: String,
+) -> impl IntoView {
+    let locale = use_locale();
+    view! {
+      <select name=name class=class>
+        <option value="" selected=selected.is_none()>
+          {locale.t("tag-all")}
+        </option>
+        {tags
+            .into_iter()
+            .map(|tag| {
+                let is_selected = selected.as_ref() == Some(&tag.name);
+                view! {
+                  <option value=tag.name.clone() selected=is_selected>
+                    {tag.name}
+                  </option>
+                }
+            })
+            .collect_view()}
+      </select>
+    }
+}
@@ -0,0 +1,285 @@
+use leptos::*;
+use leptos_router::*;
+use serde::{Deserialize, Serialize};
+
+use crate::components::edit_song::Input;
+use crate::error::AppError;
+use crate::error_template::ErrorTemplate;
+use crate::i18n::use_locale;
+use crate::models::gig::GigModel;
+use crate::models::venue::Venue;
+#[cfg(feature = "ssr")]
+use crate::repository::use_repositories;
+
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq, Eq, Default)]
+pub struct VenueDetails {
+    pub venue: Venue,
+    pub gigs: Vec<GigModel>,
+}
+
+#[server(GetVenues, "/api", "GetJson")]
+pub async fn get_venues() -> Result<Vec<Venue>, ServerFnError<AppError>> {
+    Venue::get_all(use_repositories()?.band_id)
+        .await
+        .map_err(ServerFnError::from)
+}
+
+#[server(GetVenue, "/api", "GetJson")]
+pub async fn get_venue(venue_id: i32) -> Result<VenueDetails, ServerFnError<AppError>> {
+    let repos = use_repositories()?;
+    Ok(VenueDetails {
+        venue: Venue::get_by_id(repos.band_id, venue_id).await?,
+        gigs: repos.gigs.get_by_venue(venue_id).await?,
+    })
+}
+
+#[server(CreateVenue)]
+pub async fn create_venue(name: String) -> Result<(), ServerFnError<AppError>> {
+    let name = name.trim().to_string();
+    if name.is_empty() {
+        return Err(AppError::validation("Vul een naam in").into());
+    }
+    let id = Venue::create(use_repositories()?.band_id, name).await?;
+    leptos_axum::redirect(&format!("/venue/{}", id));
+    Ok(())
+}
+
+#[server(UpdateVenue)]
+pub async fn update_venue(venue: Venue) -> Result<(), ServerFnError<AppError>> {
+    Venue::update(use_repositories()?.band_id, venue).await?;
+    leptos_axum::redirect("/venues");
+    Ok(())
+}
+
+#[server(RemoveVenue)]
+pub async fn remove_venue(venue_id: i32) -> Result<(), ServerFnError<AppError>> {
+    Venue::remove(use_repositories()?.band_id, venue_id)
+        .await
+        .map_err(ServerFnError::from)
+}
+
+#[server(SetGigVenueId)]
+pub async fn set_gig_venue_id(gig_id: i32, venue_id: Option<i32>) -> Result<(), ServerFnError<AppError>> {
+    use crate::models::gig::Gig;
+
+    Gig::set_venue_id(&use_repositories()?, gig_id, venue_id)
+        .await
+        .map_err(ServerFnError::from)
+}
+
+#[component]
+pub fn Venues() -> impl IntoView {
+    let locale = use_locale();
+    let create_venue = create_server_action::<CreateVenue>();
+    let remove_venue = create_server_action::<RemoveVenue>();
+    let venues = create_resource(move || remove_venue.version().get(), |_| get_venues());
+
+    view! {
+      <div class="m-4">
+        <ActionForm action=create_venue class="flex gap-2 mb-4">
+          <input
+            type="text"
+            name="name"
+            class="input block w-full rounded-md border-0 py-2 pl-2 text-ctp-mantle text-sm"
+            placeholder=locale.t("venue-new")
+          />
+          <button
+            type="submit"
+            class="border-0 rounded-full px-3 py-2 shadow-md bg-ctp-teal text-ctp-mantle"
+          >
+            <i class="fa-solid fa-plus"></i>
+          </button>
+        </ActionForm>
+        <Transition>
+          {move || {
+              venues
+                  .get()
+                  .and_then(Result::ok)
+                  .unwrap_or_default()
+                  .into_iter()
+                  .map(|venue| {
+                      view! {
+                        <div class="flex justify-between items-center bg-ctp-crust rounded-lg shadow-md p-2 mb-2">
+                          <a href=format!("/venue/{}", venue.id) class="flex-1">
+                            <div class="font-bold text-sm">{venue.name}</div>
+                            <div class="text-xs opacity-70">{venue.address}</div>
+                          </a>
+                          <button
+                            type="button"
+                            class="border-0 rounded-full px-3 py-2 shadow-md bg-ctp-maroon text-ctp-mantle text-xs"
+                            on:click=move |_| {
+                                remove_venue.dispatch(RemoveVenue { venue_id: venue.id })
+                            }
+                          >
+
+                            <i class="fa-solid fa-trash"></i>
+                          </button>
+                        </div>
+                      }
+                  })
+                  .collect_view()
+          }}
+
+        </Transition>
+      </div>
+    }
+}
+
+#[derive(Params, PartialEq)]
+struct VenueParams {
+    id: Option<i32>,
+}
+
+#[component]
+pub fn VenueView() -> impl IntoView {
+    let params = use_params::<VenueParams>();
+    let id = move || {
+        params.with(|params| {
+            params
+                .as_ref()
+                .map(|params| params.id.unwrap_or_default())
+                .unwrap_or_default()
+        })
+    };
+    let venue_resource = create_resource(id, get_venue);
+
+    view! {
+      <Suspense>
+        <ErrorBoundary fallback=|errors| view! { <ErrorTemplate errors/> }>
+          {move || {
+              venue_resource.get().map(|r| r.map(|details| view! { <VenueForm details/> }))
+          }}
+
+        </ErrorBoundary>
+      </Suspense>
+    }
+}
+
+#[component]
+pub fn VenueForm(details: VenueDetails) -> impl IntoView {
+    let locale = use_locale();
+    let action = create_server_action::<UpdateVenue>();
+    let venue = details.venue;
+
+    view! {
+      <ActionForm action=action class="mx-2 mt-2">
+        <button
+          type="submit"
+          class="border-0 rounded-md px-3 py-2 shadow-lg bg-ctp-lavender text-ctp-mantle"
+        >
+          {locale.t("save")}
+        </button>
+        <input type="hidden" name="venue[id]" value=venue.id/>
+        <div class="grid md:grid-cols-2 md:gap-6 mt-6">
+          <Input title=locale.t("venue-name") value=venue.name entity="venue[name]".to_string()/>
+          <Input title=locale.t("venue-address") value=venue.address entity="venue[address]".to_string()/>
+          <Input
+            title=locale.t("venue-contact-name")
+            value=venue.contact_name
+            entity="venue[contact_name]".to_string()
+          />
+          <Input
+            title=locale.t("venue-contact-phone")
+            value=venue.contact_phone
+            entity="venue[contact_phone]".to_string()
+          />
+          <Input
+            title=locale.t("venue-contact-email")
+            value=venue.contact_email
+            entity="venue[contact_email]".to_string()
+          />
+          <Input
+            title=locale.t("venue-stage-size")
+            value=venue.stage_size
+            entity="venue[stage_size]".to_string()
+          />
+        </div>
+        <textarea
+          class="textarea textarea-bordered w-full max-w p-2 h-32"
+          placeholder=locale.t("venue-technical-notes")
+          name="venue[technical_notes]"
+        >
+          {venue.technical_notes}
+        </textarea>
+      </ActionForm>
+
+      <div class="mx-2 mt-6">
+        <div class="font-bold text-lg mb-2">{locale.t("venue-played")}</div>
+        {match details.gigs.is_empty() {
+            true => view! { <div class="text-sm opacity-70">{locale.t("venue-never-played")}</div> }.into_view(),
+            false => {
+                details
+                    .gigs
+                    .into_iter()
+                    .map(|gig| {
+                        view! {
+                          <a href=format!("/gig/{}", gig.id) class="block text-sm py-1">
+                            {locale.format_date(gig.date)}
+                            <span class="opacity-70">
+                              {format!(
+                                  " - {}",
+                                  locale
+                                      .t_with(
+                                          "song-count",
+                                          &[("count", gig.songs.iter().filter(|id| **id > 0).count().into())],
+                                      ),
+                              )}
+                            </span>
+                          </a>
+                        }
+                    })
+                    .collect_view()
+            }
+        }}
+
+      </div>
+    }
+}
+
+/// Dropdown on the gig page to link the gig to a venue from the venue table.
+#[component]
+pub fn VenuePicker(gig_id: i32, venue_id: Option<i32>) -> impl IntoView {
+    let locale = use_locale();
+    let set_venue = create_server_action::<SetGigVenueId>();
+    let venues = create_resource(|| (), |_| get_venues());
+
+    view! {
+      <div>
+        <label class="block text-sm font-medium leading-6">{locale.t("gig-venue")}</label>
+        <div class="relative mt-2 rounded-md shadow-sm text-sm">
+          <Transition>
+            <select
+              class="input block w-full rounded-md border-0 py-2 pl-2 text-ctp-mantle ring-1 ring-inset ring-ctp-surface0"
+              on:change=move |ev| {
+                  set_venue
+                      .dispatch(SetGigVenueId {
+                          gig_id,
+                          venue_id: event_target_value(&ev).parse().ok(),
+                      })
+              }
+            >
+              <option value="" selected=venue_id.is_none()>
+                {locale.t("venue-none")}
+              </option>
+              {move || {
+                  venues
+                      .get()
+                      .and_then(Result::ok)
+                      .unwrap_or_default()
+                      .into_iter()
+                      .map(|venue| {
+                          view! {
+                            <option value=venue.id selected=venue_id == Some(venue.id)>
+                              {venue.name}
+                            </option>
+                          }
+                      })
+                      .collect_view()
+              }}
+
+            </select>
+          </Transition>
+        </div>
+      </div>
+    }
+}
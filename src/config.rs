@@ -0,0 +1,295 @@
+use std::path::{Path, PathBuf};
+
+use clap::Args;
+use serde::Deserialize;
+use thiserror::Error;
+
+/// Config file read when `--config` and `MDR_CONFIG` are not given, if present.
+pub const DEFAULT_CONFIG_FILE: &str = "my-dad-rocks.toml";
+
+/// Everything the binary needs to start. Values come from, in increasing
+/// order of precedence: the defaults below, a TOML file, environment
+/// variables and command line flags.
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize, Default)]
+#[serde(default, deny_unknown_fields)]
+pub struct Config {
+    pub database: DatabaseConfig,
+    pub storage: StorageConfig,
+    pub art: ArtConfig,
+    pub auth: AuthConfig,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct DatabaseConfig {
+    pub url: Option<String>,
+    pub max_connections: u32,
+    /// How often to retry connecting before giving up.
+    pub connect_retries: u32,
+    pub retry_delay_secs: u64,
+}
+
+impl Default for DatabaseConfig {
+    fn default() -> Self {
+        Self {
+            url: None,
+            max_connections: 4,
+            connect_retries: 5,
+            retry_delay_secs: 5,
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct StorageConfig {
+    /// Where `generate` saves downloaded cover art. The site serves
+    /// `/coverart` from its assets, so this should stay `<assets>/coverart`.
+    pub coverart_dir: PathBuf,
+    /// The mp3 files `generate` cuts artwork from, skipped when missing.
+    pub mp3_dir: PathBuf,
+    pub thumbnail_dir: PathBuf,
+    pub player_dir: PathBuf,
+}
+
+impl Default for StorageConfig {
+    fn default() -> Self {
+        Self {
+            coverart_dir: PathBuf::from("public/coverart"),
+            mp3_dir: PathBuf::from("assets/mp3"),
+            thumbnail_dir: PathBuf::from("assets/thumbnails"),
+            player_dir: PathBuf::from("assets/player"),
+        }
+    }
+}
+
+/// Sizes in pixels of the square images cut from the mp3 artwork.
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct ArtConfig {
+    pub thumbnail_size: u32,
+    pub player_size: u32,
+}
+
+impl Default for ArtConfig {
+    fn default() -> Self {
+        Self {
+            thumbnail_size: 48,
+            player_size: 80,
+        }
+    }
+}
+
+/// Who the app believes a request comes from. Users are passed on by a proxy
+/// in front of the app in `x-forwarded-user` or `remote-user`, which are only
+/// trusted on requests that also carry `proxy_secret` in `x-proxy-secret`.
+/// Without a secret every request is anonymous.
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize, Default)]
+#[serde(default, deny_unknown_fields)]
+pub struct AuthConfig {
+    pub proxy_secret: Option<String>,
+}
+
+/// Shortest `auth.proxy_secret` accepted, so it can't be guessed.
+pub const MIN_PROXY_SECRET_LEN: usize = 16;
+
+/// Command line flags that override the config file and environment.
+#[derive(Debug, Clone, PartialEq, Eq, Args, Default)]
+pub struct ConfigOverrides {
+    /// TOML config file [env: MDR_CONFIG] [default: my-dad-rocks.toml]
+    #[arg(long, global = true)]
+    pub config: Option<PathBuf>,
+    /// Postgres connection string [env: DATABASE_URL]
+    #[arg(long, global = true)]
+    pub database_url: Option<String>,
+    #[arg(long, global = true)]
+    pub max_connections: Option<u32>,
+    #[arg(long, global = true)]
+    pub connect_retries: Option<u32>,
+    #[arg(long, global = true)]
+    pub retry_delay_secs: Option<u64>,
+    #[arg(long, global = true)]
+    pub coverart_dir: Option<PathBuf>,
+    #[arg(long, global = true)]
+    pub mp3_dir: Option<PathBuf>,
+    #[arg(long, global = true)]
+    pub thumbnail_dir: Option<PathBuf>,
+    #[arg(long, global = true)]
+    pub player_dir: Option<PathBuf>,
+    #[arg(long, global = true)]
+    pub thumbnail_size: Option<u32>,
+    #[arg(long, global = true)]
+    pub player_size: Option<u32>,
+    #[arg(long, global = true)]
+    pub proxy_secret: Option<String>,
+}
+
+#[derive(Debug, Error)]
+pub enum ConfigError {
+    #[error("could not read config file {path}: {source}")]
+    Read {
+        path: PathBuf,
+        source: std::io::Error,
+    },
+    #[error("invalid config file {path}: {source}")]
+    Parse {
+        path: PathBuf,
+        source: toml::de::Error,
+    },
+    #[error("environment variable {name} has invalid value {value:?}")]
+    Env { name: &'static str, value: String },
+    #[error("{0}")]
+    Invalid(String),
+}
+
+impl Config {
+    /// Reads the config file, environment and flags and validates the result.
+    pub fn load(overrides: &ConfigOverrides) -> Result<Self, ConfigError> {
+        let path = overrides
+            .config
+            .clone()
+            .or_else(|| std::env::var_os("MDR_CONFIG").map(PathBuf::from));
+        let file = match &path {
+            Some(path) => Some((path.clone(), read(path)?)),
+            None => {
+                let path = PathBuf::from(DEFAULT_CONFIG_FILE);
+                match path.exists() {
+                    true => Some((path.clone(), read(&path)?)),
+                    false => None,
+                }
+            }
+        };
+        Self::resolve(
+            file.as_ref()
+                .map(|(path, toml)| (path.as_path(), toml.as_str())),
+            |name| std::env::var(name).ok(),
+            overrides,
+        )
+    }
+
+    /// Layers the file, the environment given by `env` and the flags over the
+    /// defaults, without touching the file system.
+    pub fn resolve(
+        file: Option<(&Path, &str)>,
+        env: impl Fn(&str) -> Option<String>,
+        overrides: &ConfigOverrides,
+    ) -> Result<Self, ConfigError> {
+        let mut config = match file {
+            Some((path, toml)) => toml::from_str(toml).map_err(|source| ConfigError::Parse {
+                path: path.to_path_buf(),
+                source,
+            })?,
+            None => Config::default(),
+        };
+
+        if let Some(url) = env("DATABASE_URL") {
+            config.database.url = Some(url);
+        }
+        set_parsed(
+            &env,
+            "MDR_MAX_CONNECTIONS",
+            &mut config.database.max_connections,
+        )?;
+        set_parsed(
+            &env,
+            "MDR_CONNECT_RETRIES",
+            &mut config.database.connect_retries,
+        )?;
+        set_parsed(
+            &env,
+            "MDR_RETRY_DELAY_SECS",
+            &mut config.database.retry_delay_secs,
+        )?;
+        set_parsed(&env, "MDR_COVERART_DIR", &mut config.storage.coverart_dir)?;
+        set_parsed(&env, "MDR_MP3_DIR", &mut config.storage.mp3_dir)?;
+        set_parsed(&env, "MDR_THUMBNAIL_DIR", &mut config.storage.thumbnail_dir)?;
+        set_parsed(&env, "MDR_PLAYER_DIR", &mut config.storage.player_dir)?;
+        set_parsed(&env, "MDR_THUMBNAIL_SIZE", &mut config.art.thumbnail_size)?;
+        set_parsed(&env, "MDR_PLAYER_SIZE", &mut config.art.player_size)?;
+        if let Some(secret) = env("MDR_PROXY_SECRET") {
+            config.auth.proxy_secret = Some(secret);
+        }
+
+        let o = overrides.clone();
+        config.database.url = o.database_url.or(config.database.url);
+        config.database.max_connections =
+            o.max_connections.unwrap_or(config.database.max_connections);
+        config.database.connect_retries =
+            o.connect_retries.unwrap_or(config.database.connect_retries);
+        config.database.retry_delay_secs =
+            o.retry_delay_secs.unwrap_or(config.database.retry_delay_secs);
+        config.storage.coverart_dir = o.coverart_dir.unwrap_or(config.storage.coverart_dir);
+        config.storage.mp3_dir = o.mp3_dir.unwrap_or(config.storage.mp3_dir);
+        config.storage.thumbnail_dir = o.thumbnail_dir.unwrap_or(config.storage.thumbnail_dir);
+        config.storage.player_dir = o.player_dir.unwrap_or(config.storage.player_dir);
+        config.art.thumbnail_size = o.thumbnail_size.unwrap_or(config.art.thumbnail_size);
+        config.art.player_size = o.player_size.unwrap_or(config.art.player_size);
+        config.auth.proxy_secret = o.proxy_secret.or(config.auth.proxy_secret);
+
+        config.validate()?;
+        Ok(config)
+    }
+
+    pub fn validate(&self) -> Result<(), ConfigError> {
+        let invalid = |message: &str| Err(ConfigError::Invalid(message.to_string()));
+        match self.database.url.as_deref() {
+            None | Some("") => {
+                return invalid(
+                    "no database url configured, set DATABASE_URL, --database-url or database.url",
+                )
+            }
+            Some(url) if !url.starts_with("postgres://") && !url.starts_with("postgresql://") => {
+                return invalid("database url must start with postgres:// or postgresql://")
+            }
+            _ => {}
+        }
+        if self.database.max_connections == 0 {
+            return invalid("database.max_connections must be at least 1");
+        }
+        for (name, size) in [
+            ("art.thumbnail_size", self.art.thumbnail_size),
+            ("art.player_size", self.art.player_size),
+        ] {
+            if !(1..=1024).contains(&size) {
+                return Err(ConfigError::Invalid(format!(
+                    "{} must be between 1 and 1024 pixels, got {}",
+                    name, size
+                )));
+            }
+        }
+        if let Some(secret) = &self.auth.proxy_secret {
+            if secret.len() < MIN_PROXY_SECRET_LEN {
+                return Err(ConfigError::Invalid(format!(
+                    "auth.proxy_secret must be at least {} characters",
+                    MIN_PROXY_SECRET_LEN
+                )));
+            }
+        }
+        Ok(())
+    }
+
+    /// The validated database url.
+    pub fn database_url(&self) -> &str {
+        self.database.url.as_deref().unwrap_or_default()
+    }
+}
+
+fn read(path: &Path) -> Result<String, ConfigError> {
+    std::fs::read_to_string(path).map_err(|source| ConfigError::Read {
+        path: path.to_path_buf(),
+        source,
+    })
+}
+
+fn set_parsed<T: std::str::FromStr>(
+    env: &impl Fn(&str) -> Option<String>,
+    name: &'static str,
+    target: &mut T,
+) -> Result<(), ConfigError> {
+    if let Some(value) = env(name) {
+        *target = value
+            .parse()
+            .map_err(|_| ConfigError::Env { name, value })?;
+    }
+    Ok(())
+}
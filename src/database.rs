@@ -1,69 +1,83 @@
-use core::panic;
 use std::{error::Error, fs};
 
+use crate::config::{Config, DatabaseConfig};
+
 static DB: std::sync::OnceLock<sqlx::PgPool> = std::sync::OnceLock::new();
 
-async fn create_pool() -> sqlx::PgPool {
-    let database_url = std::env::var("DATABASE_URL").expect("no database url specify");
-    let mut retries = 0;
-    while retries < 5 {
+#[derive(Debug, thiserror::Error)]
+pub enum DatabaseError {
+    #[error("could not connect to the database after {attempts} attempts: {source}")]
+    Connect { attempts: u32, source: sqlx::Error },
+    #[error("database migrations failed: {0}")]
+    Migrate(#[from] sqlx::migrate::MigrateError),
+    #[error("database was already initialized")]
+    AlreadyInitialized,
+}
+
+async fn create_pool(config: &DatabaseConfig) -> Result<sqlx::PgPool, DatabaseError> {
+    let database_url = config.url.as_deref().unwrap_or_default();
+    let mut attempts = 0;
+    loop {
+        attempts += 1;
         match sqlx::postgres::PgPoolOptions::new()
-            .max_connections(4)
-            .connect(database_url.as_str())
+            .max_connections(config.max_connections)
+            .connect(database_url)
             .await
         {
             Ok(pool) => {
-                sqlx::migrate!()
-                    .run(&pool)
-                    .await
-                    .expect("migrations failed");
-                return pool;
+                sqlx::migrate!().run(&pool).await?;
+                return Ok(pool);
+            }
+            Err(source) if attempts > config.connect_retries => {
+                return Err(DatabaseError::Connect { attempts, source });
             }
             Err(e) => {
-                println!(
-                    "Could not connect to database {}, retrying {} times, error: {}",
-                    database_url, retries, e
+                eprintln!(
+                    "Could not connect to database, retrying in {}s ({}/{}): {}",
+                    config.retry_delay_secs,
+                    attempts,
+                    config.connect_retries,
+                    e
                 );
-                std::thread::sleep(std::time::Duration::from_secs(5));
-                retries += 1;
+                tokio::time::sleep(std::time::Duration::from_secs(config.retry_delay_secs)).await;
             }
         }
     }
-    panic!("Could not connect to database");
 }
 
-pub async fn init_db() -> Result<(), sqlx::Pool<sqlx::Postgres>> {
-    DB.set(create_pool().await)
+pub async fn init_db(config: &DatabaseConfig) -> Result<(), DatabaseError> {
+    DB.set(create_pool(config).await?)
+        .map_err(|_| DatabaseError::AlreadyInitialized)
 }
 
 pub fn get_db<'a>() -> &'a sqlx::PgPool {
     DB.get().expect("database unitialized")
 }
 
-pub fn init_thumbnails() -> Result<(), Box<dyn Error>> {
+pub fn init_thumbnails(config: &Config) -> Result<(), Box<dyn Error>> {
     use id3::Tag;
     use image::imageops::{resize, FilterType};
     use image::io::Reader as ImageReader;
     use std::io::Cursor;
-    use std::path::PathBuf;
 
-    let path = PathBuf::from("assets/mp3");
-    let thumbnail_path = PathBuf::from("assets/thumbnails");
-    let player_path = PathBuf::from("assets/player");
+    let path = &config.storage.mp3_dir;
+    let thumbnail_path = &config.storage.thumbnail_dir;
+    let player_path = &config.storage.player_dir;
+    let (thumbnail_size, player_size) = (config.art.thumbnail_size, config.art.player_size);
     println!("Path: {:?}", path);
 
     match thumbnail_path.is_dir() {
         true => {},
         false => {
             println!("Creating thumbnail path");
-            fs::create_dir(&thumbnail_path)?;
+            fs::create_dir(thumbnail_path)?;
         },
     };
     match player_path.is_dir() {
         true => {},
         false => {
             println!("Creating player path");
-            fs::create_dir(&player_path)?;
+            fs::create_dir(player_path)?;
         },
     };
 
@@ -88,11 +102,11 @@ pub fn init_thumbnails() -> Result<(), Box<dyn Error>> {
                     .decode()
                     .expect("Could not decode image");
 
-                let resized = resize(&img, 48, 48, FilterType::CatmullRom);
+                let resized = resize(&img, thumbnail_size, thumbnail_size, FilterType::CatmullRom);
                 let _ = resized.save(&img_thumbnail);
                 println!("Resized image with name: {:?}", img_thumbnail);
 
-                let resized = resize(&img, 80, 80, FilterType::CatmullRom);
+                let resized = resize(&img, player_size, player_size, FilterType::CatmullRom);
                 let _ = resized.save(&img_player);
                 println!("Resized image with name: {:?}", img_player);
             }
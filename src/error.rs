@@ -0,0 +1,174 @@
+use std::str::FromStr;
+
+use http::status::StatusCode;
+use leptos::ServerFnError;
+use serde::{Deserialize, Serialize};
+use thiserror::Error;
+
+/// What went wrong, independent of where. Models return it, server functions
+/// carry it to the client as `ServerFnError<AppError>` and `ErrorTemplate`
+/// renders it.
+///
+/// The message is shown to the user as is, so it is written in Dutch.
+#[derive(Debug, Clone, PartialEq, Eq, Error, Serialize, Deserialize)]
+pub enum AppError {
+    #[error("Niet gevonden: {0}")]
+    NotFound(String),
+    /// The item exists but may not be changed, e.g. a locked setlist.
+    #[error("Vergrendeld: {0}")]
+    Locked(String),
+    #[error("Ongeldig: {0}")]
+    Validation(String),
+    /// The change clashes with existing data, e.g. a duplicate name.
+    #[error("Conflict: {0}")]
+    Conflict(String),
+    /// The database failed or could not be reached.
+    #[error("Opslagfout: {0}")]
+    Storage(String),
+    #[error("Fout: {0}")]
+    Internal(String),
+}
+
+impl AppError {
+    pub fn status_code(&self) -> StatusCode {
+        match self {
+            AppError::NotFound(_) => StatusCode::NOT_FOUND,
+            AppError::Locked(_) => StatusCode::LOCKED,
+            AppError::Validation(_) => StatusCode::UNPROCESSABLE_ENTITY,
+            AppError::Conflict(_) => StatusCode::CONFLICT,
+            AppError::Storage(_) => StatusCode::SERVICE_UNAVAILABLE,
+            AppError::Internal(_) => StatusCode::INTERNAL_SERVER_ERROR,
+        }
+    }
+
+    /// Names the kind of error, for the `error-<kind>` title in the locales.
+    pub fn kind(&self) -> &'static str {
+        match self {
+            AppError::NotFound(_) => "not_found",
+            AppError::Locked(_) => "locked",
+            AppError::Validation(_) => "validation",
+            AppError::Conflict(_) => "conflict",
+            AppError::Storage(_) => "storage",
+            AppError::Internal(_) => "internal",
+        }
+    }
+
+    /// The message without the title.
+    pub fn message(&self) -> &str {
+        match self {
+            AppError::NotFound(m)
+            | AppError::Locked(m)
+            | AppError::Validation(m)
+            | AppError::Conflict(m)
+            | AppError::Storage(m)
+            | AppError::Internal(m) => m,
+        }
+    }
+
+    pub fn validation(message: impl Into<String>) -> Self {
+        AppError::Validation(message.into())
+    }
+
+    /// The status for the body of a failed server function response, which
+    /// server functions always send as `500`. Transport failures keep theirs.
+    pub fn status_for_server_fn_body(body: &str) -> Option<StatusCode> {
+        use leptos::server_fn::error::ServerFnErrorSerde;
+
+        match ServerFnError::<AppError>::de(body) {
+            ServerFnError::WrappedServerError(e) => Some(e.status_code()),
+            _ => None,
+        }
+    }
+
+    /// Takes the domain error out of a server function error; transport
+    /// failures become [`AppError::Internal`].
+    pub fn from_server_fn(error: &ServerFnError<AppError>) -> Self {
+        match error {
+            ServerFnError::WrappedServerError(e) => e.clone(),
+            e => AppError::Internal(e.to_string()),
+        }
+    }
+}
+
+/// Parses the `Display` output back, which is how server functions send the
+/// error to the client.
+impl FromStr for AppError {
+    type Err = ();
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let (title, message) = s.split_once(": ").ok_or(())?;
+        let message = message.to_string();
+        Ok(match title {
+            "Niet gevonden" => AppError::NotFound(message),
+            "Vergrendeld" => AppError::Locked(message),
+            "Ongeldig" => AppError::Validation(message),
+            "Conflict" => AppError::Conflict(message),
+            "Opslagfout" => AppError::Storage(message),
+            "Fout" => AppError::Internal(message),
+            _ => return Err(()),
+        })
+    }
+}
+
+/// Middleware giving failed server function responses the status of their
+/// [`AppError`] instead of `500`, see [`AppError::status_code`].
+#[cfg(feature = "ssr")]
+pub async fn server_fn_status(
+    request: axum::extract::Request,
+    next: axum::middleware::Next,
+) -> axum::response::Response {
+    use axum::response::IntoResponse;
+
+    let response = next.run(request).await;
+    if response.status() != StatusCode::INTERNAL_SERVER_ERROR {
+        return response;
+    }
+    let (mut parts, body) = response.into_parts();
+    let Ok(body) = axum::body::to_bytes(body, 1024 * 1024).await else {
+        return StatusCode::INTERNAL_SERVER_ERROR.into_response();
+    };
+    if let Some(status) = std::str::from_utf8(&body)
+        .ok()
+        .and_then(AppError::status_for_server_fn_body)
+    {
+        parts.status = status;
+    }
+    axum::response::Response::from_parts(parts, axum::body::Body::from(body))
+}
+
+#[cfg(feature = "ssr")]
+impl From<sqlx::Error> for AppError {
+    fn from(error: sqlx::Error) -> Self {
+        match &error {
+            sqlx::Error::RowNotFound => {
+                AppError::NotFound("dit item bestaat niet (meer)".to_string())
+            }
+            sqlx::Error::Database(e) => match e.code().as_deref() {
+                Some("23505") => AppError::Conflict("dit bestaat al".to_string()),
+                Some("23503") => AppError::Conflict(
+                    "dit wordt nog gebruikt of verwijst naar iets dat niet bestaat".to_string(),
+                ),
+                Some(code @ ("23502" | "23514" | "22P02" | "22007" | "22008")) => {
+                    log::warn!("rejected by the database: {}", e.message());
+                    AppError::Validation(
+                        match code {
+                            "23502" => "een verplicht veld is niet ingevuld",
+                            "23514" => "een waarde valt buiten wat is toegestaan",
+                            "22P02" => "een waarde heeft niet de goede vorm",
+                            _ => "een datum of tijd klopt niet",
+                        }
+                        .to_string(),
+                    )
+                }
+                _ => {
+                    log::error!("database error: {}", error);
+                    AppError::Storage("de database gaf een fout".to_string())
+                }
+            },
+            _ => {
+                log::error!("database error: {}", error);
+                AppError::Storage("de database is niet bereikbaar".to_string())
+            }
+        }
+    }
+}
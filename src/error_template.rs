@@ -1,24 +1,11 @@
 use cfg_if::cfg_if;
-use http::status::StatusCode;
 use leptos::*;
-use thiserror::Error;
 
 #[cfg(feature = "ssr")]
 use leptos_axum::ResponseOptions;
 
-#[derive(Clone, Debug, Error)]
-pub enum AppError {
-    #[error("Not Found")]
-    NotFound,
-}
-
-impl AppError {
-    pub fn status_code(&self) -> StatusCode {
-        match self {
-            AppError::NotFound => StatusCode::NOT_FOUND,
-        }
-    }
-}
+pub use crate::error::AppError;
+use crate::i18n::use_locale;
 
 // A basic function to display errors served by the error boundaries.
 // Feel free to do more complicated things here than just displaying the error.
@@ -37,38 +24,53 @@ pub fn ErrorTemplate(
     // Get Errors from Signal
     let errors = errors.get_untracked();
 
-    // Downcast lets us take a type that implements `std::error::Error`
+    // Downcast lets us take a type that implements `std::error::Error`.
+    // Server functions wrap the domain error, anything else is unexpected.
     let errors: Vec<AppError> = errors
         .into_iter()
-        .filter_map(|(_k, v)| v.downcast_ref::<AppError>().cloned())
+        .map(|(_k, v)| {
+            if let Some(e) = v.downcast_ref::<AppError>() {
+                e.clone()
+            } else if let Some(e) = v.downcast_ref::<ServerFnError<AppError>>() {
+                AppError::from_server_fn(e)
+            } else {
+                AppError::Internal(v.to_string())
+            }
+        })
         .collect();
-    println!("Errors: {errors:#?}");
+    let locale = use_locale();
 
     // Only the response code for the first error is actually sent from the server
     // this may be customized by the specific application
     cfg_if! { if #[cfg(feature="ssr")] {
         let response = use_context::<ResponseOptions>();
-        if let Some(response) = response {
-            response.set_status(errors[0].status_code());
+        if let (Some(response), Some(error)) = (response, errors.first()) {
+            response.set_status(error.status_code());
         }
     }}
 
     view! {
-      <h1>{if errors.len() > 1 { "Errors" } else { "Error" }}</h1>
-      <For
-        // a function that returns the items we're iterating over; a signal is fine
-        each=move || { errors.clone().into_iter().enumerate() }
-        // a unique key for each item as a reference
-        key=|(index, _error)| *index
-        // renders each item to a view
-        children=move |error| {
-            let error_string = error.1.to_string();
-            let error_code = error.1.status_code();
-            view! {
-              <h2>{error_code.to_string()}</h2>
-              <p>"Error: " {error_string}</p>
-            }
-        }
-      />
+      <div class="m-4">
+        <For
+          // a function that returns the items we're iterating over; a signal is fine
+          each=move || { errors.clone().into_iter().enumerate() }
+          // a unique key for each item as a reference
+          key=|(index, _error)| *index
+          // renders each item to a view
+          children=move |(_, error)| {
+              view! {
+                <h1 class="text-xl font-bold">
+                  {locale.t(&format!("error-{}", error.kind()))} " "
+                  <span class="opacity-50">{error.status_code().as_u16()}</span>
+                </h1>
+                <p class="mb-4">{error.message().to_string()}</p>
+              }
+          }
+        />
+
+        <a href="/" class="underline">
+          {locale.t("error-back")}
+        </a>
+      </div>
     }
 }
@@ -0,0 +1,36 @@
+use axum::extract::Query;
+use axum::http::header;
+use axum::response::{IntoResponse, Response};
+use axum::Extension;
+use chrono::Datelike;
+use serde::Deserialize;
+
+use crate::models::finance::YearOverview;
+use crate::repository::Repositories;
+
+#[derive(Debug, Deserialize)]
+pub struct FinanceQuery {
+    year: Option<i32>,
+}
+
+/// Serves the yearly finance overview as CSV at `/finances.csv`.
+pub async fn finance_csv(
+    Extension(repos): Extension<Repositories>,
+    Query(query): Query<FinanceQuery>,
+) -> Response {
+    let year = query.year.unwrap_or_else(|| chrono::Local::now().year());
+    match YearOverview::get(repos.band_id, year).await {
+        Ok(overview) => (
+            [
+                (header::CONTENT_TYPE, "text/csv; charset=utf-8".to_string()),
+                (
+                    header::CONTENT_DISPOSITION,
+                    format!("attachment; filename=\"financien-{}.csv\"", year),
+                ),
+            ],
+            overview.to_csv(),
+        )
+            .into_response(),
+        Err(e) => (e.status_code(), e.to_string()).into_response(),
+    }
+}
@@ -0,0 +1,179 @@
+use std::collections::BTreeMap;
+use std::fmt::Write;
+use std::sync::{Mutex, OnceLock};
+use std::time::{Duration, Instant};
+
+use axum::extract::{MatchedPath, Request};
+use axum::http::{header, StatusCode};
+use axum::middleware::Next;
+use axum::response::{IntoResponse, Response};
+
+/// Upper bounds in seconds of the latency histogram buckets.
+pub const BUCKETS: [f64; 10] = [0.005, 0.01, 0.025, 0.05, 0.1, 0.25, 0.5, 1.0, 2.5, 5.0];
+
+/// How long `/readyz` waits for the database before reporting it unreachable.
+const READY_TIMEOUT: Duration = Duration::from_secs(2);
+
+#[derive(Debug, Clone, Default, PartialEq)]
+struct RouteStats {
+    /// Requests per bucket, not cumulative; `+Inf` is the total count.
+    buckets: [u64; BUCKETS.len()],
+    count: u64,
+    sum: f64,
+    errors: u64,
+}
+
+/// Connection counts of the database pool at the time of a scrape.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct PoolStats {
+    pub size: u32,
+    pub idle: u32,
+    pub max: u32,
+}
+
+/// Request latencies and error counts per route, rendered in the Prometheus
+/// text format by `/metrics`.
+#[derive(Debug, Default)]
+pub struct Metrics {
+    routes: Mutex<BTreeMap<String, RouteStats>>,
+}
+
+impl Metrics {
+    pub fn record(&self, route: &str, elapsed: Duration, status: StatusCode) {
+        let seconds = elapsed.as_secs_f64();
+        let mut routes = self.routes.lock().unwrap();
+        let stats = routes.entry(route.to_string()).or_default();
+        if let Some(bucket) = BUCKETS.iter().position(|b| seconds <= *b) {
+            stats.buckets[bucket] += 1;
+        }
+        stats.count += 1;
+        stats.sum += seconds;
+        if status.is_server_error() {
+            stats.errors += 1;
+        }
+    }
+
+    pub fn render(&self, pool: Option<PoolStats>) -> String {
+        let routes = self.routes.lock().unwrap();
+        let mut out = String::new();
+
+        out.push_str(
+            "# HELP mdr_request_duration_seconds Request latency per route or server function.\n",
+        );
+        out.push_str("# TYPE mdr_request_duration_seconds histogram\n");
+        for (route, stats) in routes.iter() {
+            let mut cumulative = 0;
+            for (bound, count) in BUCKETS.iter().zip(stats.buckets) {
+                cumulative += count;
+                let _ = writeln!(
+                    out,
+                    "mdr_request_duration_seconds_bucket{{route=\"{}\",le=\"{}\"}} {}",
+                    route, bound, cumulative
+                );
+            }
+            let _ = writeln!(
+                out,
+                "mdr_request_duration_seconds_bucket{{route=\"{}\",le=\"+Inf\"}} {}",
+                route, stats.count
+            );
+            let _ = writeln!(
+                out,
+                "mdr_request_duration_seconds_sum{{route=\"{}\"}} {}",
+                route, stats.sum
+            );
+            let _ = writeln!(
+                out,
+                "mdr_request_duration_seconds_count{{route=\"{}\"}} {}",
+                route, stats.count
+            );
+        }
+
+        out.push_str("# HELP mdr_request_errors_total Requests answered with a 5xx status.\n");
+        out.push_str("# TYPE mdr_request_errors_total counter\n");
+        for (route, stats) in routes.iter() {
+            let _ = writeln!(
+                out,
+                "mdr_request_errors_total{{route=\"{}\"}} {}",
+                route, stats.errors
+            );
+        }
+
+        if let Some(pool) = pool {
+            out.push_str("# HELP mdr_db_pool_connections Open database connections by state.\n");
+            out.push_str("# TYPE mdr_db_pool_connections gauge\n");
+            let _ = writeln!(
+                out,
+                "mdr_db_pool_connections{{state=\"idle\"}} {}",
+                pool.idle
+            );
+            let _ = writeln!(
+                out,
+                "mdr_db_pool_connections{{state=\"in_use\"}} {}",
+                pool.size.saturating_sub(pool.idle)
+            );
+            out.push_str(
+                "# HELP mdr_db_pool_max_connections Configured size of the database pool.\n",
+            );
+            out.push_str("# TYPE mdr_db_pool_max_connections gauge\n");
+            let _ = writeln!(out, "mdr_db_pool_max_connections {}", pool.max);
+        }
+        out
+    }
+}
+
+pub fn metrics() -> &'static Metrics {
+    static METRICS: OnceLock<Metrics> = OnceLock::new();
+    METRICS.get_or_init(Metrics::default)
+}
+
+/// The label for a matched route. Server function urls end in a hash, e.g.
+/// `/api/get_gig17363…`, which is dropped to keep the name readable.
+pub fn route_label(matched: Option<&str>) -> String {
+    match matched {
+        None => "fallback".to_string(),
+        Some(path) => match path.strip_prefix("/api/") {
+            Some(function) if !function.contains('/') => function
+                .trim_end_matches(|c: char| c.is_ascii_digit())
+                .to_string(),
+            _ => path.to_string(),
+        },
+    }
+}
+
+/// Middleware recording the latency and status of every request.
+pub async fn track(matched: Option<MatchedPath>, request: Request, next: Next) -> Response {
+    let route = route_label(matched.as_ref().map(MatchedPath::as_str));
+    let start = Instant::now();
+    let response = next.run(request).await;
+    metrics().record(&route, start.elapsed(), response.status());
+    response
+}
+
+/// Liveness: the process is up and serving requests.
+pub async fn healthz() -> &'static str {
+    "ok"
+}
+
+/// Readiness: the database answers a query.
+pub async fn readyz() -> Response {
+    let ping = sqlx::query("SELECT 1").execute(crate::database::get_db());
+    match tokio::time::timeout(READY_TIMEOUT, ping).await {
+        Ok(Ok(_)) => (StatusCode::OK, "ready").into_response(),
+        Ok(Err(e)) => (StatusCode::SERVICE_UNAVAILABLE, e.to_string()).into_response(),
+        Err(_) => (StatusCode::SERVICE_UNAVAILABLE, "database timed out").into_response(),
+    }
+}
+
+pub async fn metrics_endpoint() -> Response {
+    let db = crate::database::get_db();
+    let pool = PoolStats {
+        size: db.size(),
+        idle: db.num_idle() as u32,
+        max: db.options().get_max_connections(),
+    };
+    (
+        [(header::CONTENT_TYPE, "text/plain; version=0.0.4")],
+        metrics().render(Some(pool)),
+    )
+        .into_response()
+}
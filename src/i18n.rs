@@ -0,0 +1,228 @@
+use std::str::FromStr;
+use std::sync::OnceLock;
+
+use chrono::{NaiveDate, NaiveDateTime};
+use fluent_bundle::concurrent::FluentBundle;
+use fluent_bundle::{FluentArgs, FluentResource, FluentValue};
+use serde::{Deserialize, Serialize};
+
+#[cfg(feature = "ssr")]
+use crate::error::AppError;
+#[cfg(feature = "ssr")]
+use crate::models::band::request_user;
+
+/// Name of the cookie holding the language picked in the nav by someone who
+/// isn't signed in. A signed in user's choice is stored with them instead, see
+/// [`Locale::for_user`], so it follows them to every device.
+pub const LANG_COOKIE: &str = "lang";
+
+/// A language the UI is translated to. The messages live in
+/// `locales/<code>.ftl`.
+#[derive(Debug, Deserialize, Serialize, Clone, Copy, PartialEq, Eq, Hash, Default)]
+#[serde(rename_all = "lowercase")]
+pub enum Locale {
+    #[default]
+    Nl,
+    En,
+}
+
+impl Locale {
+    pub const ALL: [Locale; 2] = [Locale::Nl, Locale::En];
+
+    pub fn code(&self) -> &'static str {
+        match self {
+            Locale::Nl => "nl",
+            Locale::En => "en",
+        }
+    }
+
+    /// The name of the language in the language itself, for the picker.
+    pub fn label(&self) -> &'static str {
+        match self {
+            Locale::Nl => "Nederlands",
+            Locale::En => "English",
+        }
+    }
+
+    fn source(&self) -> &'static str {
+        match self {
+            Locale::Nl => include_str!("../locales/nl.ftl"),
+            Locale::En => include_str!("../locales/en.ftl"),
+        }
+    }
+
+    fn bundle(&self) -> &'static FluentBundle<FluentResource> {
+        static BUNDLES: OnceLock<Vec<FluentBundle<FluentResource>>> = OnceLock::new();
+        let bundles = BUNDLES.get_or_init(|| {
+            Locale::ALL
+                .into_iter()
+                .map(|locale| {
+                    let resource = FluentResource::try_new(locale.source().to_string())
+                        .expect("the message catalogue should parse");
+                    let lang = locale.code().parse().expect("a valid language code");
+                    let mut bundle = FluentBundle::new_concurrent(vec![lang]);
+                    // The isolation marks around arguments would end up in the HTML.
+                    bundle.set_use_isolating(false);
+                    bundle
+                        .add_resource(resource)
+                        .expect("message ids should be unique");
+                    bundle
+                })
+                .collect()
+        });
+        &bundles[*self as usize]
+    }
+
+    fn format(&self, id: &str, args: Option<&FluentArgs>) -> Option<String> {
+        let bundle = self.bundle();
+        let pattern = bundle.get_message(id)?.value()?;
+        let mut errors = vec![];
+        let message = bundle.format_pattern(pattern, args, &mut errors);
+        Some(message.into_owned())
+    }
+
+    /// The message `id`, falling back to Dutch and then to the id itself when
+    /// a translation is missing.
+    pub fn t(&self, id: &str) -> String {
+        self.t_with(id, &[])
+    }
+
+    /// Like [`Locale::t`], filling in the message's `{ $name }` arguments.
+    pub fn t_with(&self, id: &str, args: &[(&str, FluentValue)]) -> String {
+        let args: FluentArgs = args.iter().cloned().collect();
+        self.format(id, Some(&args))
+            .or_else(|| Locale::default().format(id, Some(&args)))
+            .unwrap_or_else(|| id.to_string())
+    }
+
+    pub fn format_date(&self, date: NaiveDate) -> String {
+        match self {
+            Locale::Nl => date.format("%d-%m-%Y").to_string(),
+            Locale::En => date.format("%-d %b %Y").to_string(),
+        }
+    }
+
+    pub fn format_date_time(&self, at: NaiveDateTime) -> String {
+        format!("{} {}", self.format_date(at.date()), at.format("%H:%M"))
+    }
+
+    /// The language to render in: the request's on the server, see
+    /// [`pick_locale`], and when hydrating the one the server put in
+    /// `<html lang>`.
+    pub fn detect() -> Self {
+        #[cfg(feature = "ssr")]
+        {
+            leptos::use_context::<http::request::Parts>()
+                .map(|parts| match parts.extensions.get::<Locale>() {
+                    Some(locale) => *locale,
+                    None => Locale::from_headers(&parts.headers),
+                })
+                .unwrap_or_default()
+        }
+        #[cfg(not(feature = "ssr"))]
+        {
+            leptos::document()
+                .document_element()
+                .and_then(|html| html.get_attribute("lang"))
+                .and_then(|lang| lang.parse().ok())
+                .unwrap_or_default()
+        }
+    }
+
+    /// The first language of an `Accept-Language` header that has a
+    /// translation, ignoring regions like the `GB` in `en-GB`.
+    pub fn from_accept_language(header: &str) -> Option<Self> {
+        header
+            .split(',')
+            .filter_map(|lang| lang.split(';').next()?.trim().split('-').next())
+            .find_map(|lang| lang.to_lowercase().parse().ok())
+    }
+
+    /// The language picked in the nav, or else the browser's.
+    pub fn from_headers(headers: &http::HeaderMap) -> Self {
+        let picked = headers
+            .get_all(http::header::COOKIE)
+            .iter()
+            .filter_map(|v| v.to_str().ok())
+            .flat_map(|cookies| cookies.split(';'))
+            .filter_map(|cookie| cookie.trim().split_once('='))
+            .find(|(name, _)| *name == LANG_COOKIE)
+            .and_then(|(_, value)| value.parse().ok());
+        picked
+            .or_else(|| {
+                headers
+                    .get(http::header::ACCEPT_LANGUAGE)
+                    .and_then(|v| v.to_str().ok())
+                    .and_then(Locale::from_accept_language)
+            })
+            .unwrap_or_default()
+    }
+
+    /// The language the user stored, or else the one from the headers.
+    pub fn from_request(stored: Option<Locale>, headers: &http::HeaderMap) -> Self {
+        stored.unwrap_or_else(|| Locale::from_headers(headers))
+    }
+
+    /// The language `user` picked in the nav, if they ever did.
+    #[cfg(feature = "ssr")]
+    pub async fn for_user(user: &str) -> Result<Option<Self>, AppError> {
+        let language = sqlx::query_scalar!(
+            "SELECT language FROM user_settings WHERE user_name = $1",
+            user
+        )
+        .fetch_optional(crate::database::get_db())
+        .await?;
+        Ok(language.and_then(|l| l.parse().ok()))
+    }
+
+    /// Stores the language for `user`, replacing the one they picked before.
+    #[cfg(feature = "ssr")]
+    pub async fn save_for_user(&self, user: &str) -> Result<(), AppError> {
+        sqlx::query!(
+            "
+INSERT INTO user_settings (user_name, language) VALUES ($1, $2)
+ON CONFLICT (user_name) DO UPDATE SET language = EXCLUDED.language",
+            user,
+            self.code()
+        )
+        .execute(crate::database::get_db())
+        .await?;
+        Ok(())
+    }
+}
+
+/// Middleware adding the language of each request, see
+/// [`Locale::from_request`]. When the stored language can't be read, the
+/// page is still rendered in the one from the headers.
+#[cfg(feature = "ssr")]
+pub async fn pick_locale(
+    mut request: axum::extract::Request,
+    next: axum::middleware::Next,
+) -> axum::response::Response {
+    let stored = match request_user(request.headers()) {
+        Some(user) => Locale::for_user(&user).await.unwrap_or_else(|e| {
+            log::warn!("no language for {}: {}", user, e);
+            None
+        }),
+        None => None,
+    };
+    let locale = Locale::from_request(stored, request.headers());
+    request.extensions_mut().insert(locale);
+    next.run(request).await
+}
+
+impl FromStr for Locale {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Locale::ALL
+            .into_iter()
+            .find(|l| l.code() == s)
+            .ok_or_else(|| format!("Onbekende taal: {}", s))
+    }
+}
+
+/// The language of the page being rendered, as provided by the app.
+pub fn use_locale() -> Locale {
+    leptos::use_context::<Locale>().unwrap_or_default()
+}
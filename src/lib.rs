@@ -1,11 +1,29 @@
 pub mod app;
+#[cfg(feature = "ssr")]
+pub mod backup;
+#[cfg(feature = "ssr")]
+pub mod calendar;
 pub mod components;
 #[cfg(feature = "ssr")]
+pub mod config;
+#[cfg(feature = "ssr")]
 pub mod database;
+pub mod error;
 pub mod error_template;
 #[cfg(feature = "ssr")]
 pub mod fileserv;
+#[cfg(feature = "ssr")]
+pub mod finance_export;
+#[cfg(feature = "ssr")]
+pub mod health;
+pub mod i18n;
 pub mod models;
+#[cfg(feature = "ssr")]
+pub mod repository;
+#[cfg(feature = "ssr")]
+pub mod rest_api;
+#[cfg(feature = "ssr")]
+pub mod share;
 
 #[cfg(feature = "hydrate")]
 #[wasm_bindgen::prelude::wasm_bindgen]
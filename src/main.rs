@@ -1,7 +1,12 @@
+use std::path::PathBuf;
+
 use clap::{Parser, Subcommand};
 
-use my_dad_rocks::database::init_db;
-use my_dad_rocks::models::song::Song;
+use my_dad_rocks::backup::Archive;
+use my_dad_rocks::config::{Config, ConfigOverrides};
+use my_dad_rocks::database::{init_db, init_thumbnails};
+use my_dad_rocks::models::band::{trust_proxy, Band};
+use my_dad_rocks::repository::Repositories;
 
 /// Simple program to greet a person
 #[derive(Parser)]
@@ -9,12 +14,29 @@ use my_dad_rocks::models::song::Song;
 struct Cli {
     #[command(subcommand)]
     command: Commands,
+    #[command(flatten)]
+    config: ConfigOverrides,
 }
 
 #[derive(Subcommand)]
 enum Commands {
     Api,
+    /// Download cover art, and cut thumbnails from the mp3 artwork when the
+    /// mp3 directory exists.
     Generate,
+    /// Write all band data to a JSON archive.
+    Export {
+        /// File to write to instead of stdout.
+        #[arg(short, long)]
+        output: Option<PathBuf>,
+    },
+    /// Restore a JSON archive into an empty or freshly migrated database.
+    Import {
+        file: PathBuf,
+        /// Replace the data already in the database.
+        #[arg(long)]
+        force: bool,
+    },
 }
 
 #[cfg(feature = "ssr")]
@@ -22,31 +44,68 @@ enum Commands {
 async fn main() -> Result<(), Box<dyn std::error::Error>> {
     let cli = Cli::parse();
 
+    let config = Config::load(&cli.config).unwrap_or_else(|e| {
+        eprintln!("Invalid configuration: {}", e);
+        std::process::exit(2);
+    });
+    trust_proxy(config.auth.proxy_secret.clone());
+    if let Err(e) = init_db(&config.database).await {
+        eprintln!("{}", e);
+        std::process::exit(1);
+    }
+
     match &cli.command {
         Commands::Api => {
             run_api().await;
         }
         Commands::Generate => {
-            generate_thumbnails().await?;
+            generate_thumbnails(&config).await?;
+            if config.storage.mp3_dir.is_dir() {
+                init_thumbnails(&config)?;
+            }
+        }
+        Commands::Export { output } => {
+            export(output.as_ref()).await?;
+        }
+        Commands::Import { file, force } => {
+            import(file, *force).await?;
         }
     }
     Ok(())
 }
 
-async fn generate_thumbnails() -> Result<(), Box<dyn std::error::Error>> {
-    use std::path::Path;
+async fn export(output: Option<&PathBuf>) -> Result<(), Box<dyn std::error::Error>> {
+    let archive = serde_json::to_string_pretty(&Archive::export().await?)?;
+    match output {
+        Some(path) => std::fs::write(path, archive)?,
+        None => println!("{}", archive),
+    }
+    Ok(())
+}
 
-    let _ = init_db().await;
-    let songs = Song::get_all().await?;
+async fn import(file: &PathBuf, force: bool) -> Result<(), Box<dyn std::error::Error>> {
+    let archive: Archive = serde_json::from_str(&std::fs::read_to_string(file)?)?;
+    archive.import(force).await?;
+    for table in &archive.tables {
+        println!("{}: {} rows", table.name, table.rows.len());
+    }
+    Ok(())
+}
+
+async fn generate_thumbnails(config: &Config) -> Result<(), Box<dyn std::error::Error>> {
+    let mut songs = Vec::default();
+    for band in Band::get_all().await? {
+        songs.extend(Repositories::postgres(band.id).songs.get_all().await?);
+    }
     for song in songs.into_iter() {
         if let Some(mid) = song.release_mid {
             print!(
                 "Downloading art for {} with release id {}",
                 song.title, &mid
             );
-            let path = format!("public/coverart/{}.jpeg", &mid);
-            if Path::new(&path).exists() {
-                print!("\t [Exists]\n");
+            let path = config.storage.coverart_dir.join(format!("{}.jpeg", &mid));
+            if path.exists() {
+                println!("\t [Exists]");
                 continue;
             }
 
@@ -66,7 +125,7 @@ async fn generate_thumbnails() -> Result<(), Box<dyn std::error::Error>> {
             ]
             .contains(&mid)
             {
-                print!("\t [Skip]\n");
+                println!("\t [Skip]");
                 continue;
             }
 
@@ -81,7 +140,7 @@ async fn generate_thumbnails() -> Result<(), Box<dyn std::error::Error>> {
     Ok(())
 }
 
-async fn download_art(release_id: String, path: String) -> Result<(), Box<dyn std::error::Error>> {
+async fn download_art(release_id: String, path: PathBuf) -> Result<(), Box<dyn std::error::Error>> {
     use musicbrainz_rs::entity::CoverartResponse;
     use musicbrainz_rs::prelude::*;
 
@@ -107,16 +166,21 @@ async fn download_art(release_id: String, path: String) -> Result<(), Box<dyn st
 
 #[cfg(feature = "ssr")]
 async fn run_api() {
-    use axum::Router;
+    use axum::{routing::get, Router};
     use leptos::*;
     use leptos_axum::{generate_route_list, LeptosRoutes};
     use my_dad_rocks::app::*;
-    use my_dad_rocks::database::init_db;
+    use my_dad_rocks::calendar::calendar_feed;
+    use my_dad_rocks::error::server_fn_status;
     use my_dad_rocks::fileserv::file_and_error_handler;
+    use my_dad_rocks::finance_export::finance_csv;
+    use my_dad_rocks::health::{healthz, metrics_endpoint, readyz, track};
+    use my_dad_rocks::i18n::pick_locale;
+    use my_dad_rocks::repository::scope_to_band;
+    use my_dad_rocks::rest_api;
+    use my_dad_rocks::share::shared_gig_json;
     use tower_http::cors::{Any, CorsLayer};
 
-    let _ = init_db().await;
-
     simple_logger::init_with_level(log::Level::Info).expect("couldn't initialize logging");
 
     let conf = get_configuration(None).await.unwrap();
@@ -127,9 +191,22 @@ async fn run_api() {
         .allow_methods([http::Method::GET, http::Method::POST])
         .allow_origin(Any);
 
+    // Everything above the band layer works on the data of the band picked
+    // for the request; the routes below it don't need one.
     let app = Router::new()
+        .route("/calendar.ics", get(calendar_feed))
+        .route("/finances.csv", get(finance_csv))
+        .nest("/api/v1", rest_api::routes())
         .leptos_routes(&leptos_options, routes, App)
+        .layer(axum::middleware::from_fn(scope_to_band))
+        .layer(axum::middleware::from_fn(pick_locale))
+        .route("/healthz", get(healthz))
+        .route("/readyz", get(readyz))
+        .route("/metrics", get(metrics_endpoint))
+        .route("/gedeeld/:token/setlist.json", get(shared_gig_json))
         .fallback(file_and_error_handler)
+        .layer(axum::middleware::from_fn(server_fn_status))
+        .layer(axum::middleware::from_fn(track))
         .layer(cors)
         .with_state(leptos_options);
 
@@ -1,3 +1,20 @@
+pub mod annotation;
+pub mod audit;
+pub mod band;
+pub mod calendar_token;
+pub mod finance;
 pub mod gig;
+pub mod gig_share;
+pub mod gig_template;
+pub mod key;
+pub mod member;
+pub mod money;
+pub mod rehearsal;
+pub mod section;
 pub mod setlist;
+pub mod setlist_generator;
 pub mod song;
+pub mod song_revision;
+pub mod song_role;
+pub mod tag;
+pub mod venue;
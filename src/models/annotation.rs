@@ -0,0 +1,173 @@
+use chrono::NaiveDateTime;
+use serde::{Deserialize, Serialize};
+
+#[cfg(feature = "ssr")]
+use crate::error::AppError;
+#[cfg(feature = "ssr")]
+use crate::repository::Repositories;
+
+/// Lines that look at least this much alike still count as the same line
+/// after an edit.
+const MIN_SIMILARITY: f64 = 0.6;
+
+/// A member's note on a line of the lyrics, like "drums out for 4 bars".
+#[derive(Debug, Deserialize, Serialize, Clone, PartialEq, Eq)]
+pub struct Annotation {
+    pub id: i32,
+    pub song_id: i32,
+    pub member_id: i32,
+    pub member: String,
+    /// Index of the line in the lyrics, counting empty lines.
+    pub line: i32,
+    /// The text of that line when the note was pinned to it.
+    pub anchor: String,
+    pub body: String,
+    pub created_at: NaiveDateTime,
+}
+
+/// An annotation about to be stored.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct NewAnnotation {
+    pub song_id: i32,
+    pub member_id: i32,
+    pub line: i32,
+    pub anchor: String,
+    pub body: String,
+}
+
+/// Lowercase words without punctuation, so "Hold on!" and "hold on" match.
+/// Apostrophes are dropped rather than splitting words, as in "drivin'".
+fn normalize(line: &str) -> String {
+    line.chars()
+        .filter(|c| !matches!(c, '\'' | '’'))
+        .map(|c| match c.is_alphanumeric() {
+            true => c.to_lowercase().next().unwrap_or(c),
+            false => ' ',
+        })
+        .collect::<String>()
+        .split_whitespace()
+        .collect::<Vec<_>>()
+        .join(" ")
+}
+
+/// How alike two lines are, from 0 to 1, by edit distance.
+pub fn similarity(a: &str, b: &str) -> f64 {
+    let a: Vec<char> = normalize(a).chars().collect();
+    let b: Vec<char> = normalize(b).chars().collect();
+    if a.is_empty() || b.is_empty() {
+        return match a.len() == b.len() {
+            true => 1.0,
+            false => 0.0,
+        };
+    }
+    let mut previous: Vec<usize> = (0..=b.len()).collect();
+    for (i, ca) in a.iter().enumerate() {
+        let mut current = vec![i + 1; b.len() + 1];
+        for (j, cb) in b.iter().enumerate() {
+            let substitution = previous[j] + usize::from(ca != cb);
+            current[j + 1] = substitution.min(previous[j + 1] + 1).min(current[j] + 1);
+        }
+        previous = current;
+    }
+    1.0 - previous[b.len()] as f64 / a.len().max(b.len()) as f64
+}
+
+/// Where a note pinned to `anchor` at `line` belongs in `lines`: the same
+/// line if it is unchanged, else the nearest identical line, else the most
+/// similar line. `None` when no line is close enough.
+pub fn find_anchor(lines: &[&str], line: usize, anchor: &str) -> Option<usize> {
+    if lines.get(line) == Some(&anchor) {
+        return Some(line);
+    }
+    let distance = |index: usize| index.abs_diff(line);
+    if let Some(index) = (0..lines.len())
+        .filter(|i| lines[*i] == anchor)
+        .min_by_key(|i| distance(*i))
+    {
+        return Some(index);
+    }
+    lines
+        .iter()
+        .enumerate()
+        .filter(|(_, text)| !text.trim().is_empty())
+        .map(|(index, text)| (index, similarity(text, anchor)))
+        .filter(|(_, score)| *score >= MIN_SIMILARITY)
+        // Highest score, nearest line on a tie.
+        .max_by(|(a, sa), (b, sb)| sa.total_cmp(sb).then(distance(*b).cmp(&distance(*a))))
+        .map(|(index, _)| index)
+}
+
+impl Annotation {
+    /// The annotations per line of `lyrics`, and those whose line is gone.
+    pub fn place(
+        annotations: Vec<Annotation>,
+        lyrics: &str,
+    ) -> (Vec<Vec<Annotation>>, Vec<Annotation>) {
+        let lines: Vec<&str> = lyrics.split('\n').collect();
+        let mut placed = vec![Vec::default(); lines.len()];
+        let mut loose = Vec::default();
+        for annotation in annotations {
+            match find_anchor(&lines, annotation.line.max(0) as usize, &annotation.anchor) {
+                Some(index) => placed[index].push(annotation),
+                None => loose.push(annotation),
+            }
+        }
+        (placed, loose)
+    }
+
+    /// Pins a note to a line of the song's lyrics as they are now.
+    #[cfg(feature = "ssr")]
+    pub async fn create(
+        repos: &Repositories,
+        song_id: i32,
+        member_id: i32,
+        line: i32,
+        body: String,
+    ) -> Result<i32, AppError> {
+        let body = body.trim().to_string();
+        if body.is_empty() {
+            return Err(AppError::validation("Vul een notitie in"));
+        }
+        let lyrics = repos.songs.get(song_id).await?.lyrics;
+        let anchor = usize::try_from(line)
+            .ok()
+            .and_then(|line| lyrics.split('\n').nth(line))
+            .filter(|text| !text.trim().is_empty())
+            .ok_or_else(|| AppError::validation("Kies een regel met tekst"))?;
+        repos
+            .annotations
+            .create(NewAnnotation {
+                song_id,
+                member_id,
+                line,
+                anchor: anchor.to_string(),
+                body,
+            })
+            .await
+    }
+
+    /// Moves the song's annotations along with edited lyrics. Notes whose
+    /// line can't be found anymore keep their old anchor.
+    #[cfg(feature = "ssr")]
+    pub async fn reanchor(
+        repos: &Repositories,
+        song_id: i32,
+        lyrics: &str,
+    ) -> Result<(), AppError> {
+        let lines: Vec<&str> = lyrics.split('\n').collect();
+        for annotation in repos.annotations.get_for_song(song_id).await? {
+            let Some(index) =
+                find_anchor(&lines, annotation.line.max(0) as usize, &annotation.anchor)
+            else {
+                continue;
+            };
+            if index as i32 != annotation.line || lines[index] != annotation.anchor {
+                repos
+                    .annotations
+                    .set_anchor(annotation.id, index as i32, lines[index].to_string())
+                    .await?;
+            }
+        }
+        Ok(())
+    }
+}
@@ -0,0 +1,385 @@
+use std::str::FromStr;
+
+use chrono::NaiveDateTime;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+
+use crate::error::AppError;
+use crate::i18n::Locale;
+use crate::models::band::user_from_headers;
+#[cfg(feature = "ssr")]
+use crate::repository::Repositories;
+
+/// Recorded when there is no better way to tell who made a change.
+pub const UNKNOWN_ACTOR: &str = "onbekend";
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Deserialize, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum AuditEntity {
+    Song,
+    Gig,
+    Setlist,
+}
+
+impl AuditEntity {
+    pub const ALL: [AuditEntity; 3] = [AuditEntity::Song, AuditEntity::Gig, AuditEntity::Setlist];
+
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            AuditEntity::Song => "song",
+            AuditEntity::Gig => "gig",
+            AuditEntity::Setlist => "setlist",
+        }
+    }
+}
+
+impl FromStr for AuditEntity {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        AuditEntity::ALL
+            .into_iter()
+            .find(|e| e.as_str() == s)
+            .ok_or_else(|| format!("Unknown audit entity: {}", s))
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Deserialize, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum AuditAction {
+    Create,
+    Update,
+    Delete,
+    Restore,
+    /// All songs taken off a setlist.
+    Clear,
+    /// A whole running order replaced, e.g. by the setlist generator.
+    Replace,
+    Undo,
+}
+
+impl AuditAction {
+    pub const ALL: [AuditAction; 7] = [
+        AuditAction::Create,
+        AuditAction::Update,
+        AuditAction::Delete,
+        AuditAction::Restore,
+        AuditAction::Clear,
+        AuditAction::Replace,
+        AuditAction::Undo,
+    ];
+
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            AuditAction::Create => "create",
+            AuditAction::Update => "update",
+            AuditAction::Delete => "delete",
+            AuditAction::Restore => "restore",
+            AuditAction::Clear => "clear",
+            AuditAction::Replace => "replace",
+            AuditAction::Undo => "undo",
+        }
+    }
+
+    /// Actions that throw data away and can be undone.
+    pub fn is_destructive(&self) -> bool {
+        matches!(
+            self,
+            AuditAction::Delete | AuditAction::Clear | AuditAction::Replace
+        )
+    }
+}
+
+impl FromStr for AuditAction {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        AuditAction::ALL
+            .into_iter()
+            .find(|a| a.as_str() == s)
+            .ok_or_else(|| format!("Unknown audit action: {}", s))
+    }
+}
+
+/// A change about to be written to the audit log.
+#[derive(Debug, Clone, PartialEq)]
+pub struct NewAuditEntry {
+    pub actor: String,
+    pub entity: AuditEntity,
+    pub entity_id: i32,
+    pub action: AuditAction,
+    pub before: Option<Value>,
+    pub after: Option<Value>,
+}
+
+impl NewAuditEntry {
+    pub fn new<T: Serialize>(
+        actor: &str,
+        entity: AuditEntity,
+        entity_id: i32,
+        action: AuditAction,
+        before: Option<&T>,
+        after: Option<&T>,
+    ) -> Self {
+        Self {
+            actor: actor.to_string(),
+            entity,
+            entity_id,
+            action,
+            before: before.and_then(|v| serde_json::to_value(v).ok()),
+            after: after.and_then(|v| serde_json::to_value(v).ok()),
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct AuditEntry {
+    pub id: i32,
+    pub created_at: NaiveDateTime,
+    pub actor: String,
+    pub entity: AuditEntity,
+    pub entity_id: i32,
+    pub action: AuditAction,
+    pub before: Option<Value>,
+    pub after: Option<Value>,
+    pub undone_at: Option<NaiveDateTime>,
+}
+
+impl AuditEntry {
+    pub fn can_undo(&self) -> bool {
+        self.action.is_destructive() && self.undone_at.is_none()
+    }
+
+    /// What was changed, e.g. "Optreden Paradiso (01-06-2024)".
+    pub fn subject(&self, locale: Locale) -> String {
+        let field = |name: &str| {
+            [&self.after, &self.before]
+                .into_iter()
+                .flatten()
+                .find_map(|v| v.get(name).and_then(Value::as_str).map(str::to_string))
+        };
+        let name = match self.entity {
+            AuditEntity::Gig => field("venue").map(|venue| match field("date") {
+                Some(date) => match chrono::NaiveDate::parse_from_str(&date, "%Y-%m-%d") {
+                    Ok(date) => format!("{} ({})", venue, locale.format_date(date)),
+                    Err(_) => venue,
+                },
+                None => venue,
+            }),
+            AuditEntity::Song | AuditEntity::Setlist => field("title"),
+        };
+        format!(
+            "{} {}",
+            locale.t(&format!("audit-entity-{}", self.entity.as_str())),
+            name.unwrap_or_else(|| format!("#{}", self.entity_id))
+        )
+    }
+
+    /// What happened, e.g. "Optreden Paradiso (01-06-2024) verwijderd".
+    pub fn summary(&self, locale: Locale) -> String {
+        format!(
+            "{} {}",
+            self.subject(locale),
+            locale.t(&format!("audit-action-{}", self.action.as_str()))
+        )
+    }
+
+    /// The top level fields that differ between before and after, with their
+    /// old and new values.
+    pub fn changed_fields(&self) -> Vec<(String, Option<Value>, Option<Value>)> {
+        let object = |value: &Option<Value>| match value {
+            Some(Value::Object(map)) => map.clone(),
+            _ => serde_json::Map::default(),
+        };
+        let (before, after) = (object(&self.before), object(&self.after));
+        let mut keys: Vec<&String> = before.keys().chain(after.keys()).collect();
+        keys.sort();
+        keys.dedup();
+        keys.into_iter()
+            .filter(|key| before.get(*key) != after.get(*key))
+            .map(|key| (key.clone(), before.get(key).cloned(), after.get(key).cloned()))
+            .collect()
+    }
+
+    /// The running order before the change, for gigs and setlists.
+    pub fn songs_before(&self) -> Result<Vec<i32>, AppError> {
+        self.songs(self.before.as_ref())
+    }
+
+    /// The songs right after the change, to check nothing changed since.
+    pub fn songs_after(&self) -> Result<Vec<i32>, AppError> {
+        self.songs(self.after.as_ref())
+    }
+
+    fn songs(&self, state: Option<&Value>) -> Result<Vec<i32>, AppError> {
+        state
+            .and_then(|v| v.get("songs"))
+            .and_then(|songs| serde_json::from_value(songs.clone()).ok())
+            .ok_or_else(|| AppError::Internal(format!("{} heeft geen nummers", self.summary(Locale::Nl))))
+    }
+
+    /// Fails when the songs were changed again after this entry, as undoing
+    /// it would throw those changes away.
+    #[cfg(feature = "ssr")]
+    fn check_unchanged_since(&self, songs: &[i32]) -> Result<(), AppError> {
+        match self.songs_after()? == songs {
+            true => Ok(()),
+            false => Err(AppError::Conflict(format!(
+                "{} is daarna nog aangepast, dus ongedaan maken zou die wijzigingen wissen",
+                self.summary(Locale::Nl)
+            ))),
+        }
+    }
+
+    /// Writes a change by the current actor to the audit log.
+    #[cfg(feature = "ssr")]
+    pub async fn record<T: Serialize + Sync>(
+        repos: &Repositories,
+        entity: AuditEntity,
+        entity_id: i32,
+        action: AuditAction,
+        before: Option<&T>,
+        after: Option<&T>,
+    ) -> Result<(), AppError> {
+        repos
+            .audit
+            .record(NewAuditEntry::new(
+                &current_actor(),
+                entity,
+                entity_id,
+                action,
+                before,
+                after,
+            ))
+            .await
+    }
+
+    /// Records a change to a gig, reading the gig as it is now. A deleted gig
+    /// has no state after.
+    #[cfg(feature = "ssr")]
+    pub async fn gig_changed(
+        repos: &Repositories,
+        action: AuditAction,
+        before: &crate::models::gig::GigModel,
+    ) -> Result<(), AppError> {
+        let after = repos.gigs.get(before.id).await.ok();
+        Self::record(
+            repos,
+            AuditEntity::Gig,
+            before.id,
+            action,
+            Some(before),
+            after.as_ref(),
+        )
+        .await
+    }
+
+    #[cfg(feature = "ssr")]
+    pub async fn song_changed(
+        repos: &Repositories,
+        before: &crate::models::song::Song,
+    ) -> Result<(), AppError> {
+        let after = repos.songs.get(before.id).await?;
+        Self::record(
+            repos,
+            AuditEntity::Song,
+            before.id,
+            AuditAction::Update,
+            Some(before),
+            Some(&after),
+        )
+        .await
+    }
+
+    #[cfg(feature = "ssr")]
+    pub async fn setlist_changed(
+        repos: &Repositories,
+        action: AuditAction,
+        before: &crate::models::setlist::Setlist,
+    ) -> Result<(), AppError> {
+        let after = repos.setlists.get(before.id).await?;
+        Self::record(
+            repos,
+            AuditEntity::Setlist,
+            before.id,
+            action,
+            Some(before),
+            Some(&after),
+        )
+        .await
+    }
+
+    /// Reverts the newest destructive change that hasn't been undone yet and
+    /// returns it, or `None` when there is nothing to undo. Refuses with
+    /// [`AppError::Conflict`] when the gig or setlist changed since.
+    #[cfg(feature = "ssr")]
+    pub async fn undo_last(repos: &Repositories) -> Result<Option<AuditEntry>, AppError> {
+        let Some(entry) = repos.audit.last_undoable().await? else {
+            return Ok(None);
+        };
+        let id = entry.entity_id;
+        match (entry.entity, entry.action) {
+            (AuditEntity::Gig, AuditAction::Delete) => {
+                if repos.gigs.get(id).await.is_ok() {
+                    return Err(AppError::Conflict(format!(
+                        "{} is al teruggezet",
+                        entry.summary(Locale::Nl)
+                    )));
+                }
+                repos.gigs.restore(id).await?
+            }
+            (AuditEntity::Gig, AuditAction::Replace) => {
+                entry.check_unchanged_since(&repos.gigs.get(id).await?.songs)?;
+                repos.gigs.set_songs(id, &entry.songs_before()?).await?
+            }
+            (AuditEntity::Setlist, AuditAction::Clear | AuditAction::Replace) => {
+                entry.check_unchanged_since(&repos.setlists.get(id).await?.songs)?;
+                repos.setlists.set_songs(id, &entry.songs_before()?).await?
+            }
+            _ => {
+                return Err(AppError::Internal(format!(
+                    "{} kan niet ongedaan gemaakt worden",
+                    entry.summary(Locale::Nl)
+                )))
+            }
+        }
+        repos.audit.mark_undone(entry.id).await?;
+        repos
+            .audit
+            .record(NewAuditEntry {
+                actor: current_actor(),
+                entity: entry.entity,
+                entity_id: id,
+                action: AuditAction::Undo,
+                before: entry.after.clone(),
+                after: entry.before.clone(),
+            })
+            .await?;
+        Ok(Some(entry))
+    }
+}
+
+/// Who is making a request. The app has no logins, so this is the user a
+/// trusted proxy in front of it authenticated, see [`user_from_headers`], or
+/// else the client's address.
+pub fn actor_from_headers(headers: &http::HeaderMap, proxy_secret: Option<&str>) -> String {
+    if let Some(user) = user_from_headers(headers, proxy_secret) {
+        return user;
+    }
+    ["fly-client-ip", "x-forwarded-for"]
+        .into_iter()
+        .filter_map(|name| headers.get(name)?.to_str().ok())
+        .filter_map(|value| value.split(',').next())
+        .map(str::trim)
+        .find(|value| !value.is_empty())
+        .unwrap_or(UNKNOWN_ACTOR)
+        .to_string()
+}
+
+/// The actor of the server function being handled.
+#[cfg(feature = "ssr")]
+pub fn current_actor() -> String {
+    leptos::use_context::<http::request::Parts>()
+        .map(|parts| actor_from_headers(&parts.headers, crate::models::band::proxy_secret()))
+        .unwrap_or_else(|| UNKNOWN_ACTOR.to_string())
+}
@@ -0,0 +1,345 @@
+use std::str::FromStr;
+
+use serde::{Deserialize, Serialize};
+
+use crate::error::AppError;
+
+/// Name of the cookie holding the band picked in the nav.
+pub const BAND_COOKIE: &str = "band";
+/// Lets scripts using the JSON API pick a band without a cookie.
+pub const BAND_HEADER: &str = "x-band";
+/// Carries `auth.proxy_secret` from the proxy in front of the app, proving
+/// the user headers come from it and not from the client.
+pub const PROXY_SECRET_HEADER: &str = "x-proxy-secret";
+
+#[cfg(feature = "ssr")]
+static PROXY_SECRET: std::sync::OnceLock<Option<String>> = std::sync::OnceLock::new();
+
+#[derive(Debug, Deserialize, Serialize, Clone, Copy, PartialEq, Eq, Hash, Default)]
+#[serde(rename_all = "lowercase")]
+pub enum BandRole {
+    /// Can also decide who is in the band.
+    Admin,
+    #[default]
+    Member,
+}
+
+impl BandRole {
+    pub const ALL: [BandRole; 2] = [BandRole::Admin, BandRole::Member];
+
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            BandRole::Admin => "admin",
+            BandRole::Member => "member",
+        }
+    }
+}
+
+impl FromStr for BandRole {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        BandRole::ALL
+            .into_iter()
+            .find(|r| r.as_str() == s)
+            .ok_or_else(|| format!("Onbekende rol: {}", s))
+    }
+}
+
+/// A band using the app. All songs, setlists, gigs and the rest belong to
+/// exactly one band.
+#[derive(Debug, Deserialize, Serialize, Clone, PartialEq, Eq, Hash)]
+pub struct Band {
+    pub id: i32,
+    pub name: String,
+}
+
+/// A user who may open a band, by the name the proxy in front of the app
+/// passes on.
+#[derive(Debug, Deserialize, Serialize, Clone, PartialEq, Eq, Hash)]
+pub struct Membership {
+    pub band_id: i32,
+    pub user_name: String,
+    pub role: BandRole,
+}
+
+/// The bands a user can switch between and the one they're in now.
+#[derive(Debug, Deserialize, Serialize, Clone, PartialEq, Eq, Default)]
+pub struct BandChoice {
+    pub current: Option<Band>,
+    pub bands: Vec<Band>,
+}
+
+/// The user a proxy in front of the app authenticated, if any. The user
+/// headers are only trusted when the request also carries the proxy's
+/// `secret`; without a secret configured every request is anonymous, as
+/// anyone can send these headers when the app is reached directly.
+pub fn user_from_headers(headers: &http::HeaderMap, secret: Option<&str>) -> Option<String> {
+    let sent = headers.get(PROXY_SECRET_HEADER)?.as_bytes();
+    let secret = secret.filter(|s| !s.is_empty())?.as_bytes();
+    // Compares every byte so the time taken doesn't give the secret away.
+    let matches = sent.len() == secret.len()
+        && sent
+            .iter()
+            .zip(secret)
+            .fold(0, |diff, (a, b)| diff | (a ^ b))
+            == 0;
+    if !matches {
+        return None;
+    }
+    ["x-forwarded-user", "remote-user"]
+        .into_iter()
+        .filter_map(|name| headers.get(name)?.to_str().ok())
+        .map(str::trim)
+        .find(|value| !value.is_empty())
+        .map(str::to_string)
+}
+
+/// Sets the secret from `auth.proxy_secret` that user headers need to be
+/// trusted, once at startup.
+#[cfg(feature = "ssr")]
+pub fn trust_proxy(secret: Option<String>) {
+    let _ = PROXY_SECRET.set(secret);
+}
+
+/// The secret set by [`trust_proxy`], if any.
+#[cfg(feature = "ssr")]
+pub fn proxy_secret() -> Option<&'static str> {
+    PROXY_SECRET.get().and_then(Option::as_deref)
+}
+
+/// [`user_from_headers`] with the configured proxy secret.
+#[cfg(feature = "ssr")]
+pub fn request_user(headers: &http::HeaderMap) -> Option<String> {
+    user_from_headers(headers, proxy_secret())
+}
+
+/// The band id asked for by the `x-band` header or else the `band` cookie.
+pub fn requested_band(headers: &http::HeaderMap) -> Option<i32> {
+    if let Some(band) = headers.get(BAND_HEADER).and_then(|v| v.to_str().ok()) {
+        return band.trim().parse().ok();
+    }
+    headers
+        .get_all(http::header::COOKIE)
+        .iter()
+        .filter_map(|v| v.to_str().ok())
+        .flat_map(|cookies| cookies.split(';'))
+        .filter_map(|cookie| cookie.trim().split_once('='))
+        .find(|(name, _)| *name == BAND_COOKIE)
+        .and_then(|(_, value)| value.parse().ok())
+}
+
+/// The user of the server function being handled.
+#[cfg(feature = "ssr")]
+pub fn current_user() -> Option<String> {
+    leptos::use_context::<http::request::Parts>().and_then(|parts| request_user(&parts.headers))
+}
+
+impl Membership {
+    /// Whether `user` may open a band with these memberships. Like calendar
+    /// tokens, a band is open to everyone until it has its first member.
+    pub fn may_open(memberships: &[Membership], user: Option<&str>) -> bool {
+        memberships.is_empty()
+            || memberships
+                .iter()
+                .any(|m| Some(m.user_name.as_str()) == user)
+    }
+
+    /// Whether `user` may change who is in the band.
+    pub fn may_manage(memberships: &[Membership], user: Option<&str>) -> bool {
+        memberships.is_empty()
+            || memberships
+                .iter()
+                .any(|m| Some(m.user_name.as_str()) == user && m.role == BandRole::Admin)
+    }
+
+    /// Checks a band may be made. Only the first band may be made without a
+    /// user: every band after it starts with its maker as admin, so it isn't
+    /// open to everyone.
+    pub fn check_create(existing_bands: i64, user: Option<&str>) -> Result<(), AppError> {
+        match existing_bands == 0 || user.is_some() {
+            true => Ok(()),
+            false => Err(AppError::Locked(
+                "log in via de proxy om nog een band te maken".to_string(),
+            )),
+        }
+    }
+
+    /// Checks the band keeps an admin after `change`, so nobody locks the
+    /// band out of its own memberships.
+    pub fn check_admin_left(after: &[Membership]) -> Result<(), AppError> {
+        match after.is_empty() || after.iter().any(|m| m.role == BandRole::Admin) {
+            true => Ok(()),
+            false => Err(AppError::validation(
+                "Een band met leden heeft minstens één beheerder nodig",
+            )),
+        }
+    }
+}
+
+impl Band {
+    /// The band to work in: the requested one when the user may open it,
+    /// otherwise the oldest they may open, so adding a band doesn't move
+    /// everyone without a cookie over to it.
+    pub fn pick(accessible: Vec<Band>, requested: Option<i32>) -> Option<Band> {
+        let oldest = accessible.iter().map(|b| b.id).min();
+        let id = match accessible.iter().any(|b| Some(b.id) == requested) {
+            true => requested,
+            false => oldest,
+        };
+        accessible.into_iter().find(|b| Some(b.id) == id)
+    }
+
+    #[cfg(feature = "ssr")]
+    pub async fn get_all() -> Result<Vec<Self>, AppError> {
+        sqlx::query_as!(Band, "SELECT id, name FROM bands ORDER BY name ASC")
+            .fetch_all(crate::database::get_db())
+            .await
+            .map_err(AppError::from)
+    }
+
+    /// The bands `user` may open, see [`Membership::may_open`].
+    #[cfg(feature = "ssr")]
+    pub async fn get_accessible(user: Option<&str>) -> Result<Vec<Self>, AppError> {
+        sqlx::query_as!(
+            Band,
+            "
+SELECT b.id, b.name FROM bands b
+WHERE NOT EXISTS (SELECT 1 FROM band_memberships m WHERE m.band_id = b.id)
+  OR EXISTS (SELECT 1 FROM band_memberships m WHERE m.band_id = b.id AND m.user_name = $1)
+ORDER BY b.name ASC",
+            user
+        )
+        .fetch_all(crate::database::get_db())
+        .await
+        .map_err(AppError::from)
+    }
+
+    /// Picks the band for a request, see [`Band::pick`].
+    #[cfg(feature = "ssr")]
+    pub async fn resolve(requested: Option<i32>, user: Option<&str>) -> Result<Self, AppError> {
+        Band::pick(Band::get_accessible(user).await?, requested)
+            .ok_or_else(|| AppError::NotFound("geen band waar je lid van bent".to_string()))
+    }
+
+    /// Adds a band with its own practice setlist. The user making it becomes
+    /// its admin, see [`Membership::check_create`].
+    #[cfg(feature = "ssr")]
+    pub async fn create(name: String, user: Option<&str>) -> Result<i32, AppError> {
+        let name = name.trim().to_string();
+        if name.is_empty() {
+            return Err(AppError::validation("Vul een naam in"));
+        }
+        let mut tx = crate::database::get_db().begin().await?;
+        let existing_bands = sqlx::query_scalar!(r#"SELECT COUNT(*) as "count!" FROM bands"#)
+            .fetch_one(&mut *tx)
+            .await?;
+        Membership::check_create(existing_bands, user)?;
+
+        let band_id = sqlx::query!("INSERT INTO bands (name) VALUES ($1) RETURNING id", name)
+            .fetch_one(&mut *tx)
+            .await?
+            .id;
+        sqlx::query!(
+            "INSERT INTO setlists (title, songs, band_id) VALUES ('Oefenen', '{}', $1)",
+            band_id
+        )
+        .execute(&mut *tx)
+        .await?;
+        if let Some(user) = user {
+            sqlx::query!(
+                "INSERT INTO band_memberships (band_id, user_name, role) VALUES ($1, $2, $3)",
+                band_id,
+                user,
+                BandRole::Admin.as_str()
+            )
+            .execute(&mut *tx)
+            .await?;
+        }
+
+        tx.commit().await?;
+        Ok(band_id)
+    }
+
+    #[cfg(feature = "ssr")]
+    pub async fn get_memberships(band_id: i32) -> Result<Vec<Membership>, AppError> {
+        sqlx::query!(
+            "SELECT band_id, user_name, role FROM band_memberships WHERE band_id = $1 ORDER BY user_name",
+            band_id
+        )
+        .map(|row| Membership {
+            band_id: row.band_id,
+            user_name: row.user_name,
+            role: row.role.parse().unwrap_or_default(),
+        })
+        .fetch_all(crate::database::get_db())
+        .await
+        .map_err(AppError::from)
+    }
+
+    /// Fails unless `user` may change who is in the band.
+    #[cfg(feature = "ssr")]
+    async fn check_manage(band_id: i32, user: Option<&str>) -> Result<Vec<Membership>, AppError> {
+        let memberships = Band::get_memberships(band_id).await?;
+        match Membership::may_manage(&memberships, user) {
+            true => Ok(memberships),
+            false => Err(AppError::Locked(
+                "alleen een beheerder kan leden van de band wijzigen".to_string(),
+            )),
+        }
+    }
+
+    /// Adds a user to the band or changes their role.
+    #[cfg(feature = "ssr")]
+    pub async fn set_membership(
+        band_id: i32,
+        user: Option<&str>,
+        user_name: String,
+        role: BandRole,
+    ) -> Result<(), AppError> {
+        let user_name = user_name.trim().to_string();
+        if user_name.is_empty() {
+            return Err(AppError::validation("Vul een gebruikersnaam in"));
+        }
+        let mut after = Band::check_manage(band_id, user).await?;
+        after.retain(|m| m.user_name != user_name);
+        after.push(Membership {
+            band_id,
+            user_name: user_name.clone(),
+            role,
+        });
+        Membership::check_admin_left(&after)?;
+
+        sqlx::query!(
+            "
+INSERT INTO band_memberships (band_id, user_name, role) VALUES ($1, $2, $3)
+ON CONFLICT (band_id, user_name) DO UPDATE SET role = EXCLUDED.role",
+            band_id,
+            user_name,
+            role.as_str()
+        )
+        .execute(crate::database::get_db())
+        .await?;
+        Ok(())
+    }
+
+    #[cfg(feature = "ssr")]
+    pub async fn remove_membership(
+        band_id: i32,
+        user: Option<&str>,
+        user_name: String,
+    ) -> Result<(), AppError> {
+        let mut after = Band::check_manage(band_id, user).await?;
+        after.retain(|m| m.user_name != user_name);
+        Membership::check_admin_left(&after)?;
+
+        sqlx::query!(
+            "DELETE FROM band_memberships WHERE band_id = $1 AND user_name = $2",
+            band_id,
+            user_name
+        )
+        .execute(crate::database::get_db())
+        .await?;
+        Ok(())
+    }
+}
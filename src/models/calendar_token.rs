@@ -0,0 +1,75 @@
+use serde::{Deserialize, Serialize};
+
+#[cfg(feature = "ssr")]
+use crate::error::AppError;
+
+/// A secret that lets a calendar app subscribe to a band's `/calendar.ics`
+/// without logging in. As soon as a band has one token its feed requires a
+/// valid one.
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq, Eq)]
+pub struct CalendarToken {
+    pub id: i32,
+    pub name: String,
+    pub token: String,
+}
+
+impl CalendarToken {
+    #[cfg(feature = "ssr")]
+    pub async fn get_all(band_id: i32) -> Result<Vec<Self>, AppError> {
+        sqlx::query_as!(
+            CalendarToken,
+            "SELECT id, name, token FROM calendar_tokens WHERE band_id = $1 ORDER BY name ASC",
+            band_id
+        )
+        .fetch_all(crate::database::get_db())
+        .await
+        .map_err(AppError::from)
+    }
+
+    #[cfg(feature = "ssr")]
+    pub async fn create(band_id: i32, name: String) -> Result<(), AppError> {
+        use rand::distributions::{Alphanumeric, DistString};
+
+        let token = Alphanumeric.sample_string(&mut rand::thread_rng(), 32);
+        sqlx::query!(
+            "INSERT INTO calendar_tokens (name, token, band_id) VALUES ($1, $2, $3)",
+            name,
+            token,
+            band_id
+        )
+        .execute(crate::database::get_db())
+        .await?;
+        Ok(())
+    }
+
+    #[cfg(feature = "ssr")]
+    pub async fn revoke(band_id: i32, id: i32) -> Result<(), AppError> {
+        sqlx::query!(
+            "DELETE FROM calendar_tokens WHERE id = $1 AND band_id = $2",
+            id,
+            band_id
+        )
+        .execute(crate::database::get_db())
+        .await?;
+        Ok(())
+    }
+
+    /// The band whose feed may be served: the band of an issued `token`, or
+    /// else the band of the request as long as it hasn't issued any tokens.
+    #[cfg(feature = "ssr")]
+    pub async fn band_for(token: Option<&str>, band_id: Option<i32>) -> Result<Option<i32>, AppError> {
+        sqlx::query!(
+            r#"SELECT COALESCE(
+              (SELECT band_id FROM calendar_tokens WHERE token = $1),
+              (SELECT id FROM bands b
+               WHERE b.id = $2 AND NOT EXISTS (SELECT 1 FROM calendar_tokens t WHERE t.band_id = b.id))
+            ) as band_id"#,
+            token,
+            band_id
+        )
+        .map(|row| row.band_id)
+        .fetch_one(crate::database::get_db())
+        .await
+        .map_err(AppError::from)
+    }
+}
@@ -0,0 +1,425 @@
+use std::str::FromStr;
+
+use chrono::NaiveDate;
+use serde::{Deserialize, Serialize};
+
+use super::money::Euros;
+#[cfg(feature = "ssr")]
+use crate::error::AppError;
+
+#[derive(Debug, Deserialize, Serialize, Clone, Copy, PartialEq, Eq, Hash, Default)]
+#[serde(rename_all = "lowercase")]
+pub enum ExpenseCategory {
+    Fuel,
+    Rental,
+    Food,
+    #[default]
+    Other,
+}
+
+impl ExpenseCategory {
+    pub const ALL: [ExpenseCategory; 4] = [
+        ExpenseCategory::Fuel,
+        ExpenseCategory::Rental,
+        ExpenseCategory::Food,
+        ExpenseCategory::Other,
+    ];
+
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            ExpenseCategory::Fuel => "fuel",
+            ExpenseCategory::Rental => "rental",
+            ExpenseCategory::Food => "food",
+            ExpenseCategory::Other => "other",
+        }
+    }
+}
+
+impl FromStr for ExpenseCategory {
+    type Err = String;
+
+    fn from_str(s: &str) -> std::result::Result<Self, Self::Err> {
+        ExpenseCategory::ALL
+            .into_iter()
+            .find(|c| c.as_str() == s)
+            .ok_or_else(|| format!("Unknown expense category: {}", s))
+    }
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq, Eq, Hash)]
+pub struct Expense {
+    pub id: i32,
+    pub gig_id: i32,
+    pub category: ExpenseCategory,
+    pub description: String,
+    pub amount: Euros,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq, Eq, Hash)]
+pub struct PayoutShare {
+    pub member_id: i32,
+    pub name: String,
+    pub shares: u32,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq, Eq, Hash, Default)]
+pub struct GigFinances {
+    pub gig_id: i32,
+    pub fee: Euros,
+    pub deposit: Euros,
+    pub expenses: Vec<Expense>,
+    pub shares: Vec<PayoutShare>,
+}
+
+impl GigFinances {
+    pub fn total_expenses(&self) -> Euros {
+        self.expenses.iter().map(|e| e.amount).sum()
+    }
+
+    /// What is left to divide after paying the expenses.
+    pub fn net(&self) -> Euros {
+        self.fee - self.total_expenses()
+    }
+
+    /// Part of the fee still to be paid after the deposit.
+    pub fn outstanding(&self) -> Euros {
+        self.fee - self.deposit
+    }
+
+    /// The net amount divided over the members by their shares.
+    pub fn payouts(&self) -> Vec<(PayoutShare, Euros)> {
+        let shares: Vec<u32> = self.shares.iter().map(|s| s.shares).collect();
+        self.shares
+            .iter()
+            .cloned()
+            .zip(self.net().split(&shares))
+            .collect()
+    }
+
+    #[cfg(feature = "ssr")]
+    pub async fn get(band_id: i32, gig_id: i32) -> Result<Self, AppError> {
+        let db = crate::database::get_db();
+        sqlx::query!(
+            "SELECT id FROM gigs WHERE id = $1 AND band_id = $2",
+            gig_id,
+            band_id
+        )
+        .fetch_one(db)
+        .await?;
+
+        let (fee, deposit) = sqlx::query!(
+            "SELECT fee_cents, deposit_cents FROM gig_finances WHERE gig_id = $1",
+            gig_id
+        )
+        .map(|row| (Euros::from_cents(row.fee_cents), Euros::from_cents(row.deposit_cents)))
+        .fetch_optional(db)
+        .await?
+        .unwrap_or_default();
+
+        let expenses = sqlx::query!(
+            "SELECT * FROM gig_expenses WHERE gig_id = $1 ORDER BY id ASC",
+            gig_id
+        )
+        .map(|row| Expense {
+            id: row.id,
+            gig_id: row.gig_id,
+            category: row.category.parse().unwrap_or_default(),
+            description: row.description,
+            amount: Euros::from_cents(row.amount_cents),
+        })
+        .fetch_all(db)
+        .await?;
+
+        let shares = sqlx::query!(
+            r#"
+SELECT m.id, m.name, COALESCE(s.shares, 1) as "shares!"
+FROM members m
+LEFT JOIN gig_payout_shares s ON s.member_id = m.id AND s.gig_id = $1
+WHERE m.band_id = $2
+ORDER BY m.name"#,
+            gig_id,
+            band_id
+        )
+        .map(|row| PayoutShare {
+            member_id: row.id,
+            name: row.name,
+            shares: row.shares.max(0) as u32,
+        })
+        .fetch_all(db)
+        .await?;
+
+        Ok(GigFinances {
+            gig_id,
+            fee,
+            deposit,
+            expenses,
+            shares,
+        })
+    }
+
+    #[cfg(feature = "ssr")]
+    pub async fn set_fee(
+        band_id: i32,
+        gig_id: i32,
+        fee: Euros,
+        deposit: Euros,
+    ) -> Result<(), AppError> {
+        sqlx::query!(
+            "
+INSERT INTO gig_finances (gig_id, fee_cents, deposit_cents)
+SELECT $1, $2, $3 WHERE EXISTS (SELECT 1 FROM gigs WHERE id = $1 AND band_id = $4)
+ON CONFLICT (gig_id) DO UPDATE SET fee_cents = EXCLUDED.fee_cents, deposit_cents = EXCLUDED.deposit_cents",
+            gig_id,
+            fee.cents(),
+            deposit.cents(),
+            band_id
+        )
+        .execute(crate::database::get_db())
+        .await?;
+        Ok(())
+    }
+
+    #[cfg(feature = "ssr")]
+    pub async fn add_expense(
+        band_id: i32,
+        gig_id: i32,
+        category: ExpenseCategory,
+        description: String,
+        amount: Euros,
+    ) -> Result<(), AppError> {
+        sqlx::query!(
+            "
+INSERT INTO gig_expenses (gig_id, category, description, amount_cents)
+SELECT $1, $2, $3, $4 WHERE EXISTS (SELECT 1 FROM gigs WHERE id = $1 AND band_id = $5)",
+            gig_id,
+            category.as_str(),
+            description,
+            amount.cents(),
+            band_id
+        )
+        .execute(crate::database::get_db())
+        .await?;
+        Ok(())
+    }
+
+    #[cfg(feature = "ssr")]
+    pub async fn remove_expense(band_id: i32, expense_id: i32) -> Result<(), AppError> {
+        sqlx::query!(
+            "
+DELETE FROM gig_expenses e USING gigs g
+WHERE e.id = $1 AND g.id = e.gig_id AND g.band_id = $2",
+            expense_id,
+            band_id
+        )
+        .execute(crate::database::get_db())
+        .await?;
+        Ok(())
+    }
+
+    #[cfg(feature = "ssr")]
+    pub async fn set_shares(
+        band_id: i32,
+        gig_id: i32,
+        member_id: i32,
+        shares: u32,
+    ) -> Result<(), AppError> {
+        sqlx::query!(
+            "
+INSERT INTO gig_payout_shares (gig_id, member_id, shares)
+SELECT $1, $2, $3
+WHERE EXISTS (SELECT 1 FROM gigs WHERE id = $1 AND band_id = $4)
+  AND EXISTS (SELECT 1 FROM members WHERE id = $2 AND band_id = $4)
+ON CONFLICT (gig_id, member_id) DO UPDATE SET shares = EXCLUDED.shares",
+            gig_id,
+            member_id,
+            shares as i32,
+            band_id
+        )
+        .execute(crate::database::get_db())
+        .await?;
+        Ok(())
+    }
+}
+
+/// One gig in the yearly overview.
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq, Eq, Hash)]
+pub struct GigFinanceLine {
+    pub gig_id: i32,
+    pub date: NaiveDate,
+    pub venue: String,
+    pub finances: GigFinances,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq, Eq, Hash, Default)]
+pub struct YearOverview {
+    pub year: i32,
+    pub gigs: Vec<GigFinanceLine>,
+}
+
+impl YearOverview {
+    pub fn total_fees(&self) -> Euros {
+        self.gigs.iter().map(|g| g.finances.fee).sum()
+    }
+
+    pub fn total_expenses(&self) -> Euros {
+        self.gigs.iter().map(|g| g.finances.total_expenses()).sum()
+    }
+
+    pub fn total_net(&self) -> Euros {
+        self.gigs.iter().map(|g| g.finances.net()).sum()
+    }
+
+    /// Total payout per member over the year, in order of first appearance.
+    pub fn payouts_per_member(&self) -> Vec<(String, Euros)> {
+        let mut totals: Vec<(i32, String, Euros)> = Vec::default();
+        for (share, amount) in self.gigs.iter().flat_map(|g| g.finances.payouts()) {
+            match totals.iter_mut().find(|(id, ..)| *id == share.member_id) {
+                Some((.., total)) => *total = *total + amount,
+                None => totals.push((share.member_id, share.name, amount)),
+            }
+        }
+        totals
+            .into_iter()
+            .map(|(_, name, total)| (name, total))
+            .collect()
+    }
+
+    /// The overview as CSV with one line per gig and a column per member.
+    /// Amounts use a decimal point so spreadsheets read them as numbers.
+    pub fn to_csv(&self) -> String {
+        let members = self.payouts_per_member();
+        let mut header = vec![
+            "datum".to_string(),
+            "locatie".to_string(),
+            "gage".to_string(),
+            "aanbetaling".to_string(),
+            "kosten".to_string(),
+            "netto".to_string(),
+        ];
+        header.extend(members.iter().map(|(name, _)| name.clone()));
+
+        let mut lines = vec![header];
+        for gig in &self.gigs {
+            let payouts = gig.finances.payouts();
+            let mut line = vec![
+                gig.date.format("%Y-%m-%d").to_string(),
+                gig.venue.clone(),
+                gig.finances.fee.to_decimal(),
+                gig.finances.deposit.to_decimal(),
+                gig.finances.total_expenses().to_decimal(),
+                gig.finances.net().to_decimal(),
+            ];
+            line.extend(members.iter().map(|(name, _)| {
+                payouts
+                    .iter()
+                    .find(|(share, _)| &share.name == name)
+                    .map(|(_, amount)| amount.to_decimal())
+                    .unwrap_or_default()
+            }));
+            lines.push(line);
+        }
+
+        lines
+            .into_iter()
+            .map(|line| {
+                line.iter()
+                    .map(|field| csv_field(field))
+                    .collect::<Vec<String>>()
+                    .join(",")
+                    + "\r\n"
+            })
+            .collect()
+    }
+
+    /// The finances of all gigs in `year`, with one query for the gigs and
+    /// their fees and one each for all their expenses and payout shares.
+    #[cfg(feature = "ssr")]
+    pub async fn get(band_id: i32, year: i32) -> Result<Self, AppError> {
+        let db = crate::database::get_db();
+        let mut lines = sqlx::query!(
+            r#"
+SELECT g.id, g.date, g.venue,
+  COALESCE(f.fee_cents, 0) as "fee_cents!", COALESCE(f.deposit_cents, 0) as "deposit_cents!"
+FROM gigs g LEFT JOIN gig_finances f ON f.gig_id = g.id
+WHERE g.date >= make_date($1, 1, 1) AND g.date < make_date($1 + 1, 1, 1)
+  AND g.deleted_at IS NULL AND g.band_id = $2
+ORDER BY g.date ASC, g.id ASC"#,
+            year,
+            band_id
+        )
+        .map(|row| GigFinanceLine {
+            gig_id: row.id,
+            date: row.date,
+            venue: row.venue,
+            finances: GigFinances {
+                gig_id: row.id,
+                fee: Euros::from_cents(row.fee_cents),
+                deposit: Euros::from_cents(row.deposit_cents),
+                ..Default::default()
+            },
+        })
+        .fetch_all(db)
+        .await?;
+        let gig_ids: Vec<i32> = lines.iter().map(|line| line.gig_id).collect();
+
+        let expenses = sqlx::query!(
+            "
+SELECT id, gig_id, category, description, amount_cents FROM gig_expenses
+WHERE gig_id = ANY($1) ORDER BY id ASC",
+            &gig_ids
+        )
+        .map(|row| Expense {
+            id: row.id,
+            gig_id: row.gig_id,
+            category: row.category.parse().unwrap_or_default(),
+            description: row.description,
+            amount: Euros::from_cents(row.amount_cents),
+        })
+        .fetch_all(db)
+        .await?;
+
+        let shares = sqlx::query!(
+            r#"
+SELECT g.id as "gig_id!", m.id, m.name, COALESCE(s.shares, 1) as "shares!"
+FROM UNNEST($1::int[]) AS g(id) CROSS JOIN members m
+LEFT JOIN gig_payout_shares s ON s.member_id = m.id AND s.gig_id = g.id
+WHERE m.band_id = $2
+ORDER BY m.name"#,
+            &gig_ids,
+            band_id
+        )
+        .map(|row| {
+            (
+                row.gig_id,
+                PayoutShare {
+                    member_id: row.id,
+                    name: row.name,
+                    shares: row.shares.max(0) as u32,
+                },
+            )
+        })
+        .fetch_all(db)
+        .await?;
+
+        for line in lines.iter_mut() {
+            line.finances.expenses = expenses
+                .iter()
+                .filter(|e| e.gig_id == line.gig_id)
+                .cloned()
+                .collect();
+            line.finances.shares = shares
+                .iter()
+                .filter(|(gig_id, _)| *gig_id == line.gig_id)
+                .map(|(_, share)| share.clone())
+                .collect();
+        }
+        Ok(YearOverview { year, gigs: lines })
+    }
+}
+
+fn csv_field(field: &str) -> String {
+    match field.contains([',', '"', '\n', '\r']) {
+        true => format!("\"{}\"", field.replace('"', "\"\"")),
+        false => field.to_string(),
+    }
+}
@@ -2,11 +2,18 @@ use chrono::NaiveDate;
 use serde::{Deserialize, Serialize};
 
 use super::song::Song;
+use super::venue::Venue;
+#[cfg(feature = "ssr")]
+use crate::error::AppError;
+#[cfg(feature = "ssr")]
+use crate::models::audit::{AuditAction, AuditEntity, AuditEntry};
+#[cfg(feature = "ssr")]
+use crate::repository::Repositories;
 
 #[derive(Debug, Serialize, Deserialize, Clone, PartialEq, Eq, Hash)]
 pub enum SongKind {
     Break(i32),
-    Song(Song),
+    Song(Box<Song>),
 }
 
 impl Default for SongKind {
@@ -15,6 +22,7 @@ impl Default for SongKind {
     }
 }
 
+#[cfg_attr(feature = "ssr", derive(utoipa::ToSchema))]
 #[derive(Debug, Serialize, Deserialize, Clone, PartialEq, Eq)]
 pub struct GigModel {
     pub id: i32,
@@ -22,12 +30,14 @@ pub struct GigModel {
     pub date: NaiveDate,
     pub time: Option<String>,
     pub songs: Vec<i32>,
+    pub venue_id: Option<i32>,
 }
 
 #[derive(Debug, Serialize, Deserialize, Clone, PartialEq, Eq)]
 pub struct Gig {
     pub id: i32,
     pub venue: String,
+    pub venue_id: Option<i32>,
     pub date: NaiveDate,
     pub time: Option<String>,
     pub songs: Vec<GigSong>,
@@ -39,6 +49,7 @@ impl Default for Gig {
         Self {
             id: 0,
             venue: "".to_string(),
+            venue_id: None,
             date: NaiveDate::default(),
             time: None,
             songs: Vec::default(),
@@ -47,6 +58,34 @@ impl Default for Gig {
     }
 }
 
+/// Where the running order of a new gig comes from.
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize, Serialize, Copy, Default)]
+pub enum GigSource {
+    #[default]
+    Empty,
+    Gig(i32),
+    Template(i32),
+}
+
+impl GigSource {
+    pub fn to_value(&self) -> String {
+        match self {
+            GigSource::Empty => "".to_string(),
+            GigSource::Gig(id) => format!("gig:{}", id),
+            GigSource::Template(id) => format!("template:{}", id),
+        }
+    }
+
+    /// Parses the value of the source `<select>`; anything unknown is empty.
+    pub fn from_value(value: &str) -> Self {
+        match value.split_once(':') {
+            Some(("gig", id)) => id.parse().map(GigSource::Gig).unwrap_or_default(),
+            Some(("template", id)) => id.parse().map(GigSource::Template).unwrap_or_default(),
+            _ => GigSource::Empty,
+        }
+    }
+}
+
 #[derive(Debug, Clone, PartialEq, Eq, Hash, Deserialize, Serialize, Copy)]
 pub enum MoveKind {
     Up,
@@ -60,41 +99,84 @@ pub struct GigSong {
     pub song: Option<Song>,
 }
 
-impl Gig {
-    #[cfg(feature = "ssr")]
-    pub async fn get_by_id(id: i32) -> Result<Self, sqlx::Error> {
-        let all_songs = Song::get_all().await?;
-        let gig = sqlx::query_as!(GigModel, "SELECT * FROM gigs WHERE id = $1", id)
-            .fetch_one(crate::database::get_db())
-            .await?;
+/// Id for a new break: breaks are negative and unique within a gig.
+pub fn next_break_id(songs: &[i32]) -> i32 {
+    songs.iter().copied().min().unwrap_or_default().min(0) - 1
+}
+
+/// Swaps a song with its neighbour. Returns whether anything moved, which it
+/// doesn't at either end of the running order or for a song not in it.
+pub fn move_in_order(songs: &mut [i32], song_id: i32, move_kind: MoveKind) -> bool {
+    let Some(index) = songs.iter().position(|s| *s == song_id) else {
+        return false;
+    };
+    let new_index = match move_kind {
+        MoveKind::Up => index.checked_sub(1),
+        MoveKind::Down => Some(index + 1).filter(|i| *i < songs.len()),
+    };
+    match new_index {
+        Some(new_index) => {
+            songs.swap(index, new_index);
+            true
+        }
+        None => false,
+    }
+}
 
+/// Groups gigs per venue, keeping the venues in order of their first gig.
+/// Gigs linked to a venue are grouped by it under its current name, so
+/// renaming a venue keeps its history together. Only gigs without a venue
+/// are grouped by the name typed in.
+pub fn group_by_venue(gigs: Vec<GigModel>, venues: &[Venue]) -> Vec<(String, Vec<GigModel>)> {
+    let mut groups: Vec<(Result<i32, String>, String, Vec<GigModel>)> = Vec::default();
+    for gig in gigs {
+        let key = gig.venue_id.ok_or_else(|| gig.venue.clone());
+        match groups.iter_mut().find(|(k, _, _)| k == &key) {
+            Some((_, _, venue_gigs)) => venue_gigs.push(gig),
+            None => {
+                let name = venues
+                    .iter()
+                    .find(|v| Some(v.id) == gig.venue_id)
+                    .map(|v| v.name.clone())
+                    .unwrap_or_else(|| gig.venue.clone());
+                groups.push((key, name, vec![gig]));
+            }
+        }
+    }
+    groups
+        .into_iter()
+        .map(|(_, name, gigs)| (name, gigs))
+        .collect()
+}
+
+impl Gig {
+    /// Resolves the running order against `all_songs`. Songs are numbered from
+    /// zero; a break gets the index of the song before it. Songs that have
+    /// been deleted since are left out.
+    pub fn from_model(gig: GigModel, all_songs: Vec<Song>) -> Self {
         let mut songs_indexed: Vec<GigSong> = Vec::default();
-        let mut break_count = 0usize;
-        for (index, song_id) in gig.songs.iter().enumerate() {
-            if song_id < &0 {
-                break_count += 1;
+        let mut song_count = 0usize;
+        for song_id in gig.songs.iter() {
+            if *song_id < 0 {
                 songs_indexed.push(GigSong {
-                    index: index - break_count,
+                    index: song_count.saturating_sub(1),
                     id: *song_id,
                     song: None,
                 });
-            } else {
-                let song = all_songs
-                    .iter()
-                    .find(|song| song.id == *song_id)
-                    .unwrap()
-                    .clone();
+            } else if let Some(song) = all_songs.iter().find(|song| song.id == *song_id) {
                 songs_indexed.push(GigSong {
-                    index: index - break_count,
+                    index: song_count,
                     id: *song_id,
-                    song: Some(song),
+                    song: Some(song.clone()),
                 });
+                song_count += 1;
             }
         }
 
-        let gig = Gig {
+        Gig {
             id: gig.id,
             venue: gig.venue,
+            venue_id: gig.venue_id,
             time: gig.time,
             date: gig.date,
             songs: songs_indexed,
@@ -102,142 +184,166 @@ impl Gig {
                 .into_iter()
                 .filter(|s| !gig.songs.contains(&s.id))
                 .collect(),
-        };
-
-        Ok(gig)
+        }
     }
 
     #[cfg(feature = "ssr")]
-    pub async fn add_song(gig_id: i32, song_id: i32) -> Result<(), sqlx::Error> {
-        use std::cmp::min;
-
-        let song_id = if song_id < 0 {
-            min(
-                sqlx::query!("SELECT songs FROM gigs WHERE id = $1", gig_id)
-                    .map(|row| row.songs.into_iter().min())
-                    .fetch_one(crate::database::get_db())
-                    .await?
-                    .unwrap(),
-                0,
-            ) - 1
-        } else {
-            song_id
-        };
+    pub async fn get_by_id(repos: &Repositories, id: i32) -> Result<Self, AppError> {
+        let gig = repos.gigs.get(id).await?;
+        Ok(Gig::from_model(gig, repos.songs.get_all().await?))
+    }
 
-        sqlx::query!(
-            "UPDATE gigs SET songs = array_append(songs, $1) WHERE id = $2",
-            song_id,
-            gig_id,
-        )
-        .execute(crate::database::get_db())
-        .await?;
-        Ok(())
+    /// Appends a song, or a new break for a negative `song_id`.
+    #[cfg(feature = "ssr")]
+    pub async fn add_song(repos: &Repositories, gig_id: i32, song_id: i32) -> Result<(), AppError> {
+        let before = repos.gigs.get(gig_id).await?;
+        let mut songs = before.songs.clone();
+        songs.push(match song_id < 0 {
+            true => next_break_id(&songs),
+            false => song_id,
+        });
+        repos.gigs.set_songs(gig_id, &songs).await?;
+        AuditEntry::gig_changed(repos, AuditAction::Update, &before).await
     }
 
     #[cfg(feature = "ssr")]
-    pub async fn remove_song(gig_id: i32, song_id: i32) -> Result<(), sqlx::Error> {
-        sqlx::query!(
-            "UPDATE gigs SET songs = array_remove(songs, $1) WHERE id = $2",
-            song_id,
-            gig_id,
-        )
-        .execute(crate::database::get_db())
-        .await?;
-        Ok(())
+    pub async fn remove_song(
+        repos: &Repositories,
+        gig_id: i32,
+        song_id: i32,
+    ) -> Result<(), AppError> {
+        let before = repos.gigs.get(gig_id).await?;
+        let mut songs = before.songs.clone();
+        songs.retain(|s| *s != song_id);
+        repos.gigs.set_songs(gig_id, &songs).await?;
+        AuditEntry::gig_changed(repos, AuditAction::Update, &before).await
     }
 
     #[cfg(feature = "ssr")]
     pub async fn move_song(
+        repos: &Repositories,
         gig_id: i32,
         song_id: i32,
         move_kind: MoveKind,
-    ) -> Result<(), sqlx::Error> {
-        let mut songs = sqlx::query!("SELECT * from gigs WHERE id = $1", gig_id)
-            .map(|row| row.songs)
-            .fetch_one(crate::database::get_db())
-            .await?;
-
-        let index_of_song = songs.iter().position(|s| *s == song_id).unwrap();
-
-        let new_index = match move_kind {
-            MoveKind::Up => index_of_song - 1,
-            MoveKind::Down => index_of_song + 1,
-        };
-
-        if new_index == index_of_song || new_index >= songs.len() {
+    ) -> Result<(), AppError> {
+        let before = repos.gigs.get(gig_id).await?;
+        let mut songs = before.songs.clone();
+        if !move_in_order(&mut songs, song_id, move_kind) {
             return Ok(());
         }
+        repos.gigs.set_songs(gig_id, &songs).await?;
+        AuditEntry::gig_changed(repos, AuditAction::Update, &before).await
+    }
 
-        songs.swap(index_of_song, new_index);
-
-        sqlx::query!("UPDATE gigs SET songs = $1 WHERE id = $2", &songs, gig_id)
-            .execute(crate::database::get_db())
-            .await?;
+    /// Replaces the whole running order, which can be undone.
+    #[cfg(feature = "ssr")]
+    pub async fn replace_songs(
+        repos: &Repositories,
+        gig_id: i32,
+        songs: &[i32],
+    ) -> Result<(), AppError> {
+        let before = repos.gigs.get(gig_id).await?;
+        repos.gigs.set_songs(gig_id, songs).await?;
+        AuditEntry::gig_changed(repos, AuditAction::Replace, &before).await
+    }
 
-        Ok(())
+    /// Deletes the gig. It can be restored from the deleted gigs or by undoing.
+    #[cfg(feature = "ssr")]
+    pub async fn remove(repos: &Repositories, gig_id: i32) -> Result<(), AppError> {
+        let before = repos.gigs.get(gig_id).await?;
+        repos.gigs.remove(gig_id).await?;
+        AuditEntry::gig_changed(repos, AuditAction::Delete, &before).await
     }
 
     #[cfg(feature = "ssr")]
-    pub async fn set_venue(gig_id: i32, venue: String) -> Result<(), sqlx::Error> {
-        sqlx::query!(
-            "UPDATE gigs SET venue = $1 WHERE id = $2",
-            venue,
-            gig_id as i32
+    pub async fn restore(repos: &Repositories, gig_id: i32) -> Result<(), AppError> {
+        repos.gigs.restore(gig_id).await?;
+        let after = repos.gigs.get(gig_id).await?;
+        AuditEntry::record(
+            repos,
+            AuditEntity::Gig,
+            gig_id,
+            AuditAction::Restore,
+            None,
+            Some(&after),
         )
-        .execute(crate::database::get_db())
-        .await?;
-        Ok(())
+        .await
     }
+
     #[cfg(feature = "ssr")]
-    pub async fn set_time(gig_id: i32, time: String) -> Result<(), sqlx::Error> {
-        sqlx::query!(
-            "UPDATE gigs SET time = $1 WHERE id = $2",
-            time,
-            gig_id as i32
-        )
-        .execute(crate::database::get_db())
-        .await?;
-        Ok(())
+    pub async fn set_venue(repos: &Repositories, gig_id: i32, venue: String) -> Result<(), AppError> {
+        let before = repos.gigs.get(gig_id).await?;
+        repos.gigs.set_venue(gig_id, venue).await?;
+        AuditEntry::gig_changed(repos, AuditAction::Update, &before).await
     }
+
     #[cfg(feature = "ssr")]
-    pub async fn set_date(gig_id: i32, date: String) -> Result<(), sqlx::Error> {
-        sqlx::query!(
-            "UPDATE gigs SET date = $1 WHERE id = $2",
-            match NaiveDate::parse_from_str(&date, "%Y-%m-%d") {
-                Ok(d) => d,
-                Err(_) => return Err(sqlx::Error::RowNotFound),
-            },
-            gig_id as i32
-        )
-        .execute(crate::database::get_db())
-        .await?;
-        Ok(())
+    pub async fn set_venue_id(
+        repos: &Repositories,
+        gig_id: i32,
+        venue_id: Option<i32>,
+    ) -> Result<(), AppError> {
+        let before = repos.gigs.get(gig_id).await?;
+        repos.gigs.set_venue_id(gig_id, venue_id).await?;
+        AuditEntry::gig_changed(repos, AuditAction::Update, &before).await
     }
 
     #[cfg(feature = "ssr")]
-    pub async fn get_all() -> Result<Vec<GigModel>, sqlx::Error> {
-        sqlx::query_as!(GigModel, "SELECT * FROM gigs ORDER BY date ASC")
-            .fetch_all(crate::database::get_db())
-            .await
+    pub async fn set_time(repos: &Repositories, gig_id: i32, time: String) -> Result<(), AppError> {
+        let before = repos.gigs.get(gig_id).await?;
+        repos.gigs.set_time(gig_id, time).await?;
+        AuditEntry::gig_changed(repos, AuditAction::Update, &before).await
     }
+
     #[cfg(feature = "ssr")]
-    pub async fn create() -> Result<(), sqlx::Error> {
-        sqlx::query!(
-            "
-INSERT INTO gigs (venue, date, songs)  VALUES ('Nieuw', $1, '{}');
-",
-            NaiveDate::default()
-        )
-        .execute(crate::database::get_db())
-        .await?;
-        Ok(())
+    pub async fn set_date(repos: &Repositories, gig_id: i32, date: &str) -> Result<(), AppError> {
+        let date = NaiveDate::parse_from_str(date, "%Y-%m-%d")
+            .map_err(|_| AppError::validation(format!("ongeldige datum {}", date)))?;
+        let before = repos.gigs.get(gig_id).await?;
+        repos.gigs.set_date(gig_id, date).await?;
+        AuditEntry::gig_changed(repos, AuditAction::Update, &before).await
     }
 
+    /// Creates a gig and returns its id. The running order is copied from the
+    /// source, leaving out songs that have been deleted since.
     #[cfg(feature = "ssr")]
-    pub async fn remove(gig_id: i32) -> Result<(), sqlx::Error> {
-        sqlx::query!("DELETE FROM gigs WHERE id = $1", gig_id)
-            .execute(crate::database::get_db())
-            .await?;
-        Ok(())
+    pub async fn create(
+        repos: &Repositories,
+        venue: String,
+        date: NaiveDate,
+        time: Option<String>,
+        source: GigSource,
+    ) -> Result<i32, AppError> {
+        use super::gig_template::GigTemplate;
+
+        let songs = match source {
+            GigSource::Empty => Vec::default(),
+            GigSource::Gig(id) => repos.gigs.get(id).await?.songs,
+            GigSource::Template(id) => GigTemplate::get_by_id(repos.band_id, id).await?.songs,
+        };
+        let existing: Vec<i32> = repos
+            .songs
+            .get_all()
+            .await?
+            .into_iter()
+            .map(|s| s.id)
+            .collect();
+        let songs: Vec<i32> = songs
+            .into_iter()
+            .filter(|id| *id < 0 || existing.contains(id))
+            .collect();
+
+        let id = repos.gigs.create(venue, date, time, &songs).await?;
+        let after = repos.gigs.get(id).await?;
+        AuditEntry::record(
+            repos,
+            AuditEntity::Gig,
+            id,
+            AuditAction::Create,
+            None,
+            Some(&after),
+        )
+        .await?;
+        Ok(id)
     }
 }
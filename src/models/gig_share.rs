@@ -0,0 +1,197 @@
+use chrono::{Duration, NaiveDate};
+use serde::{Deserialize, Serialize};
+
+use super::gig::Gig;
+use super::key::Key;
+use crate::error::AppError;
+#[cfg(feature = "ssr")]
+use crate::repository::Repositories;
+
+/// How long after the gig a new link keeps working, unless another expiry
+/// date is picked.
+pub const DEFAULT_SHARE_DAYS: i64 = 7;
+
+/// A secret link that shows a gig's running order to anyone who has it,
+/// like the venue or a stand-in musician. Deleting it revokes the link.
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq, Eq)]
+pub struct GigShare {
+    pub id: i32,
+    pub gig_id: i32,
+    pub token: String,
+    pub include_lyrics: bool,
+    /// The last day the link works.
+    pub expires_on: NaiveDate,
+}
+
+impl GigShare {
+    pub fn is_expired(&self, today: NaiveDate) -> bool {
+        today > self.expires_on
+    }
+
+    /// A week after the gig, or after today for a gig in the past.
+    pub fn default_expiry(gig_date: NaiveDate, today: NaiveDate) -> NaiveDate {
+        gig_date.max(today) + Duration::days(DEFAULT_SHARE_DAYS)
+    }
+
+    /// Parses the expiry date from the share form, where empty means the
+    /// default. The date can't lie in the past.
+    pub fn parse_expiry(
+        expires_on: &str,
+        gig_date: NaiveDate,
+        today: NaiveDate,
+    ) -> Result<NaiveDate, AppError> {
+        let expires_on = match expires_on.trim() {
+            "" => return Ok(Self::default_expiry(gig_date, today)),
+            date => NaiveDate::parse_from_str(date, "%Y-%m-%d")
+                .map_err(|_| AppError::validation(format!("Ongeldige datum: {}", date)))?,
+        };
+        match expires_on < today {
+            true => Err(AppError::validation("Kies een verloopdatum in de toekomst")),
+            false => Ok(expires_on),
+        }
+    }
+
+    #[cfg(feature = "ssr")]
+    pub async fn get_for_gig(band_id: i32, gig_id: i32) -> Result<Vec<Self>, AppError> {
+        sqlx::query_as!(
+            GigShare,
+            "
+SELECT s.id, s.gig_id, s.token, s.include_lyrics, s.expires_on
+FROM gig_shares s JOIN gigs g ON g.id = s.gig_id
+WHERE s.gig_id = $1 AND g.band_id = $2
+ORDER BY s.id ASC",
+            gig_id,
+            band_id
+        )
+        .fetch_all(crate::database::get_db())
+        .await
+        .map_err(AppError::from)
+    }
+
+    /// The share behind `token` with the band of its gig.
+    #[cfg(feature = "ssr")]
+    pub async fn get_by_token(token: &str) -> Result<(Self, i32), AppError> {
+        sqlx::query!(
+            "
+SELECT s.id, s.gig_id, s.token, s.include_lyrics, s.expires_on, g.band_id
+FROM gig_shares s JOIN gigs g ON g.id = s.gig_id
+WHERE s.token = $1",
+            token
+        )
+        .map(|row| {
+            let share = GigShare {
+                id: row.id,
+                gig_id: row.gig_id,
+                token: row.token,
+                include_lyrics: row.include_lyrics,
+                expires_on: row.expires_on,
+            };
+            (share, row.band_id)
+        })
+        .fetch_optional(crate::database::get_db())
+        .await?
+        .ok_or_else(|| AppError::NotFound("deze link bestaat niet (meer)".to_string()))
+    }
+
+    #[cfg(feature = "ssr")]
+    pub async fn create(
+        band_id: i32,
+        gig_id: i32,
+        include_lyrics: bool,
+        expires_on: NaiveDate,
+    ) -> Result<(), AppError> {
+        use rand::distributions::{Alphanumeric, DistString};
+
+        let token = Alphanumeric.sample_string(&mut rand::thread_rng(), 32);
+        sqlx::query!(
+            "
+INSERT INTO gig_shares (gig_id, token, include_lyrics, expires_on)
+SELECT $1, $2, $3, $4 WHERE EXISTS (SELECT 1 FROM gigs WHERE id = $1 AND band_id = $5)",
+            gig_id,
+            token,
+            include_lyrics,
+            expires_on,
+            band_id
+        )
+        .execute(crate::database::get_db())
+        .await?;
+        Ok(())
+    }
+
+    #[cfg(feature = "ssr")]
+    pub async fn revoke(band_id: i32, id: i32) -> Result<(), AppError> {
+        sqlx::query!(
+            "
+DELETE FROM gig_shares s USING gigs g
+WHERE s.id = $1 AND g.id = s.gig_id AND g.band_id = $2",
+            id,
+            band_id
+        )
+        .execute(crate::database::get_db())
+        .await?;
+        Ok(())
+    }
+}
+
+/// A song as shown on a shared setlist.
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq, Eq)]
+pub struct SharedSong {
+    pub title: String,
+    pub artist: String,
+    pub bpm: Option<i32>,
+    pub key: Option<Key>,
+    /// Only when the link was made with lyrics.
+    pub lyrics: Option<String>,
+}
+
+/// What a share link shows: where and when, and the running order split
+/// into sets at the breaks. Nothing else about the band is included.
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq, Eq, Default)]
+pub struct SharedGig {
+    pub venue: String,
+    pub date: NaiveDate,
+    pub time: Option<String>,
+    pub sets: Vec<Vec<SharedSong>>,
+}
+
+impl SharedGig {
+    pub fn from_gig(gig: &Gig, include_lyrics: bool) -> Self {
+        let mut sets: Vec<Vec<SharedSong>> = gig
+            .songs
+            .split(|s| s.id < 0)
+            .map(|set| {
+                set.iter()
+                    .filter_map(|s| s.song.as_ref())
+                    .map(|song| SharedSong {
+                        title: song.title.clone(),
+                        artist: song.artist.clone(),
+                        bpm: song.bpm,
+                        key: song.key.clone(),
+                        lyrics: include_lyrics.then(|| song.lyrics.clone()),
+                    })
+                    .collect()
+            })
+            .collect();
+        // A break at the start or end, or two in a row, leaves an empty set.
+        sets.retain(|set| !set.is_empty());
+
+        SharedGig {
+            venue: gig.venue.clone(),
+            date: gig.date,
+            time: gig.time.clone(),
+            sets,
+        }
+    }
+
+    /// The gig behind `token`, as long as the link hasn't expired. Anyone
+    /// with the link may see it, whichever band they're in.
+    #[cfg(feature = "ssr")]
+    pub async fn get(token: &str, today: NaiveDate) -> Result<Self, AppError> {
+        let (share, band_id) = GigShare::get_by_token(token).await?;
+        if share.is_expired(today) {
+            return Err(AppError::NotFound("deze link is verlopen".to_string()));
+        }
+        let gig = Gig::get_by_id(&Repositories::postgres(band_id), share.gig_id).await?;
+        Ok(Self::from_gig(&gig, share.include_lyrics))
+    }
+}
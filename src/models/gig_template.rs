@@ -0,0 +1,66 @@
+use serde::{Deserialize, Serialize};
+
+#[cfg(feature = "ssr")]
+use crate::error::AppError;
+
+/// A saved running order, breaks included, to start new gigs from.
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq, Eq)]
+pub struct GigTemplate {
+    pub id: i32,
+    pub name: String,
+    pub songs: Vec<i32>,
+}
+
+impl GigTemplate {
+    #[cfg(feature = "ssr")]
+    pub async fn get_all(band_id: i32) -> Result<Vec<Self>, AppError> {
+        sqlx::query_as!(
+            GigTemplate,
+            "SELECT id, name, songs FROM gig_templates WHERE band_id = $1 ORDER BY name ASC",
+            band_id
+        )
+        .fetch_all(crate::database::get_db())
+        .await
+        .map_err(AppError::from)
+    }
+
+    #[cfg(feature = "ssr")]
+    pub async fn get_by_id(band_id: i32, id: i32) -> Result<Self, AppError> {
+        sqlx::query_as!(
+            GigTemplate,
+            "SELECT id, name, songs FROM gig_templates WHERE id = $1 AND band_id = $2",
+            id,
+            band_id
+        )
+        .fetch_one(crate::database::get_db())
+        .await
+        .map_err(AppError::from)
+    }
+
+    #[cfg(feature = "ssr")]
+    pub async fn create_from_gig(band_id: i32, gig_id: i32, name: String) -> Result<(), AppError> {
+        sqlx::query!(
+            "
+INSERT INTO gig_templates (name, songs, band_id)
+SELECT $1, songs, band_id FROM gigs WHERE id = $2 AND band_id = $3",
+            name,
+            gig_id,
+            band_id
+        )
+        .execute(crate::database::get_db())
+        .await?;
+        Ok(())
+    }
+
+    #[cfg(feature = "ssr")]
+    pub async fn remove(band_id: i32, id: i32) -> Result<(), AppError> {
+        sqlx::query!(
+            "DELETE FROM gig_templates WHERE id = $1 AND band_id = $2",
+            id,
+            band_id
+        )
+        .execute(crate::database::get_db())
+        .await?;
+        Ok(())
+    }
+}
@@ -0,0 +1,202 @@
+use std::fmt;
+use std::str::FromStr;
+
+use serde::{Deserialize, Serialize};
+
+use super::gig::GigSong;
+
+/// Keys this many steps apart on the circle of fifths make an awkward segue,
+/// like C to E or C to F#.
+pub const AWKWARD_DISTANCE: u8 = 4;
+
+#[cfg_attr(feature = "ssr", derive(utoipa::ToSchema))]
+#[derive(Debug, Deserialize, Serialize, Clone, Copy, PartialEq, Eq, Hash, Default)]
+#[serde(rename_all = "lowercase")]
+pub enum Mode {
+    #[default]
+    Major,
+    Minor,
+}
+
+/// The key a song is played in.
+#[cfg_attr(feature = "ssr", derive(utoipa::ToSchema))]
+#[derive(Debug, Deserialize, Serialize, Clone, PartialEq, Eq, Hash)]
+pub struct Key {
+    /// Note name with an optional sharp or flat, like "C", "F#" or "Bb".
+    pub tonic: String,
+    pub mode: Mode,
+}
+
+impl Key {
+    /// Semitones above C.
+    pub fn pitch_class(&self) -> u8 {
+        let mut chars = self.tonic.chars();
+        let natural: i32 = match chars.next() {
+            Some('C') => 0,
+            Some('D') => 2,
+            Some('E') => 4,
+            Some('F') => 5,
+            Some('G') => 7,
+            Some('A') => 9,
+            _ => 11,
+        };
+        let accidental = match chars.next() {
+            Some('#') => 1,
+            Some('b') => -1,
+            _ => 0,
+        };
+        (natural + accidental).rem_euclid(12) as u8
+    }
+
+    /// Place on the circle of fifths, counting a minor key as its relative
+    /// major: C and Am are 0, G and Em are 1.
+    fn fifths(&self) -> u8 {
+        let major = match self.mode {
+            Mode::Major => self.pitch_class(),
+            Mode::Minor => (self.pitch_class() + 3) % 12,
+        };
+        major * 7 % 12
+    }
+
+    /// Steps between the keys on the circle of fifths, 0 to 6.
+    pub fn distance(&self, other: &Key) -> u8 {
+        let steps = self.fifths().abs_diff(other.fifths());
+        steps.min(12 - steps)
+    }
+
+    /// The key from a ChordPro `{key: Am}` directive in the lyrics.
+    pub fn from_chordpro(lyrics: &str) -> Option<Key> {
+        let start = lyrics.to_ascii_lowercase().find("{key:")? + "{key:".len();
+        let rest = &lyrics[start..];
+        rest[..rest.find('}')?].parse().ok()
+    }
+
+    /// Parses the key field of the song form, where empty means no key.
+    pub fn parse_optional(key: &str) -> Result<Option<Key>, String> {
+        match key.trim() {
+            "" => Ok(None),
+            key => key.parse().map(Some),
+        }
+    }
+}
+
+impl FromStr for Key {
+    type Err = String;
+
+    /// Reads "C", "F#m", "Bb", "Ebmin", "A minor" and "a mol".
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let error = || format!("Onbekende toonsoort: {}", s.trim());
+        let mut chars = s.trim().chars().peekable();
+        let letter = chars
+            .next()
+            .map(|c| c.to_ascii_uppercase())
+            .filter(|c| ('A'..='G').contains(c))
+            .ok_or_else(error)?;
+        let mut tonic = letter.to_string();
+        match chars.peek() {
+            Some('#' | '♯') => tonic.push('#'),
+            Some('b' | '♭') => tonic.push('b'),
+            _ => {}
+        }
+        if tonic.len() > 1 {
+            chars.next();
+        }
+        let mode = match chars.collect::<String>().trim().to_lowercase().as_str() {
+            "" | "maj" | "major" | "dur" | "majeur" => Mode::Major,
+            "m" | "-" | "min" | "minor" | "mol" | "mineur" => Mode::Minor,
+            _ => return Err(error()),
+        };
+        Ok(Key { tonic, mode })
+    }
+}
+
+impl fmt::Display for Key {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self.mode {
+            Mode::Major => write!(f, "{}", self.tonic),
+            Mode::Minor => write!(f, "{}m", self.tonic),
+        }
+    }
+}
+
+fn key(song: &GigSong) -> Option<&Key> {
+    song.song.as_ref()?.key.as_ref()
+}
+
+/// A segue between two songs whose keys lie far apart.
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq, Eq, Hash)]
+pub struct KeyTransition {
+    /// Id of the song after the segue.
+    pub song_id: i32,
+    pub from: Key,
+    pub to: Key,
+}
+
+impl KeyTransition {
+    /// Finds the awkward key changes between consecutive songs. Like
+    /// changeovers, songs after a break are skipped.
+    pub fn find(running_order: &[GigSong]) -> Vec<KeyTransition> {
+        running_order
+            .windows(2)
+            .filter_map(|pair| {
+                let (from, to) = (key(&pair[0])?, key(&pair[1])?);
+                (from.distance(to) >= AWKWARD_DISTANCE).then(|| KeyTransition {
+                    song_id: pair[1].id,
+                    from: from.clone(),
+                    to: to.clone(),
+                })
+            })
+            .collect()
+    }
+}
+
+/// Bigger jumps weigh more, so two small steps beat one big one.
+fn cost(set: &[&GigSong]) -> u32 {
+    set.windows(2)
+        .filter_map(|pair| Some(key(pair[0])?.distance(key(pair[1])?) as u32))
+        .map(|distance| distance * distance)
+        .sum()
+}
+
+/// A running order with smoother key changes, or `None` when it can't be
+/// improved. Songs only move within their set and every set keeps its
+/// opening and closing song.
+pub fn smoother_order(running_order: &[GigSong]) -> Option<Vec<i32>> {
+    let breaks: Vec<i32> = running_order
+        .iter()
+        .map(|s| s.id)
+        .filter(|id| *id < 0)
+        .collect();
+    let mut improved = false;
+    let mut order = Vec::with_capacity(running_order.len());
+    for (index, set) in running_order.split(|s| s.id < 0).enumerate() {
+        if index > 0 {
+            // The break the set was split at.
+            order.push(breaks[index - 1]);
+        }
+        let mut set: Vec<&GigSong> = set.iter().collect();
+        // Move one song at a time to where it helps most, until nothing helps.
+        loop {
+            let current = cost(&set);
+            let best = (1..set.len().saturating_sub(1))
+                .flat_map(|from| (1..set.len() - 1).map(move |to| (from, to)))
+                .filter(|(from, to)| from != to)
+                .map(|(from, to)| {
+                    let mut candidate = set.clone();
+                    let song = candidate.remove(from);
+                    candidate.insert(to, song);
+                    (cost(&candidate), candidate)
+                })
+                .min_by_key(|(cost, _)| *cost);
+            match best {
+                Some((cost, candidate)) if cost < current => {
+                    set = candidate;
+                    improved = true;
+                }
+                _ => break,
+            }
+        }
+        order.extend(set.iter().map(|s| s.id));
+    }
+    improved.then_some(order)
+}
@@ -0,0 +1,392 @@
+use std::str::FromStr;
+
+use chrono::NaiveDate;
+use serde::{Deserialize, Serialize};
+
+#[cfg(feature = "ssr")]
+use crate::error::AppError;
+use crate::i18n::Locale;
+
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq, Eq, Hash, Default)]
+pub struct Member {
+    pub id: i32,
+    pub name: String,
+    pub instrument: String,
+    pub required: bool,
+}
+
+/// A member's answer to whether they can make it to a gig or rehearsal.
+#[derive(Debug, Deserialize, Serialize, Clone, Copy, PartialEq, Eq, Hash)]
+#[serde(rename_all = "lowercase")]
+pub enum Rsvp {
+    Yes,
+    Maybe,
+    No,
+}
+
+impl Rsvp {
+    pub const ALL: [Rsvp; 3] = [Rsvp::Yes, Rsvp::Maybe, Rsvp::No];
+
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Rsvp::Yes => "yes",
+            Rsvp::Maybe => "maybe",
+            Rsvp::No => "no",
+        }
+    }
+}
+
+impl FromStr for Rsvp {
+    type Err = String;
+
+    fn from_str(s: &str) -> std::result::Result<Self, Self::Err> {
+        Rsvp::ALL
+            .into_iter()
+            .find(|r| r.as_str() == s)
+            .ok_or_else(|| format!("Unknown rsvp: {}", s))
+    }
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq, Eq, Hash)]
+pub struct Unavailability {
+    pub member_id: i32,
+    pub date: NaiveDate,
+    pub note: String,
+}
+
+/// Whether a member is coming to a gig or rehearsal, and whether they marked
+/// its date as unavailable.
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq, Eq, Hash)]
+pub struct Attendance {
+    pub member: Member,
+    pub rsvp: Option<Rsvp>,
+    pub unavailable: Option<String>,
+}
+
+impl Attendance {
+    pub fn is_in(&self) -> bool {
+        self.rsvp == Some(Rsvp::Yes)
+    }
+
+    pub fn is_out(&self) -> bool {
+        self.rsvp == Some(Rsvp::No) || (self.rsvp.is_none() && self.unavailable.is_some())
+    }
+
+    /// Warnings for required members who can't make it, either because they
+    /// said so or because they marked the date as unavailable.
+    pub fn warnings(attendance: &[Attendance], locale: Locale) -> Vec<String> {
+        attendance
+            .iter()
+            .filter(|a| a.member.required && a.rsvp != Some(Rsvp::Yes))
+            .filter_map(|a| {
+                let name = ("name", a.member.name.clone().into());
+                match (&a.rsvp, &a.unavailable) {
+                    (Some(Rsvp::No), _) => Some(locale.t_with("warning-cant-make-it", &[name])),
+                    (_, Some(note)) if note.is_empty() => {
+                        Some(locale.t_with("warning-unavailable", &[name]))
+                    }
+                    (_, Some(note)) => Some(locale.t_with(
+                        "warning-unavailable-because",
+                        &[name, ("note", note.clone().into())],
+                    )),
+                    _ => None,
+                }
+            })
+            .collect()
+    }
+}
+
+#[cfg(feature = "ssr")]
+struct AttendanceRow {
+    event_id: i32,
+    id: i32,
+    name: String,
+    instrument: String,
+    required: bool,
+    status: Option<String>,
+    unavailable: Option<String>,
+}
+
+#[cfg(feature = "ssr")]
+impl From<AttendanceRow> for (i32, Attendance) {
+    fn from(row: AttendanceRow) -> Self {
+        (
+            row.event_id,
+            Attendance {
+                member: Member {
+                    id: row.id,
+                    name: row.name,
+                    instrument: row.instrument,
+                    required: row.required,
+                },
+                rsvp: row.status.and_then(|s| s.parse().ok()),
+                unavailable: row.unavailable,
+            },
+        )
+    }
+}
+
+impl Member {
+    #[cfg(feature = "ssr")]
+    pub async fn get_all(band_id: i32) -> Result<Vec<Self>, AppError> {
+        sqlx::query_as!(
+            Member,
+            "SELECT id, name, instrument, required FROM members WHERE band_id = $1 ORDER BY name ASC",
+            band_id
+        )
+        .fetch_all(crate::database::get_db())
+        .await
+        .map_err(AppError::from)
+    }
+
+    #[cfg(feature = "ssr")]
+    pub async fn create(band_id: i32, name: String, instrument: String) -> Result<i32, AppError> {
+        sqlx::query!(
+            "INSERT INTO members (name, instrument, band_id) VALUES ($1, $2, $3) RETURNING id",
+            name,
+            instrument,
+            band_id
+        )
+        .map(|row| row.id)
+        .fetch_one(crate::database::get_db())
+        .await
+        .map_err(AppError::from)
+    }
+
+    #[cfg(feature = "ssr")]
+    pub async fn set_required(band_id: i32, member_id: i32, required: bool) -> Result<(), AppError> {
+        sqlx::query!(
+            "UPDATE members SET required = $2 WHERE id = $1 AND band_id = $3",
+            member_id,
+            required,
+            band_id
+        )
+        .execute(crate::database::get_db())
+        .await?;
+        Ok(())
+    }
+
+    #[cfg(feature = "ssr")]
+    pub async fn remove(band_id: i32, member_id: i32) -> Result<(), AppError> {
+        sqlx::query!(
+            "DELETE FROM members WHERE id = $1 AND band_id = $2",
+            member_id,
+            band_id
+        )
+        .execute(crate::database::get_db())
+        .await?;
+        Ok(())
+    }
+
+    /// Dates members can't make it from `from` onwards.
+    #[cfg(feature = "ssr")]
+    pub async fn get_unavailability(
+        band_id: i32,
+        from: NaiveDate,
+    ) -> Result<Vec<Unavailability>, AppError> {
+        sqlx::query_as!(
+            Unavailability,
+            "
+SELECT u.* FROM member_unavailability u JOIN members m ON m.id = u.member_id
+WHERE u.date >= $1 AND m.band_id = $2
+ORDER BY u.date ASC",
+            from,
+            band_id
+        )
+        .fetch_all(crate::database::get_db())
+        .await
+        .map_err(AppError::from)
+    }
+
+    #[cfg(feature = "ssr")]
+    pub async fn set_unavailable(
+        band_id: i32,
+        member_id: i32,
+        date: NaiveDate,
+        note: String,
+    ) -> Result<(), AppError> {
+        sqlx::query!(
+            "
+INSERT INTO member_unavailability (member_id, date, note)
+SELECT $1, $2, $3 WHERE EXISTS (SELECT 1 FROM members WHERE id = $1 AND band_id = $4)
+ON CONFLICT (member_id, date) DO UPDATE SET note = EXCLUDED.note",
+            member_id,
+            date,
+            note,
+            band_id
+        )
+        .execute(crate::database::get_db())
+        .await?;
+        Ok(())
+    }
+
+    #[cfg(feature = "ssr")]
+    pub async fn set_available(band_id: i32, member_id: i32, date: NaiveDate) -> Result<(), AppError> {
+        sqlx::query!(
+            "
+DELETE FROM member_unavailability u USING members m
+WHERE u.member_id = $1 AND u.date = $2 AND m.id = u.member_id AND m.band_id = $3",
+            member_id,
+            date,
+            band_id
+        )
+        .execute(crate::database::get_db())
+        .await?;
+        Ok(())
+    }
+
+    /// Attendance of every member for every gig, as `(gig_id, attendance)`.
+    #[cfg(feature = "ssr")]
+    pub async fn get_gig_attendance(band_id: i32) -> Result<Vec<(i32, Attendance)>, AppError> {
+        sqlx::query_as!(
+            AttendanceRow,
+            r#"
+SELECT g.id as event_id, m.id, m.name, m.instrument, m.required,
+  a.status as "status?", u.note as "unavailable?"
+FROM gigs g CROSS JOIN members m
+LEFT JOIN gig_attendance a ON a.gig_id = g.id AND a.member_id = m.id
+LEFT JOIN member_unavailability u ON u.member_id = m.id AND u.date = g.date
+WHERE g.deleted_at IS NULL AND g.band_id = $1 AND m.band_id = $1
+ORDER BY g.id, m.name"#,
+            band_id
+        )
+        .fetch_all(crate::database::get_db())
+        .await
+        .map(|rows| rows.into_iter().map(Into::into).collect())
+        .map_err(AppError::from)
+    }
+
+    #[cfg(feature = "ssr")]
+    pub async fn get_attendance_for_gig(
+        band_id: i32,
+        gig_id: i32,
+    ) -> Result<Vec<Attendance>, AppError> {
+        sqlx::query_as!(
+            AttendanceRow,
+            r#"
+SELECT g.id as event_id, m.id, m.name, m.instrument, m.required,
+  a.status as "status?", u.note as "unavailable?"
+FROM gigs g CROSS JOIN members m
+LEFT JOIN gig_attendance a ON a.gig_id = g.id AND a.member_id = m.id
+LEFT JOIN member_unavailability u ON u.member_id = m.id AND u.date = g.date
+WHERE g.id = $1 AND g.band_id = $2 AND m.band_id = $2
+ORDER BY m.name"#,
+            gig_id,
+            band_id
+        )
+        .fetch_all(crate::database::get_db())
+        .await
+        .map(|rows| {
+            rows.into_iter()
+                .map(|row| <(i32, Attendance)>::from(row).1)
+                .collect()
+        })
+            .map_err(AppError::from)
+    }
+
+    #[cfg(feature = "ssr")]
+    pub async fn get_attendance_for_rehearsal(
+        band_id: i32,
+        rehearsal_id: i32,
+    ) -> Result<Vec<Attendance>, AppError> {
+        sqlx::query_as!(
+            AttendanceRow,
+            r#"
+SELECT r.id as event_id, m.id, m.name, m.instrument, m.required,
+  a.status as "status?", u.note as "unavailable?"
+FROM rehearsals r CROSS JOIN members m
+LEFT JOIN rehearsal_attendance a ON a.rehearsal_id = r.id AND a.member_id = m.id
+LEFT JOIN member_unavailability u ON u.member_id = m.id AND u.date = r.date
+WHERE r.id = $1 AND r.band_id = $2 AND m.band_id = $2
+ORDER BY m.name"#,
+            rehearsal_id,
+            band_id
+        )
+        .fetch_all(crate::database::get_db())
+        .await
+        .map(|rows| {
+            rows.into_iter()
+                .map(|row| <(i32, Attendance)>::from(row).1)
+                .collect()
+        })
+            .map_err(AppError::from)
+    }
+
+    /// Required members who marked `date` as unavailable.
+    #[cfg(feature = "ssr")]
+    pub async fn get_unavailable_on(
+        band_id: i32,
+        date: NaiveDate,
+    ) -> Result<Vec<Attendance>, AppError> {
+        sqlx::query!(
+            "
+SELECT m.id, m.name, m.instrument, m.required, u.note
+FROM members m JOIN member_unavailability u ON u.member_id = m.id
+WHERE m.required AND u.date = $1 AND m.band_id = $2
+ORDER BY m.name",
+            date,
+            band_id
+        )
+        .map(|row| Attendance {
+            member: Member {
+                id: row.id,
+                name: row.name,
+                instrument: row.instrument,
+                required: row.required,
+            },
+            rsvp: None,
+            unavailable: Some(row.note),
+        })
+        .fetch_all(crate::database::get_db())
+        .await
+        .map_err(AppError::from)
+    }
+
+    #[cfg(feature = "ssr")]
+    pub async fn set_gig_rsvp(
+        band_id: i32,
+        gig_id: i32,
+        member_id: i32,
+        rsvp: Rsvp,
+    ) -> Result<(), AppError> {
+        sqlx::query!(
+            "
+INSERT INTO gig_attendance (gig_id, member_id, status)
+SELECT $1, $2, $3
+WHERE EXISTS (SELECT 1 FROM gigs WHERE id = $1 AND band_id = $4)
+  AND EXISTS (SELECT 1 FROM members WHERE id = $2 AND band_id = $4)
+ON CONFLICT (gig_id, member_id) DO UPDATE SET status = EXCLUDED.status",
+            gig_id,
+            member_id,
+            rsvp.as_str(),
+            band_id
+        )
+        .execute(crate::database::get_db())
+        .await?;
+        Ok(())
+    }
+
+    #[cfg(feature = "ssr")]
+    pub async fn set_rehearsal_rsvp(
+        band_id: i32,
+        rehearsal_id: i32,
+        member_id: i32,
+        rsvp: Rsvp,
+    ) -> Result<(), AppError> {
+        sqlx::query!(
+            "
+INSERT INTO rehearsal_attendance (rehearsal_id, member_id, status)
+SELECT $1, $2, $3
+WHERE EXISTS (SELECT 1 FROM rehearsals WHERE id = $1 AND band_id = $4)
+  AND EXISTS (SELECT 1 FROM members WHERE id = $2 AND band_id = $4)
+ON CONFLICT (rehearsal_id, member_id) DO UPDATE SET status = EXCLUDED.status",
+            rehearsal_id,
+            member_id,
+            rsvp.as_str(),
+            band_id
+        )
+        .execute(crate::database::get_db())
+        .await?;
+        Ok(())
+    }
+}
@@ -0,0 +1,153 @@
+use std::fmt;
+use std::iter::Sum;
+use std::ops::{Add, Neg, Sub};
+use std::str::FromStr;
+
+use serde::{Deserialize, Serialize};
+
+/// An amount in euros, stored as whole cents so sums never drift. The band
+/// only gets paid in euros, so there is no currency to keep track of: parsing
+/// and display always use euros in Dutch notation.
+#[derive(
+    Debug, Serialize, Deserialize, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Default,
+)]
+#[serde(transparent)]
+pub struct Euros(i64);
+
+impl Euros {
+    pub const ZERO: Euros = Euros(0);
+
+    pub fn from_cents(cents: i64) -> Self {
+        Euros(cents)
+    }
+
+    pub fn cents(&self) -> i64 {
+        self.0
+    }
+
+    /// Plain decimal notation for exports, e.g. "-1234.50".
+    pub fn to_decimal(&self) -> String {
+        let sign = if self.0 < 0 { "-" } else { "" };
+        let abs = self.0.unsigned_abs();
+        format!("{}{}.{:02}", sign, abs / 100, abs % 100)
+    }
+
+    /// Splits the amount by `shares`, handing out the cents that don't divide
+    /// evenly one by one from the front, so the parts always add up to the
+    /// whole amount. When there are no shares at all every part is zero.
+    pub fn split(&self, shares: &[u32]) -> Vec<Euros> {
+        let total: i64 = shares.iter().map(|s| *s as i64).sum();
+        if total == 0 {
+            return shares.iter().map(|_| Euros::ZERO).collect();
+        }
+        let mut parts: Vec<i64> = shares
+            .iter()
+            .map(|s| self.0.div_euclid(total) * *s as i64)
+            .collect();
+        let mut remainder = self.0 - parts.iter().sum::<i64>();
+        for (part, share) in parts.iter_mut().zip(shares) {
+            let extra = remainder.min(*share as i64);
+            *part += extra;
+            remainder -= extra;
+        }
+        parts.into_iter().map(Euros).collect()
+    }
+}
+
+impl fmt::Display for Euros {
+    /// Dutch notation, e.g. "€ 1.234,50" or "-€ 12,00".
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let abs = self.0.unsigned_abs();
+        let euros = (abs / 100).to_string();
+        let mut grouped = String::with_capacity(euros.len() + euros.len() / 3);
+        for (i, c) in euros.chars().enumerate() {
+            if i > 0 && (euros.len() - i).is_multiple_of(3) {
+                grouped.push('.');
+            }
+            grouped.push(c);
+        }
+        let sign = if self.0 < 0 { "-" } else { "" };
+        write!(f, "{}€ {},{:02}", sign, grouped, abs % 100)
+    }
+}
+
+impl FromStr for Euros {
+    type Err = String;
+
+    /// Accepts "12", "12,5", "1.234,56", "1234.56" and "€ 12,00". When both
+    /// separators are used the last one is the decimal separator; a single
+    /// separator followed by exactly three digits groups thousands.
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let invalid = || format!("Ongeldig bedrag: {}", s);
+        let trimmed = s.trim().replace(['€', ' '], "");
+        let (negative, number) = match trimmed.strip_prefix('-') {
+            Some(rest) => (true, rest),
+            None => (false, trimmed.as_str()),
+        };
+        if number.is_empty() {
+            return Err(invalid());
+        }
+
+        let decimal_at = match (number.rfind(','), number.rfind('.')) {
+            (Some(c), Some(d)) => Some(c.max(d)),
+            (Some(i), None) | (None, Some(i)) => {
+                let separators = number.matches([',', '.']).count();
+                (separators == 1 && number.len() - i - 1 != 3).then_some(i)
+            }
+            (None, None) => None,
+        };
+        let (whole, fraction) = match decimal_at {
+            Some(i) => (&number[..i], &number[i + 1..]),
+            None => (number, ""),
+        };
+        let whole: String = whole.chars().filter(|c| *c != '.' && *c != ',').collect();
+        if (whole.is_empty() && fraction.is_empty())
+            || !whole.chars().all(|c| c.is_ascii_digit())
+            || !fraction.chars().all(|c| c.is_ascii_digit())
+            || fraction.len() > 2
+        {
+            return Err(invalid());
+        }
+
+        let euros: i64 = match whole.is_empty() {
+            true => 0,
+            false => whole.parse().map_err(|_| invalid())?,
+        };
+        let cents: i64 = format!("{:0<2}", fraction).parse().map_err(|_| invalid())?;
+        let amount = euros
+            .checked_mul(100)
+            .and_then(|e| e.checked_add(cents))
+            .ok_or_else(invalid)?;
+        Ok(Euros(if negative { -amount } else { amount }))
+    }
+}
+
+impl Add for Euros {
+    type Output = Euros;
+
+    fn add(self, rhs: Euros) -> Euros {
+        Euros(self.0 + rhs.0)
+    }
+}
+
+impl Sub for Euros {
+    type Output = Euros;
+
+    fn sub(self, rhs: Euros) -> Euros {
+        Euros(self.0 - rhs.0)
+    }
+}
+
+impl Neg for Euros {
+    type Output = Euros;
+
+    fn neg(self) -> Euros {
+        Euros(-self.0)
+    }
+}
+
+impl Sum for Euros {
+    fn sum<I: Iterator<Item = Euros>>(iter: I) -> Euros {
+        iter.fold(Euros::ZERO, Add::add)
+    }
+}
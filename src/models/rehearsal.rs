@@ -0,0 +1,232 @@
+use chrono::{NaiveDate, NaiveDateTime};
+use serde::{Deserialize, Serialize};
+
+#[cfg(feature = "ssr")]
+use crate::error::AppError;
+
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq, Eq, Hash)]
+pub struct RehearsalSong {
+    pub song_id: i32,
+    pub title: String,
+    pub artist: String,
+    pub notes: String,
+    pub played_at: NaiveDateTime,
+}
+
+/// A rehearsal with the songs that were actually played. A rehearsal without
+/// `closed_at` is in progress; there is at most one of those.
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq, Eq, Hash)]
+pub struct RehearsalSession {
+    pub id: i32,
+    pub date: NaiveDate,
+    pub location: String,
+    pub attendees: Vec<String>,
+    pub notes: String,
+    pub started_at: NaiveDateTime,
+    pub closed_at: Option<NaiveDateTime>,
+    pub songs: Vec<RehearsalSong>,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq, Eq)]
+pub struct RehearsalModel {
+    pub id: i32,
+    pub date: NaiveDate,
+    pub location: String,
+    pub attendees: Vec<String>,
+    pub notes: String,
+    pub started_at: NaiveDateTime,
+    pub closed_at: Option<NaiveDateTime>,
+}
+
+impl RehearsalSession {
+    /// Parses a comma separated list of names, keeping their spelling.
+    pub fn parse_attendees(attendees: &str) -> Vec<String> {
+        let mut parsed: Vec<String> = Vec::default();
+        for name in attendees.split(',').map(str::trim) {
+            if !name.is_empty() && !parsed.iter().any(|p| p.eq_ignore_ascii_case(name)) {
+                parsed.push(name.to_string());
+            }
+        }
+        parsed
+    }
+
+    pub fn is_played(&self, song_id: i32) -> bool {
+        self.songs.iter().any(|s| s.song_id == song_id)
+    }
+
+    /// Minutes between starting and closing, or until `now` while in progress.
+    pub fn duration_minutes(&self, now: NaiveDateTime) -> i64 {
+        (self.closed_at.unwrap_or(now) - self.started_at).num_minutes()
+    }
+
+    #[cfg(feature = "ssr")]
+    async fn with_songs(rehearsal: RehearsalModel) -> Result<Self, AppError> {
+        let songs = sqlx::query_as!(
+            RehearsalSong,
+            "
+SELECT rs.song_id, s.title, s.artist, rs.notes, rs.played_at
+FROM rehearsal_songs rs JOIN songs s ON s.id = rs.song_id
+WHERE rs.rehearsal_id = $1
+ORDER BY rs.played_at ASC",
+            rehearsal.id
+        )
+        .fetch_all(crate::database::get_db())
+        .await?;
+
+        Ok(RehearsalSession {
+            id: rehearsal.id,
+            date: rehearsal.date,
+            location: rehearsal.location,
+            attendees: rehearsal.attendees,
+            notes: rehearsal.notes,
+            started_at: rehearsal.started_at,
+            closed_at: rehearsal.closed_at,
+            songs,
+        })
+    }
+
+    #[cfg(feature = "ssr")]
+    pub async fn get_by_id(band_id: i32, id: i32) -> Result<Self, AppError> {
+        let rehearsal = sqlx::query_as!(
+            RehearsalModel,
+            "
+SELECT id, date, location, attendees, notes, started_at, closed_at
+FROM rehearsals WHERE id = $1 AND band_id = $2",
+            id,
+            band_id
+        )
+        .fetch_one(crate::database::get_db())
+        .await?;
+        Self::with_songs(rehearsal).await
+    }
+
+    #[cfg(feature = "ssr")]
+    pub async fn get_open(band_id: i32) -> Result<Option<Self>, AppError> {
+        let rehearsal = sqlx::query_as!(
+            RehearsalModel,
+            "
+SELECT id, date, location, attendees, notes, started_at, closed_at
+FROM rehearsals WHERE closed_at IS NULL AND band_id = $1",
+            band_id
+        )
+        .fetch_optional(crate::database::get_db())
+        .await?;
+        match rehearsal {
+            Some(rehearsal) => Ok(Some(Self::with_songs(rehearsal).await?)),
+            None => Ok(None),
+        }
+    }
+
+    #[cfg(feature = "ssr")]
+    pub async fn get_all(band_id: i32) -> Result<Vec<RehearsalModel>, AppError> {
+        sqlx::query_as!(
+            RehearsalModel,
+            "
+SELECT id, date, location, attendees, notes, started_at, closed_at
+FROM rehearsals WHERE band_id = $1
+ORDER BY date DESC, started_at DESC",
+            band_id
+        )
+        .fetch_all(crate::database::get_db())
+        .await
+        .map_err(AppError::from)
+    }
+
+    /// Starts a rehearsal today, or returns the one already in progress.
+    #[cfg(feature = "ssr")]
+    pub async fn start(
+        band_id: i32,
+        location: String,
+        attendees: Vec<String>,
+    ) -> Result<i32, AppError> {
+        if let Some(open) = Self::get_open(band_id).await? {
+            return Ok(open.id);
+        }
+        sqlx::query!(
+            "INSERT INTO rehearsals (location, attendees, band_id) VALUES ($1, $2, $3) RETURNING id",
+            location,
+            &attendees,
+            band_id
+        )
+        .map(|row| row.id)
+        .fetch_one(crate::database::get_db())
+        .await
+        .map_err(AppError::from)
+    }
+
+    /// Ticks a song off as played, or unticks it when it already was. Ticking
+    /// also updates the song's `last_played_at`.
+    #[cfg(feature = "ssr")]
+    pub async fn toggle_song(
+        repos: &crate::repository::Repositories,
+        rehearsal_id: i32,
+        song_id: i32,
+    ) -> Result<(), AppError> {
+        let removed = sqlx::query!(
+            "
+DELETE FROM rehearsal_songs rs USING rehearsals r
+WHERE rs.rehearsal_id = $1 AND rs.song_id = $2 AND r.id = rs.rehearsal_id AND r.band_id = $3",
+            rehearsal_id,
+            song_id,
+            repos.band_id
+        )
+        .execute(crate::database::get_db())
+        .await?
+        .rows_affected();
+
+        if removed == 0 {
+            let added = sqlx::query!(
+                "
+INSERT INTO rehearsal_songs (rehearsal_id, song_id)
+SELECT $1, $2
+WHERE EXISTS (SELECT 1 FROM rehearsals WHERE id = $1 AND band_id = $3)
+  AND EXISTS (SELECT 1 FROM songs WHERE id = $2 AND band_id = $3)",
+                rehearsal_id,
+                song_id,
+                repos.band_id
+            )
+            .execute(crate::database::get_db())
+            .await?
+            .rows_affected();
+            if added == 0 {
+                return Err(AppError::from(sqlx::Error::RowNotFound));
+            }
+            repos.songs.set_played(song_id).await?;
+        }
+        Ok(())
+    }
+
+    #[cfg(feature = "ssr")]
+    pub async fn set_song_notes(
+        band_id: i32,
+        rehearsal_id: i32,
+        song_id: i32,
+        notes: String,
+    ) -> Result<(), AppError> {
+        sqlx::query!(
+            "
+UPDATE rehearsal_songs rs SET notes = $3 FROM rehearsals r
+WHERE rs.rehearsal_id = $1 AND rs.song_id = $2 AND r.id = rs.rehearsal_id AND r.band_id = $4",
+            rehearsal_id,
+            song_id,
+            notes,
+            band_id
+        )
+        .execute(crate::database::get_db())
+        .await?;
+        Ok(())
+    }
+
+    #[cfg(feature = "ssr")]
+    pub async fn close(band_id: i32, rehearsal_id: i32, notes: String) -> Result<(), AppError> {
+        sqlx::query!(
+            "UPDATE rehearsals SET notes = $2, closed_at = CURRENT_TIMESTAMP WHERE id = $1 AND closed_at IS NULL AND band_id = $3",
+            rehearsal_id,
+            notes,
+            band_id
+        )
+        .execute(crate::database::get_db())
+        .await?;
+        Ok(())
+    }
+}
@@ -0,0 +1,209 @@
+use std::str::FromStr;
+
+use serde::{Deserialize, Serialize};
+
+#[cfg(feature = "ssr")]
+use super::song::Song;
+#[cfg(feature = "ssr")]
+use crate::error::AppError;
+use crate::i18n::Locale;
+
+/// Sections are counted in bars of four beats.
+pub const BEATS_PER_BAR: i32 = 4;
+
+#[cfg_attr(feature = "ssr", derive(utoipa::ToSchema))]
+#[derive(Debug, Deserialize, Serialize, Clone, Copy, PartialEq, Eq, Hash, Default)]
+#[serde(rename_all = "snake_case")]
+pub enum SectionKind {
+    Intro,
+    #[default]
+    Verse,
+    PreChorus,
+    Chorus,
+    Bridge,
+    Solo,
+    Interlude,
+    Outro,
+}
+
+impl SectionKind {
+    pub const ALL: [SectionKind; 8] = [
+        SectionKind::Intro,
+        SectionKind::Verse,
+        SectionKind::PreChorus,
+        SectionKind::Chorus,
+        SectionKind::Bridge,
+        SectionKind::Solo,
+        SectionKind::Interlude,
+        SectionKind::Outro,
+    ];
+
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            SectionKind::Intro => "intro",
+            SectionKind::Verse => "verse",
+            SectionKind::PreChorus => "pre_chorus",
+            SectionKind::Chorus => "chorus",
+            SectionKind::Bridge => "bridge",
+            SectionKind::Solo => "solo",
+            SectionKind::Interlude => "interlude",
+            SectionKind::Outro => "outro",
+        }
+    }
+
+    pub fn label(&self, locale: Locale) -> String {
+        locale.t(&format!("section-{}", self.as_str()))
+    }
+
+    /// Tailwind background for the timeline.
+    pub fn color(&self) -> &'static str {
+        match self {
+            SectionKind::Intro | SectionKind::Outro => "bg-ctp-overlay1",
+            SectionKind::Verse => "bg-ctp-blue",
+            SectionKind::PreChorus => "bg-ctp-sapphire",
+            SectionKind::Chorus => "bg-ctp-mauve",
+            SectionKind::Bridge => "bg-ctp-yellow",
+            SectionKind::Solo => "bg-ctp-red",
+            SectionKind::Interlude => "bg-ctp-teal",
+        }
+    }
+}
+
+impl FromStr for SectionKind {
+    type Err = String;
+
+    /// Accepts the stored name as well as the label in any language, in any
+    /// case.
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let s = s.trim().to_lowercase();
+        SectionKind::ALL
+            .into_iter()
+            .find(|k| {
+                k.as_str() == s
+                    || Locale::ALL
+                        .into_iter()
+                        .any(|locale| k.label(locale).to_lowercase() == s)
+            })
+            .ok_or_else(|| format!("Onbekend deel: {}", s))
+    }
+}
+
+/// A part of a song's arrangement, like "couplet 16 x2".
+#[cfg_attr(feature = "ssr", derive(utoipa::ToSchema))]
+#[derive(Debug, Deserialize, Serialize, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Section {
+    pub kind: SectionKind,
+    pub bars: i32,
+    pub repeats: i32,
+}
+
+impl Section {
+    pub fn total_bars(&self) -> i32 {
+        self.bars * self.repeats
+    }
+
+    /// Parses a comma separated arrangement like "intro 4, couplet 16 x2,
+    /// refrein 8". Each section is a kind, a bar count and optionally a repeat
+    /// count.
+    pub fn parse_list(arrangement: &str) -> Result<Vec<Section>, String> {
+        arrangement
+            .split(',')
+            .map(str::trim)
+            .filter(|s| !s.is_empty())
+            .map(|section| {
+                let parts: Vec<&str> = section.split_whitespace().collect();
+                let number = |part: Option<&&str>, what: &str| match part {
+                    Some(part) => part
+                        .trim_start_matches(['x', '×'])
+                        .parse::<i32>()
+                        .ok()
+                        .filter(|n| *n > 0)
+                        .ok_or_else(|| format!("Ongeldig aantal {} in \"{}\"", what, section)),
+                    None => Ok(1),
+                };
+                if !(2..=3).contains(&parts.len()) {
+                    return Err(format!(
+                        "Verwacht deel, maten en herhalingen in \"{}\"",
+                        section
+                    ));
+                }
+                Ok(Section {
+                    kind: parts[0].parse()?,
+                    bars: number(parts.get(1), "maten")?,
+                    repeats: number(parts.get(2), "herhalingen")?,
+                })
+            })
+            .collect()
+    }
+
+    /// The arrangement as written in the song form, see [`Section::parse_list`].
+    pub fn format_list(sections: &[Section], locale: Locale) -> String {
+        sections
+            .iter()
+            .map(|s| {
+                let kind = s.kind.label(locale).to_lowercase();
+                match s.repeats {
+                    1 => format!("{} {}", kind, s.bars),
+                    repeats => format!("{} {} x{}", kind, s.bars, repeats),
+                }
+            })
+            .collect::<Vec<_>>()
+            .join(", ")
+    }
+
+    /// How long the arrangement takes at `bpm`, in seconds.
+    pub fn playing_seconds(sections: &[Section], bpm: Option<i32>) -> Option<i32> {
+        let bpm = bpm.filter(|b| *b > 0)?;
+        let bars: i32 = sections.iter().map(Section::total_bars).sum();
+        (bars > 0).then(|| bars * BEATS_PER_BAR * 60 / bpm)
+    }
+
+    #[cfg(feature = "ssr")]
+    pub async fn attach(songs: &mut [Song]) -> Result<(), AppError> {
+        let ids: Vec<i32> = songs.iter().map(|s| s.id).collect();
+        let rows = sqlx::query!(
+            "SELECT song_id, kind, bars, repeats FROM song_sections WHERE song_id = ANY($1) ORDER BY position",
+            &ids
+        )
+        .fetch_all(crate::database::get_db())
+        .await?;
+        for song in songs.iter_mut() {
+            song.sections = rows
+                .iter()
+                .filter(|r| r.song_id == song.id)
+                .map(|r| Section {
+                    kind: r.kind.parse().unwrap_or_default(),
+                    bars: r.bars,
+                    repeats: r.repeats,
+                })
+                .collect();
+        }
+        Ok(())
+    }
+
+    /// Replaces the arrangement of a song, which the caller checked belongs
+    /// to its band, in the caller's transaction.
+    #[cfg(feature = "ssr")]
+    pub async fn set_for_song(
+        tx: &mut sqlx::PgConnection,
+        song_id: i32,
+        sections: &[Section],
+    ) -> Result<(), AppError> {
+        sqlx::query!("DELETE FROM song_sections WHERE song_id = $1", song_id)
+            .execute(&mut *tx)
+            .await?;
+        for (position, section) in sections.iter().enumerate() {
+            sqlx::query!(
+                "INSERT INTO song_sections (song_id, position, kind, bars, repeats) VALUES ($1, $2, $3, $4, $5)",
+                song_id,
+                position as i32,
+                section.kind.as_str(),
+                section.bars,
+                section.repeats
+            )
+            .execute(&mut *tx)
+            .await?;
+        }
+        Ok(())
+    }
+}
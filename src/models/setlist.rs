@@ -1,5 +1,12 @@
 use serde::{Deserialize, Serialize};
 
+use crate::error::AppError;
+#[cfg(feature = "ssr")]
+use crate::models::audit::{AuditAction, AuditEntry};
+#[cfg(feature = "ssr")]
+use crate::repository::Repositories;
+
+#[cfg_attr(feature = "ssr", derive(utoipa::ToSchema))]
 #[derive(Debug, Serialize, Deserialize, Clone, PartialEq, Eq)]
 pub struct Setlist {
     pub id: i32,
@@ -9,57 +16,48 @@ pub struct Setlist {
 }
 
 impl Setlist {
-    #[cfg(feature = "ssr")]
-    pub async fn get_by_id(id: i32) -> Result<Self, sqlx::Error> {
-        sqlx::query_as!(Setlist, "SELECT * FROM setlists WHERE id = $1", id)
-            .fetch_one(crate::database::get_db())
-            .await
-    }
-
-    #[cfg(feature = "ssr")]
-    pub async fn get() -> Result<Self, sqlx::Error> {
-        sqlx::query_as!(Setlist, "SELECT * FROM setlists WHERE title = 'Oefenen'")
-            .fetch_one(crate::database::get_db())
-            .await
+    /// The songs of `songs` that aren't in the setlist yet, in the given order
+    /// and each only once.
+    pub fn new_songs(&self, songs: &[i32]) -> Vec<i32> {
+        let mut new_songs: Vec<i32> = Vec::default();
+        for song in songs {
+            if !self.songs.contains(song) && !new_songs.contains(song) {
+                new_songs.push(*song);
+            }
+        }
+        new_songs
     }
 
-    #[cfg(feature = "ssr")]
-    pub async fn set_songs(songs: Vec<i32>) -> Result<(), sqlx::Error> {
-        let existing_songs = Setlist::get().await?.songs;
-        let new_songs = songs
-            .iter()
-            .filter(|s| !existing_songs.contains(s))
-            .map(|s| s.to_owned())
-            .collect::<Vec<i32>>();
-
-        match Setlist::get().await {
-            Ok(setlist) => {
-                if setlist.is_locked {
-                    return Err(sqlx::Error::RowNotFound);
-                };
-                sqlx::query!(
-                    "UPDATE setlists SET songs = array_cat(songs, $1) WHERE id = 1",
-                    &new_songs
-                )
-                .execute(crate::database::get_db())
-                .await
-                .map(|_| ())
-            }
-            Err(e) => return Err(e),
+    /// Fails for a locked setlist, which can't be changed from the app.
+    pub fn check_unlocked(&self) -> Result<(), AppError> {
+        match self.is_locked {
+            true => Err(AppError::Locked(format!(
+                "setlist {} is vergrendeld",
+                self.title
+            ))),
+            false => Ok(()),
         }
     }
 
+    /// Adds songs to the practice setlist, skipping the ones already on it.
     #[cfg(feature = "ssr")]
-    pub async fn clean() -> Result<(), sqlx::Error> {
-        sqlx::query!("UPDATE setlists SET songs = '{}' WHERE id = 1")
-            .execute(crate::database::get_db())
-            .await
-            .map(|_| ())
+    pub async fn set_songs(repos: &Repositories, songs: Vec<i32>) -> Result<(), AppError> {
+        let practice = repos.setlists.get_practice().await?;
+        let before = repos.setlists.add_songs(practice.id, &songs).await?;
+        if before.new_songs(&songs).is_empty() {
+            return Ok(());
+        }
+        AuditEntry::setlist_changed(repos, AuditAction::Update, &before).await
     }
 
+    /// Takes all songs off the practice setlist, which can be undone.
     #[cfg(feature = "ssr")]
-    pub async fn song_in_setlist(song_id: i32) -> Result<bool, sqlx::Error> {
-        let setlist = Setlist::get().await?;
-        Ok(setlist.songs.contains(&song_id))
+    pub async fn clean(repos: &Repositories) -> Result<(), AppError> {
+        let setlist = repos.setlists.get_practice().await?;
+        if setlist.songs.is_empty() {
+            return Ok(());
+        }
+        repos.setlists.set_songs(setlist.id, &[]).await?;
+        AuditEntry::setlist_changed(repos, AuditAction::Clear, &setlist).await
     }
 }
@@ -0,0 +1,264 @@
+use serde::{Deserialize, Serialize};
+
+#[cfg(feature = "ssr")]
+use super::song::{Readiness, Song};
+#[cfg(feature = "ssr")]
+use crate::i18n::Locale;
+
+/// Assumed length of a song that has no duration set.
+pub const DEFAULT_SONG_SECONDS: i32 = 240;
+
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq, Eq)]
+pub struct GeneratorSettings {
+    pub target_minutes: u32,
+    pub sets: u32,
+    pub pause_minutes: u32,
+    pub opener_tag: Option<String>,
+    pub closer_tag: Option<String>,
+    pub avoid_same_artist: bool,
+    pub seed: u64,
+}
+
+impl Default for GeneratorSettings {
+    fn default() -> Self {
+        Self {
+            target_minutes: 120,
+            sets: 2,
+            pause_minutes: 15,
+            opener_tag: Some("opener".to_string()),
+            closer_tag: Some("closer".to_string()),
+            avoid_same_artist: true,
+            seed: 0,
+        }
+    }
+}
+
+/// A generated running order in the same shape as `gigs.songs`: song ids with
+/// negative ids for the breaks between sets.
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq, Eq, Default)]
+pub struct GeneratedSetlist {
+    pub songs: Vec<i32>,
+    pub explanations: Vec<String>,
+    pub playing_seconds: i32,
+}
+
+#[cfg(feature = "ssr")]
+fn duration(song: &Song) -> i32 {
+    song.length_seconds().unwrap_or(DEFAULT_SONG_SECONDS)
+}
+
+/// Energy of a song between 0 and 1, relative to the slowest and fastest song
+/// in the pool. Songs without a bpm sit in the middle.
+#[cfg(feature = "ssr")]
+fn energy(song: &Song, bpm_range: (i32, i32)) -> f64 {
+    match song.bpm.filter(|b| *b > 0) {
+        Some(bpm) if bpm_range.1 > bpm_range.0 => {
+            (bpm - bpm_range.0) as f64 / (bpm_range.1 - bpm_range.0) as f64
+        }
+        _ => 0.5,
+    }
+}
+
+/// The energy we aim for at `progress` (0..1) through a set: start high, dip in
+/// the middle and build up again towards the closer.
+#[cfg(feature = "ssr")]
+fn target_energy(progress: f64) -> f64 {
+    0.9 - 0.4 * (std::f64::consts::PI * progress.clamp(0.0, 1.0)).sin()
+}
+
+#[cfg(feature = "ssr")]
+fn format_minutes(seconds: i32) -> String {
+    format!("{}:{:02}", seconds / 3600, (seconds % 3600) / 60)
+}
+
+/// Takes the song to open or close a set with: a song with `tag` if there is
+/// one, otherwise the song with the most energy. Returns the index in `pool`
+/// and why it was picked.
+#[cfg(feature = "ssr")]
+fn pick_anchor(
+    pool: &[&Song],
+    tag: Option<&String>,
+    previous_artist: Option<&str>,
+    bpm_range: (i32, i32),
+    locale: Locale,
+) -> Option<(usize, String)> {
+    let allowed = |s: &&Song| previous_artist != Some(s.artist.as_str());
+    let by_tag = tag.and_then(|tag| {
+        pool.iter()
+            .position(|s| s.tags.contains(tag) && allowed(s))
+            .or_else(|| pool.iter().position(|s| s.tags.contains(tag)))
+            .map(|i| (i, locale.t_with("generator-tag", &[("tag", tag.clone().into())])))
+    });
+    by_tag.or_else(|| {
+        pool.iter()
+            .enumerate()
+            .max_by(|(ia, a), (ib, b)| {
+                (allowed(a), energy(a, bpm_range))
+                    .partial_cmp(&(allowed(b), energy(b, bpm_range)))
+                    .unwrap_or(std::cmp::Ordering::Equal)
+                    .then(ib.cmp(ia))
+            })
+            .map(|(i, s)| {
+                let bpm = s.bpm.unwrap_or_default();
+                (i, locale.t_with("generator-most-energy", &[("bpm", bpm.into())]))
+            })
+    })
+}
+
+/// Generates a running order of roughly `target_minutes`, split into `sets`
+/// with breaks in between. Only songs that are ready are used. The outcome is
+/// fully determined by `songs` and `settings`, including the seed. The
+/// explanations are written in `locale`.
+#[cfg(feature = "ssr")]
+pub fn generate(songs: &[Song], settings: &GeneratorSettings, locale: Locale) -> GeneratedSetlist {
+    use rand::rngs::StdRng;
+    use rand::seq::SliceRandom;
+    use rand::{Rng, SeedableRng};
+
+    let mut rng = StdRng::seed_from_u64(settings.seed);
+    let mut pool: Vec<&Song> = songs
+        .iter()
+        .filter(|s| s.readiness == Readiness::Ready)
+        .collect();
+    pool.sort_by_key(|s| s.id);
+    pool.shuffle(&mut rng);
+
+    let bpms = pool.iter().filter_map(|s| s.bpm.filter(|b| *b > 0));
+    let bpm_range = (bpms.clone().min().unwrap_or(0), bpms.max().unwrap_or(0));
+
+    let sets = settings.sets.max(1) as i32;
+    let pause_seconds = settings.pause_minutes as i32 * 60;
+    let playing_budget = (settings.target_minutes as i32 * 60 - pause_seconds * (sets - 1)).max(0);
+    let set_budget = playing_budget / sets;
+
+    let mut result = GeneratedSetlist::default();
+    for set in 1..=sets {
+        if set > 1 {
+            result.songs.push(-(set - 1));
+            result.explanations.push(locale.t_with(
+                "generator-break",
+                &[("minutes", settings.pause_minutes.into())],
+            ));
+        }
+        if pool.is_empty() {
+            result
+                .explanations
+                .push(locale.t_with("generator-no-songs-left", &[("set", set.into())]));
+            continue;
+        }
+
+        let mut set_seconds = 0;
+        let mut previous_artist: Option<String> = None;
+
+        if let Some((index, reason)) =
+            pick_anchor(&pool, settings.opener_tag.as_ref(), None, bpm_range, locale)
+        {
+            let song = pool.remove(index);
+            set_seconds += duration(song);
+            previous_artist = Some(song.artist.clone());
+            result.songs.push(song.id);
+            result.explanations.push(locale.t_with(
+                "generator-opener",
+                &[
+                    ("set", set.into()),
+                    ("title", song.title.clone().into()),
+                    ("reason", reason.into()),
+                ],
+            ));
+        }
+
+        // Keep room for the closer, without committing to it yet so it can
+        // still respect the artist of the song before it.
+        let closer_reserve =
+            pick_anchor(&pool, settings.closer_tag.as_ref(), None, bpm_range, locale)
+            .map(|(i, _)| duration(pool[i]))
+            .unwrap_or(0);
+        let middle_budget = set_budget - closer_reserve;
+
+        loop {
+            let progress = set_seconds as f64 / set_budget.max(1) as f64;
+            let target = target_energy(progress);
+            let fits = |s: &&&Song| set_seconds + duration(s) <= middle_budget;
+            let other_artist = |s: &&&Song| {
+                !settings.avoid_same_artist || previous_artist.as_ref() != Some(&s.artist)
+            };
+
+            // Songs tagged as opener or closer are saved for those spots
+            // unless nothing else fits.
+            let is_anchor = |s: &&&Song| {
+                [&settings.opener_tag, &settings.closer_tag]
+                    .into_iter()
+                    .flatten()
+                    .any(|tag| s.tags.contains(tag))
+            };
+            let select = |filter: &dyn Fn(&&&Song) -> bool| -> Vec<(usize, &&Song)> {
+                pool.iter().enumerate().filter(|(_, s)| filter(s)).collect()
+            };
+            let mut forced = false;
+            let mut candidates = select(&|s| fits(s) && other_artist(s) && !is_anchor(s));
+            if candidates.is_empty() {
+                candidates = select(&|s| fits(s) && other_artist(s));
+            }
+            if candidates.is_empty() {
+                forced = true;
+                candidates = select(&fits);
+            }
+
+            let best = candidates
+                .into_iter()
+                .map(|(i, s)| {
+                    let jitter: f64 = rng.gen_range(0.0..0.05);
+                    (i, (energy(s, bpm_range) - target).abs() + jitter)
+                })
+                .min_by(|a, b| a.1.partial_cmp(&b.1).unwrap_or(std::cmp::Ordering::Equal));
+            let Some((index, _)) = best else {
+                break;
+            };
+
+            let song = pool.remove(index);
+            set_seconds += duration(song);
+            result.songs.push(song.id);
+            let title = ("title", song.title.clone().into());
+            result.explanations.push(match forced {
+                true => locale.t_with("generator-forced", &[title]),
+                false => locale.t_with(
+                    "generator-fits",
+                    &[title, ("bpm", song.bpm.unwrap_or_default().into())],
+                ),
+            });
+            previous_artist = Some(song.artist.clone());
+        }
+
+        let previous_artist = previous_artist.filter(|_| settings.avoid_same_artist);
+        if let Some((index, reason)) = pick_anchor(
+            &pool,
+            settings.closer_tag.as_ref(),
+            previous_artist.as_deref(),
+            bpm_range,
+            locale,
+        ) {
+            let song = pool.remove(index);
+            set_seconds += duration(song);
+            result.songs.push(song.id);
+            result.explanations.push(locale.t_with(
+                "generator-closer",
+                &[
+                    ("set", set.into()),
+                    ("title", song.title.clone().into()),
+                    ("reason", reason.into()),
+                ],
+            ));
+        }
+
+        result.playing_seconds += set_seconds;
+    }
+
+    result.explanations.push(locale.t_with(
+        "generator-total",
+        &[
+            ("playing", format_minutes(result.playing_seconds).into()),
+            ("available", format_minutes(playing_budget).into()),
+        ],
+    ));
+    result
+}
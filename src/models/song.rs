@@ -1,7 +1,16 @@
+use std::str::FromStr;
+
 use chrono::NaiveDate;
+use leptos_router::ParamsMap;
 use serde::{Deserialize, Serialize};
 
-use crate::components::edit_song::EditSongData;
+#[cfg(feature = "ssr")]
+use crate::error::AppError;
+use crate::models::key::Key;
+use crate::models::section::Section;
+use crate::models::song_role::SongRole;
+#[cfg(feature = "ssr")]
+use crate::repository::Repositories;
 
 #[derive(Debug, Deserialize, Serialize, Clone, PartialEq, Eq, Hash, Default)]
 pub struct Rehearsal {
@@ -9,6 +18,7 @@ pub struct Rehearsal {
     pub selected_songs: Vec<Song>,
 }
 
+#[cfg_attr(feature = "ssr", derive(utoipa::ToSchema))]
 #[derive(Debug, Deserialize, Serialize, Clone, PartialEq, Eq, Hash, Default)]
 pub struct Song {
     pub id: i32,
@@ -20,43 +30,173 @@ pub struct Song {
     pub bpm: Option<i32>,
     pub gs_url: Option<String>,
     pub lyrics: String,
+    pub readiness: Readiness,
+    pub tags: Vec<String>,
+    /// Length in seconds, if known.
+    pub duration: Option<i32>,
+    pub roles: Vec<SongRole>,
+    /// The arrangement in playing order, empty when not written down.
+    pub sections: Vec<Section>,
+    /// From the song form, or the ChordPro `{key:}` in the lyrics.
+    pub key: Option<Key>,
+}
+
+/// How far along the band is with a song.
+#[cfg_attr(feature = "ssr", derive(utoipa::ToSchema))]
+#[derive(Debug, Deserialize, Serialize, Clone, Copy, PartialEq, Eq, Hash, Default)]
+#[serde(rename_all = "lowercase")]
+pub enum Readiness {
+    New,
+    Learning,
+    #[default]
+    Ready,
+}
+
+impl Readiness {
+    pub const ALL: [Readiness; 3] = [Readiness::New, Readiness::Learning, Readiness::Ready];
+
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Readiness::New => "new",
+            Readiness::Learning => "learning",
+            Readiness::Ready => "ready",
+        }
+    }
+}
+
+impl FromStr for Readiness {
+    type Err = String;
+
+    fn from_str(s: &str) -> std::result::Result<Self, Self::Err> {
+        Readiness::ALL
+            .into_iter()
+            .find(|r| r.as_str() == s)
+            .ok_or_else(|| format!("Unknown readiness: {}", s))
+    }
+}
+
+#[derive(Debug, Deserialize, Serialize, Clone, Copy, PartialEq, Eq, Hash, Default)]
+#[serde(rename_all = "snake_case")]
+pub enum SongSort {
+    #[default]
+    Title,
+    Artist,
+    LastPlayed,
+    Bpm,
+}
+
+impl SongSort {
+    pub const ALL: [SongSort; 4] = [
+        SongSort::Title,
+        SongSort::Artist,
+        SongSort::LastPlayed,
+        SongSort::Bpm,
+    ];
+
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            SongSort::Title => "title",
+            SongSort::Artist => "artist",
+            SongSort::LastPlayed => "last_played",
+            SongSort::Bpm => "bpm",
+        }
+    }
+}
+
+impl FromStr for SongSort {
+    type Err = String;
+
+    fn from_str(s: &str) -> std::result::Result<Self, Self::Err> {
+        SongSort::ALL
+            .into_iter()
+            .find(|sort| sort.as_str() == s)
+            .ok_or_else(|| format!("Unknown sort: {}", s))
+    }
+}
+
+/// Search, filter and sort state of the song list. It round-trips through the
+/// URL query string so a filtered view can be shared as a link.
+#[derive(Debug, Deserialize, Serialize, Clone, PartialEq, Eq, Hash, Default)]
+pub struct SongFilter {
+    pub query: Option<String>,
+    pub readiness: Option<Readiness>,
+    pub min_bpm: Option<i32>,
+    pub max_bpm: Option<i32>,
+    pub not_played_days: Option<i32>,
+    pub tag: Option<String>,
+    pub sort: SongSort,
+}
+
+impl SongFilter {
+    /// Builds a filter from URL query parameters. Empty or malformed values are
+    /// ignored rather than rejected, as they come straight from a GET form.
+    pub fn from_query(params: &ParamsMap) -> Self {
+        fn parse<T: FromStr>(value: Option<&String>) -> Option<T> {
+            value
+                .map(|v| v.trim())
+                .filter(|v| !v.is_empty())
+                .and_then(|v| v.parse().ok())
+        }
+
+        SongFilter {
+            query: parse(params.get("query")),
+            readiness: parse(params.get("readiness")),
+            min_bpm: parse(params.get("min_bpm")),
+            max_bpm: parse(params.get("max_bpm")),
+            not_played_days: parse(params.get("not_played_days")),
+            tag: parse(params.get("tag")),
+            sort: parse(params.get("sort")).unwrap_or_default(),
+        }
+    }
+
+    pub fn is_active(&self) -> bool {
+        self != &SongFilter::default()
+    }
 }
 
 #[cfg(feature = "ssr")]
-type Result<T> = std::result::Result<T, sqlx::Error>;
+type Result<T> = std::result::Result<T, AppError>;
 
 impl Song {
+    /// The known length in seconds, else the length of the arrangement at the
+    /// song's bpm.
+    pub fn length_seconds(&self) -> Option<i32> {
+        self.duration
+            .filter(|d| *d > 0)
+            .or_else(|| Section::playing_seconds(&self.sections, self.bpm))
+    }
+
     #[cfg(feature = "ssr")]
-    pub async fn get(song_id: i32) -> Result<Self> {
-        sqlx::query!(
-            "
-        SELECT 
-          * 
-        FROM songs as s 
-        WHERE s.id = $1",
-            song_id
-        )
-        .map(|row| Song {
-            id: row.id,
-            artist: row.artist,
-            title: row.title,
-            last_played_at: row.last_played_at,
-            release_mid: row.release_mid,
-            artist_mid: row.artist_mid,
-            gs_url: row.gs_url,
-            bpm: row.bpm,
-            lyrics: row.lyrics,
-        })
-        .fetch_one(crate::database::get_db())
-        .await
+    pub async fn update(
+        repos: &Repositories,
+        mut data: crate::components::edit_song::EditSongData,
+    ) -> Result<()> {
+        use super::annotation::Annotation;
+        use super::audit::{current_actor, AuditEntry};
+        use super::song_revision::SongRevision;
+
+        Section::parse_list(&data.arrangement).map_err(AppError::Validation)?;
+        if Key::parse_optional(&data.key).map_err(AppError::Validation)?.is_none() {
+            // Without a key of its own the song takes the one in its ChordPro.
+            data.key = Key::from_chordpro(&data.lyrics)
+                .map(|key| key.to_string())
+                .unwrap_or_default();
+        }
+        let before = repos.songs.get(data.id).await?;
+        let lyrics = (data.lyrics != before.lyrics).then(|| data.lyrics.clone());
+        repos.songs.update(data).await?;
+        if let Some(lyrics) = lyrics {
+            Annotation::reanchor(repos, before.id, &lyrics).await?;
+        }
+        SongRevision::record(repos, &before, &current_actor()).await?;
+        AuditEntry::song_changed(repos, &before).await
     }
 
     #[cfg(feature = "ssr")]
-    pub async fn get_all_in_setlist(setlist_id: i32) -> Result<Vec<Song>> {
-        use super::setlist::Setlist;
-        let setlist_songs = Setlist::get_by_id(setlist_id).await?.songs;
+    pub async fn get_all_in_setlist(repos: &Repositories, setlist_id: i32) -> Result<Vec<Song>> {
+        let setlist_songs = repos.setlists.get(setlist_id).await?.songs;
 
-        let all = Self::get_all().await?;
+        let all = repos.songs.get_all().await?;
         Ok(all
             .into_iter()
             .filter(|song| setlist_songs.contains(&song.id))
@@ -64,9 +204,12 @@ impl Song {
     }
 
     #[cfg(feature = "ssr")]
-    pub async fn get_rehearsal() -> Result<Rehearsal> {
-        let selected = Self::get_all_in_setlist(1).await?;
-        let unselected = Self::get_all()
+    pub async fn get_rehearsal(repos: &Repositories, filter: &SongFilter) -> Result<Rehearsal> {
+        let practice = repos.setlists.get_practice().await?;
+        let selected = Self::get_all_in_setlist(repos, practice.id).await?;
+        let unselected = repos
+            .songs
+            .search(filter)
             .await?
             .into_iter()
             .filter(|song| !selected.contains(song))
@@ -76,54 +219,4 @@ impl Song {
             unselected_songs: unselected,
         })
     }
-
-    #[cfg(feature = "ssr")]
-    pub async fn get_all() -> Result<Vec<Self>> {
-        sqlx::query!(
-            "SELECT 
-              * 
-            FROM songs as s 
-            ORDER BY 
-              s.title ASC"
-        )
-        .map(|row| Song {
-            id: row.id,
-            artist: row.artist,
-            title: row.title,
-            gs_url: row.gs_url,
-            last_played_at: row.last_played_at,
-            release_mid: row.release_mid,
-            artist_mid: row.artist_mid,
-            bpm: row.bpm,
-            lyrics: row.lyrics,
-        })
-        .fetch_all(crate::database::get_db())
-        .await
-    }
-
-    #[cfg(feature = "ssr")]
-    pub async fn set_played(song_id: i32) -> Result<()> {
-        sqlx::query!(
-            "UPDATE songs SET last_played_at = CURRENT_DATE WHERE id = $1",
-            song_id
-        )
-        .execute(crate::database::get_db())
-        .await
-        .map(|_| ())
-    }
-
-    #[cfg(feature = "ssr")]
-    pub async fn update(data: EditSongData) -> Result<()> {
-        sqlx::query!(
-            "UPDATE songs SET artist=$2, title=$3, bpm=$4, lyrics = $5 WHERE id = $1",
-            data.id,
-            data.artist,
-            data.title,
-            data.bpm,
-            data.lyrics
-        )
-        .execute(crate::database::get_db())
-        .await
-        .map(|_| ())
-    }
 }
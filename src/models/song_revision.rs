@@ -0,0 +1,205 @@
+use chrono::NaiveDateTime;
+use serde::{Deserialize, Serialize};
+
+use super::key::Key;
+use super::section::Section;
+use super::song::{Readiness, Song};
+use crate::components::edit_song::EditSongData;
+#[cfg(feature = "ssr")]
+use crate::error::AppError;
+use crate::i18n::Locale;
+#[cfg(feature = "ssr")]
+use crate::repository::Repositories;
+
+/// A saved state of the fields that can be edited on the song page.
+#[derive(Debug, Deserialize, Serialize, Clone, PartialEq, Eq)]
+pub struct SongRevision {
+    pub id: i32,
+    pub song_id: i32,
+    pub created_at: NaiveDateTime,
+    pub author: String,
+    pub artist: String,
+    pub title: String,
+    pub bpm: Option<i32>,
+    pub lyrics: String,
+    pub readiness: Readiness,
+    pub duration: Option<i32>,
+    pub tags: Vec<String>,
+    /// As written in the Dutch song form, see [`Section::format_list`].
+    pub arrangement: String,
+    pub key: Option<Key>,
+}
+
+/// A revision about to be stored.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct NewSongRevision {
+    pub song_id: i32,
+    pub author: String,
+    pub artist: String,
+    pub title: String,
+    pub bpm: Option<i32>,
+    pub lyrics: String,
+    pub readiness: Readiness,
+    pub duration: Option<i32>,
+    pub tags: Vec<String>,
+    pub arrangement: String,
+    pub key: Option<Key>,
+}
+
+impl NewSongRevision {
+    pub fn from_song(song: &Song, author: &str) -> Self {
+        Self {
+            song_id: song.id,
+            author: author.to_string(),
+            artist: song.artist.clone(),
+            title: song.title.clone(),
+            bpm: song.bpm,
+            lyrics: song.lyrics.clone(),
+            readiness: song.readiness,
+            duration: song.duration,
+            tags: song.tags.clone(),
+            // In one language, so revisions compare equal whoever edits them.
+            arrangement: Section::format_list(&song.sections, Locale::Nl),
+            key: song.key.clone(),
+        }
+    }
+
+    /// Whether the revision holds the same song as `revision`, ignoring who
+    /// made them and when.
+    pub fn same_as(&self, revision: &SongRevision) -> bool {
+        self.artist == revision.artist
+            && self.title == revision.title
+            && self.bpm == revision.bpm
+            && self.lyrics == revision.lyrics
+            && self.readiness == revision.readiness
+            && self.duration == revision.duration
+            && self.tags == revision.tags
+            && self.arrangement == revision.arrangement
+            && self.key == revision.key
+    }
+}
+
+/// One line of the lyrics diff between two revisions.
+#[derive(Debug, Deserialize, Serialize, Clone, PartialEq, Eq)]
+pub enum DiffLine {
+    Same(String),
+    Added(String),
+    Removed(String),
+}
+
+/// Line by line difference from `old` to `new`, based on the longest common
+/// subsequence. Lyrics are short enough for the quadratic table.
+pub fn diff_lines(old: &str, new: &str) -> Vec<DiffLine> {
+    let old: Vec<&str> = old.lines().collect();
+    let new: Vec<&str> = new.lines().collect();
+    // common[i][j] is the length of the LCS of old[i..] and new[j..].
+    let mut common = vec![vec![0usize; new.len() + 1]; old.len() + 1];
+    for i in (0..old.len()).rev() {
+        for j in (0..new.len()).rev() {
+            common[i][j] = match old[i] == new[j] {
+                true => common[i + 1][j + 1] + 1,
+                false => common[i + 1][j].max(common[i][j + 1]),
+            };
+        }
+    }
+
+    let (mut i, mut j) = (0, 0);
+    let mut lines = Vec::with_capacity(old.len().max(new.len()));
+    while i < old.len() || j < new.len() {
+        if i < old.len() && j < new.len() && old[i] == new[j] {
+            lines.push(DiffLine::Same(old[i].to_string()));
+            i += 1;
+            j += 1;
+        } else if j < new.len() && (i == old.len() || common[i][j + 1] >= common[i + 1][j]) {
+            lines.push(DiffLine::Added(new[j].to_string()));
+            j += 1;
+        } else {
+            lines.push(DiffLine::Removed(old[i].to_string()));
+            i += 1;
+        }
+    }
+    lines
+}
+
+fn optional_key(key: &Option<Key>) -> String {
+    key.as_ref().map(Key::to_string).unwrap_or_default()
+}
+
+impl SongRevision {
+    /// The fields other than the lyrics that differ from `older`, as label,
+    /// old value and new value.
+    pub fn changed_fields(&self, older: &SongRevision, locale: Locale) -> Vec<(String, String, String)> {
+        let optional = |value: Option<i32>| value.map(|v| v.to_string()).unwrap_or_default();
+        let readiness = |r: Readiness| locale.t(&format!("readiness-{}", r.as_str()));
+        [
+            ("artist", older.artist.clone(), self.artist.clone()),
+            ("title", older.title.clone(), self.title.clone()),
+            ("bpm", optional(older.bpm), optional(self.bpm)),
+            ("readiness", readiness(older.readiness), readiness(self.readiness)),
+            ("duration", optional(older.duration), optional(self.duration)),
+            ("tags", older.tags.join(", "), self.tags.join(", ")),
+            ("arrangement", older.arrangement.clone(), self.arrangement.clone()),
+            ("key", optional_key(&older.key), optional_key(&self.key)),
+        ]
+        .into_iter()
+        .filter(|(_, old, new)| old != new)
+        .map(|(field, old, new)| (locale.t(&format!("revision-{}", field)), old, new))
+        .collect()
+    }
+
+    pub fn lyrics_diff(&self, older: &SongRevision) -> Vec<DiffLine> {
+        diff_lines(&older.lyrics, &self.lyrics)
+    }
+
+    /// The edit that brings the song back to this revision.
+    pub fn to_edit_data(&self) -> EditSongData {
+        EditSongData {
+            id: self.song_id,
+            artist: self.artist.clone(),
+            title: self.title.clone(),
+            bpm: self.bpm.unwrap_or_default(),
+            lyrics: self.lyrics.clone(),
+            readiness: self.readiness,
+            duration: self.duration.unwrap_or_default(),
+            tags: self.tags.join(", "),
+            arrangement: self.arrangement.clone(),
+            key: optional_key(&self.key),
+        }
+    }
+
+    /// Stores the song as it is now, unless that's what the newest revision
+    /// already holds. Songs from before revisions were kept get their state
+    /// before the first edit stored too, so that one can be restored.
+    #[cfg(feature = "ssr")]
+    pub async fn record(repos: &Repositories, before: &Song, author: &str) -> Result<(), AppError> {
+        use super::audit::UNKNOWN_ACTOR;
+
+        let latest = match repos
+            .revisions
+            .get_for_song(before.id)
+            .await?
+            .into_iter()
+            .next()
+        {
+            Some(latest) => latest,
+            None => {
+                repos
+                    .revisions
+                    .record(NewSongRevision::from_song(before, UNKNOWN_ACTOR))
+                    .await?
+            }
+        };
+        let after = NewSongRevision::from_song(&repos.songs.get(before.id).await?, author);
+        if !after.same_as(&latest) {
+            repos.revisions.record(after).await?;
+        }
+        Ok(())
+    }
+
+    /// Brings the song back to this revision, which is stored as a new edit.
+    #[cfg(feature = "ssr")]
+    pub async fn restore(repos: &Repositories, revision_id: i32) -> Result<(), AppError> {
+        let revision = repos.revisions.get(revision_id).await?;
+        Song::update(repos, revision.to_edit_data()).await
+    }
+}
@@ -0,0 +1,229 @@
+use std::str::FromStr;
+
+use serde::{Deserialize, Serialize};
+
+use super::gig::GigSong;
+use super::song::Song;
+#[cfg(feature = "ssr")]
+use crate::error::AppError;
+use crate::i18n::Locale;
+
+/// What a member does in a song apart from playing their usual part.
+#[cfg_attr(feature = "ssr", derive(utoipa::ToSchema))]
+#[derive(Debug, Deserialize, Serialize, Clone, Copy, PartialEq, Eq, Hash, Default)]
+#[serde(rename_all = "snake_case")]
+pub enum RoleKind {
+    #[default]
+    LeadVocal,
+    Backing,
+    Tuning,
+    Capo,
+    Instrument,
+}
+
+impl RoleKind {
+    pub const ALL: [RoleKind; 5] = [
+        RoleKind::LeadVocal,
+        RoleKind::Backing,
+        RoleKind::Tuning,
+        RoleKind::Capo,
+        RoleKind::Instrument,
+    ];
+
+    /// Roles that change how an instrument is set up, which takes time
+    /// between two songs.
+    pub const SETUP: [RoleKind; 3] = [RoleKind::Tuning, RoleKind::Capo, RoleKind::Instrument];
+
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            RoleKind::LeadVocal => "lead_vocal",
+            RoleKind::Backing => "backing",
+            RoleKind::Tuning => "tuning",
+            RoleKind::Capo => "capo",
+            RoleKind::Instrument => "instrument",
+        }
+    }
+
+    pub fn label(&self, locale: Locale) -> String {
+        locale.t(&format!("song-role-{}", self.as_str()))
+    }
+
+    /// How the member plays when the song doesn't say otherwise.
+    fn default_value(&self, locale: Locale) -> String {
+        match self {
+            RoleKind::Tuning => locale.t("song-role-default-tuning"),
+            RoleKind::Capo => locale.t("song-role-default-capo"),
+            _ => locale.t("song-role-default-instrument"),
+        }
+    }
+}
+
+impl FromStr for RoleKind {
+    type Err = String;
+
+    fn from_str(s: &str) -> std::result::Result<Self, Self::Err> {
+        RoleKind::ALL
+            .into_iter()
+            .find(|r| r.as_str() == s)
+            .ok_or_else(|| format!("Unknown role: {}", s))
+    }
+}
+
+#[cfg_attr(feature = "ssr", derive(utoipa::ToSchema))]
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq, Eq, Hash, Default)]
+pub struct SongRole {
+    pub id: i32,
+    pub song_id: i32,
+    pub member_id: i32,
+    pub member_name: String,
+    pub kind: RoleKind,
+    pub value: String,
+}
+
+impl SongRole {
+    /// Short description for chips, e.g. "Henk: Stemming Drop D".
+    pub fn describe(&self, locale: Locale) -> String {
+        match self.value.is_empty() {
+            true => format!("{}: {}", self.member_name, self.kind.label(locale)),
+            false => format!("{}: {} {}", self.member_name, self.kind.label(locale), self.value),
+        }
+    }
+
+    #[cfg(feature = "ssr")]
+    pub async fn get_for_songs(song_ids: &[i32]) -> Result<Vec<Self>, AppError> {
+        sqlx::query!(
+            "
+SELECT r.id, r.song_id, r.member_id, m.name as member_name, r.kind, r.value
+FROM song_roles r JOIN members m ON m.id = r.member_id
+WHERE r.song_id = ANY($1)
+ORDER BY r.song_id, m.name, r.id",
+            song_ids
+        )
+        .map(|row| SongRole {
+            id: row.id,
+            song_id: row.song_id,
+            member_id: row.member_id,
+            member_name: row.member_name,
+            kind: row.kind.parse().unwrap_or_default(),
+            value: row.value,
+        })
+        .fetch_all(crate::database::get_db())
+        .await
+        .map_err(AppError::from)
+    }
+
+    /// Fills in the roles of each song.
+    #[cfg(feature = "ssr")]
+    pub async fn attach(songs: &mut [Song]) -> Result<(), AppError> {
+        let ids: Vec<i32> = songs.iter().map(|s| s.id).collect();
+        let roles = Self::get_for_songs(&ids).await?;
+        for song in songs.iter_mut() {
+            song.roles = roles
+                .iter()
+                .filter(|r| r.song_id == song.id)
+                .cloned()
+                .collect();
+        }
+        Ok(())
+    }
+
+    #[cfg(feature = "ssr")]
+    pub async fn add(
+        band_id: i32,
+        song_id: i32,
+        member_id: i32,
+        kind: RoleKind,
+        value: String,
+    ) -> Result<(), AppError> {
+        let added = sqlx::query!(
+            "
+INSERT INTO song_roles (song_id, member_id, kind, value)
+SELECT $1, $2, $3, $4
+WHERE EXISTS (SELECT 1 FROM songs WHERE id = $1 AND band_id = $5)
+  AND EXISTS (SELECT 1 FROM members WHERE id = $2 AND band_id = $5)",
+            song_id,
+            member_id,
+            kind.as_str(),
+            value,
+            band_id
+        )
+        .execute(crate::database::get_db())
+        .await?;
+        match added.rows_affected() {
+            0 => Err(AppError::from(sqlx::Error::RowNotFound)),
+            _ => Ok(()),
+        }
+    }
+
+    #[cfg(feature = "ssr")]
+    pub async fn remove(band_id: i32, role_id: i32) -> Result<(), AppError> {
+        sqlx::query!(
+            "DELETE FROM song_roles r USING songs s WHERE r.id = $1 AND s.id = r.song_id AND s.band_id = $2",
+            role_id,
+            band_id
+        )
+        .execute(crate::database::get_db())
+        .await?;
+        Ok(())
+    }
+}
+
+/// The setup changes needed between two consecutive songs in a running order.
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq, Eq, Hash)]
+pub struct Changeover {
+    /// Id of the song that needs the changes.
+    pub song_id: i32,
+    pub changes: Vec<String>,
+}
+
+impl Changeover {
+    /// Finds the tuning, capo and instrument swaps between consecutive songs.
+    /// A break leaves time to change, so songs after a break are skipped.
+    pub fn find(running_order: &[GigSong], locale: Locale) -> Vec<Changeover> {
+        running_order
+            .windows(2)
+            .filter_map(|pair| match (&pair[0].song, &pair[1].song) {
+                (Some(previous), Some(next)) => {
+                    let changes = changes(previous, next, locale);
+                    (!changes.is_empty()).then_some(Changeover {
+                        song_id: next.id,
+                        changes,
+                    })
+                }
+                _ => None,
+            })
+            .collect()
+    }
+}
+
+fn setup_value(song: &Song, member_id: i32, kind: RoleKind, locale: Locale) -> String {
+    song.roles
+        .iter()
+        .find(|r| r.member_id == member_id && r.kind == kind)
+        .map(|r| r.value.clone())
+        .filter(|v| !v.is_empty())
+        .unwrap_or_else(|| kind.default_value(locale))
+}
+
+fn changes(previous: &Song, next: &Song, locale: Locale) -> Vec<String> {
+    let mut members: Vec<(i32, &str)> = Vec::default();
+    for role in previous.roles.iter().chain(next.roles.iter()) {
+        if RoleKind::SETUP.contains(&role.kind)
+            && !members.iter().any(|(id, _)| *id == role.member_id)
+        {
+            members.push((role.member_id, role.member_name.as_str()));
+        }
+    }
+
+    let mut changes = Vec::default();
+    for (member_id, name) in members {
+        for kind in RoleKind::SETUP {
+            let from = setup_value(previous, member_id, kind, locale);
+            let to = setup_value(next, member_id, kind, locale);
+            if !from.eq_ignore_ascii_case(&to) {
+                changes.push(format!("{}: {} {} → {}", name, kind.label(locale), from, to));
+            }
+        }
+    }
+    changes
+}
@@ -0,0 +1,67 @@
+use serde::{Deserialize, Serialize};
+
+#[cfg(feature = "ssr")]
+use crate::error::AppError;
+
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq, Eq, Hash)]
+pub struct Tag {
+    pub id: i32,
+    pub name: String,
+}
+
+impl Tag {
+    /// Normalises a comma separated list of tags as typed by a user: trimmed,
+    /// lowercased, without empties and duplicates.
+    pub fn parse_list(tags: &str) -> Vec<String> {
+        let mut parsed: Vec<String> = Vec::default();
+        for tag in tags.split(',').map(|t| t.trim().to_lowercase()) {
+            if !tag.is_empty() && !parsed.contains(&tag) {
+                parsed.push(tag);
+            }
+        }
+        parsed
+    }
+
+    #[cfg(feature = "ssr")]
+    pub async fn get_all(band_id: i32) -> Result<Vec<Self>, AppError> {
+        sqlx::query_as!(
+            Tag,
+            "SELECT id, name FROM tags WHERE band_id = $1 ORDER BY name ASC",
+            band_id
+        )
+        .fetch_all(crate::database::get_db())
+        .await
+        .map_err(AppError::from)
+    }
+
+    /// Replaces the tags of a song, which the caller checked belongs to the
+    /// band, creating tags that don't exist yet. Runs in the caller's
+    /// transaction so the tags are saved together with the song.
+    #[cfg(feature = "ssr")]
+    pub async fn set_for_song(
+        tx: &mut sqlx::PgConnection,
+        band_id: i32,
+        song_id: i32,
+        tags: &[String],
+    ) -> Result<(), AppError> {
+        sqlx::query!(
+            "INSERT INTO tags (name, band_id) SELECT name, $2 FROM UNNEST($1::varchar[]) AS t(name) ON CONFLICT (band_id, name) DO NOTHING",
+            tags,
+            band_id
+        )
+        .execute(&mut *tx)
+        .await?;
+        sqlx::query!("DELETE FROM song_tags WHERE song_id = $1", song_id)
+            .execute(&mut *tx)
+            .await?;
+        sqlx::query!(
+            "INSERT INTO song_tags (song_id, tag_id) SELECT $1, id FROM tags WHERE name = ANY($2) AND band_id = $3",
+            song_id,
+            tags,
+            band_id
+        )
+        .execute(&mut *tx)
+        .await?;
+        Ok(())
+    }
+}
@@ -0,0 +1,110 @@
+use serde::{Deserialize, Serialize};
+
+#[cfg(feature = "ssr")]
+use crate::error::AppError;
+
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq, Eq, Default)]
+pub struct Venue {
+    pub id: i32,
+    pub name: String,
+    pub address: String,
+    pub contact_name: String,
+    pub contact_phone: String,
+    pub contact_email: String,
+    pub stage_size: String,
+    pub technical_notes: String,
+}
+
+impl Venue {
+    #[cfg(feature = "ssr")]
+    pub async fn get_all(band_id: i32) -> Result<Vec<Self>, AppError> {
+        sqlx::query_as!(
+            Venue,
+            "
+SELECT id, name, address, contact_name, contact_phone, contact_email, stage_size, technical_notes
+FROM venues WHERE band_id = $1 ORDER BY name ASC",
+            band_id
+        )
+        .fetch_all(crate::database::get_db())
+        .await
+        .map_err(AppError::from)
+    }
+
+    #[cfg(feature = "ssr")]
+    pub async fn get_by_id(band_id: i32, id: i32) -> Result<Self, AppError> {
+        sqlx::query_as!(
+            Venue,
+            "
+SELECT id, name, address, contact_name, contact_phone, contact_email, stage_size, technical_notes
+FROM venues WHERE id = $1 AND band_id = $2",
+            id,
+            band_id
+        )
+        .fetch_one(crate::database::get_db())
+        .await
+        .map_err(AppError::from)
+    }
+
+    #[cfg(feature = "ssr")]
+    pub async fn create(band_id: i32, name: String) -> Result<i32, AppError> {
+        sqlx::query!(
+            "INSERT INTO venues (name, band_id) VALUES ($1, $2) RETURNING id",
+            name,
+            band_id
+        )
+        .map(|row| row.id)
+        .fetch_one(crate::database::get_db())
+        .await
+        .map_err(AppError::from)
+    }
+
+    /// Updates the venue and renames it on the gigs linked to it.
+    #[cfg(feature = "ssr")]
+    pub async fn update(band_id: i32, venue: Venue) -> Result<(), AppError> {
+        let mut tx = crate::database::get_db().begin().await?;
+        let updated = sqlx::query!(
+            "
+UPDATE venues SET
+  name = $2, address = $3, contact_name = $4, contact_phone = $5,
+  contact_email = $6, stage_size = $7, technical_notes = $8
+WHERE id = $1 AND band_id = $9",
+            venue.id,
+            venue.name,
+            venue.address,
+            venue.contact_name,
+            venue.contact_phone,
+            venue.contact_email,
+            venue.stage_size,
+            venue.technical_notes,
+            band_id
+        )
+        .execute(&mut *tx)
+        .await?
+        .rows_affected();
+        if updated == 0 {
+            return Err(AppError::from(sqlx::Error::RowNotFound));
+        }
+        sqlx::query!(
+            "UPDATE gigs SET venue = $1 WHERE venue_id = $2 AND band_id = $3",
+            venue.name,
+            venue.id,
+            band_id
+        )
+        .execute(&mut *tx)
+        .await?;
+        tx.commit().await
+            .map_err(AppError::from)
+    }
+
+    #[cfg(feature = "ssr")]
+    pub async fn remove(band_id: i32, id: i32) -> Result<(), AppError> {
+        sqlx::query!(
+            "DELETE FROM venues WHERE id = $1 AND band_id = $2",
+            id,
+            band_id
+        )
+        .execute(crate::database::get_db())
+        .await?;
+        Ok(())
+    }
+}
@@ -0,0 +1,194 @@
+use std::sync::Arc;
+
+use async_trait::async_trait;
+use axum::extract::Request;
+use axum::middleware::Next;
+use axum::response::Response;
+use chrono::NaiveDate;
+use leptos::use_context;
+
+use crate::components::edit_song::EditSongData;
+use crate::error::AppError;
+use crate::models::annotation::{Annotation, NewAnnotation};
+use crate::models::audit::{AuditEntry, NewAuditEntry};
+use crate::models::band::{request_user, requested_band, Band};
+use crate::models::gig::GigModel;
+use crate::models::setlist::Setlist;
+use crate::models::song::{Song, SongFilter};
+use crate::models::song_revision::{NewSongRevision, SongRevision};
+
+mod memory;
+mod postgres;
+
+pub use memory::{
+    MemoryAnnotations, MemoryAudit, MemoryDatabase, MemoryGigs, MemoryRevisions, MemorySetlists,
+    MemorySongs,
+};
+pub use postgres::{PgAnnotations, PgAudit, PgGigs, PgRevisions, PgSetlists, PgSongs};
+
+#[async_trait]
+pub trait SongRepository: Send + Sync {
+    /// A song with its tags and roles.
+    async fn get(&self, id: i32) -> Result<Song, AppError>;
+    /// All songs ordered by title.
+    async fn get_all(&self) -> Result<Vec<Song>, AppError>;
+    async fn search(&self, filter: &SongFilter) -> Result<Vec<Song>, AppError>;
+    /// Marks the song as played today.
+    async fn set_played(&self, id: i32) -> Result<(), AppError>;
+    async fn update(&self, data: EditSongData) -> Result<(), AppError>;
+}
+
+#[async_trait]
+pub trait GigRepository: Send + Sync {
+    /// A gig that hasn't been deleted.
+    async fn get(&self, id: i32) -> Result<GigModel, AppError>;
+    /// All gigs, oldest first.
+    async fn get_all(&self) -> Result<Vec<GigModel>, AppError>;
+    /// The gigs at a venue, newest first.
+    async fn get_by_venue(&self, venue_id: i32) -> Result<Vec<GigModel>, AppError>;
+    /// Inserts a gig and returns its id. The venue is linked by name.
+    async fn create(
+        &self,
+        venue: String,
+        date: NaiveDate,
+        time: Option<String>,
+        songs: &[i32],
+    ) -> Result<i32, AppError>;
+    /// Moves the gig to the deleted gigs, from where it can be restored.
+    async fn remove(&self, id: i32) -> Result<(), AppError>;
+    /// Deleted gigs, most recently deleted first.
+    async fn get_deleted(&self) -> Result<Vec<GigModel>, AppError>;
+    async fn restore(&self, id: i32) -> Result<(), AppError>;
+    /// Replaces the whole running order, breaks included.
+    async fn set_songs(&self, id: i32, songs: &[i32]) -> Result<(), AppError>;
+    /// Sets the venue name, linking the venue with that name if there is one.
+    async fn set_venue(&self, id: i32, venue: String) -> Result<(), AppError>;
+    /// Links the gig to a venue from the venue table, or unlinks it with
+    /// `None`. The venue name on the gig follows the linked venue.
+    async fn set_venue_id(&self, id: i32, venue_id: Option<i32>) -> Result<(), AppError>;
+    async fn set_time(&self, id: i32, time: String) -> Result<(), AppError>;
+    async fn set_date(&self, id: i32, date: NaiveDate) -> Result<(), AppError>;
+}
+
+#[async_trait]
+pub trait SetlistRepository: Send + Sync {
+    async fn get(&self, id: i32) -> Result<Setlist, AppError>;
+    async fn get_all(&self) -> Result<Vec<Setlist>, AppError>;
+    /// The setlist filled from the rehearsal page, titled "Oefenen".
+    async fn get_practice(&self) -> Result<Setlist, AppError>;
+    async fn set_songs(&self, id: i32, songs: &[i32]) -> Result<(), AppError>;
+    /// Appends the songs that aren't on the setlist yet, see
+    /// [`Setlist::new_songs`], holding the setlist while doing so that two
+    /// fills at the same time don't lose each other's songs. Returns the
+    /// setlist as it was before.
+    async fn add_songs(&self, id: i32, songs: &[i32]) -> Result<Setlist, AppError>;
+}
+
+#[async_trait]
+pub trait RevisionRepository: Send + Sync {
+    async fn get(&self, id: i32) -> Result<SongRevision, AppError>;
+    /// The revisions of a song, newest first.
+    async fn get_for_song(&self, song_id: i32) -> Result<Vec<SongRevision>, AppError>;
+    async fn record(&self, revision: NewSongRevision) -> Result<SongRevision, AppError>;
+}
+
+#[async_trait]
+pub trait AnnotationRepository: Send + Sync {
+    /// The notes on a song with their member's name, oldest first.
+    async fn get_for_song(&self, song_id: i32) -> Result<Vec<Annotation>, AppError>;
+    async fn create(&self, annotation: NewAnnotation) -> Result<i32, AppError>;
+    async fn remove(&self, id: i32) -> Result<(), AppError>;
+    /// Pins the note to another line, remembering that line's text.
+    async fn set_anchor(&self, id: i32, line: i32, anchor: String) -> Result<(), AppError>;
+}
+
+#[async_trait]
+pub trait AuditRepository: Send + Sync {
+    async fn record(&self, entry: NewAuditEntry) -> Result<(), AppError>;
+    /// The newest `limit` entries, newest first.
+    async fn get_recent(&self, limit: i64) -> Result<Vec<AuditEntry>, AppError>;
+    /// The newest destructive entry that hasn't been undone.
+    async fn last_undoable(&self) -> Result<Option<AuditEntry>, AppError>;
+    async fn mark_undone(&self, id: i32) -> Result<(), AppError>;
+}
+
+/// The repositories the models work on, all limited to the data of one band.
+/// [`scope_to_band`] adds the Postgres ones to each request; tests use the
+/// in-memory ones.
+#[derive(Clone)]
+pub struct Repositories {
+    /// Models querying the database themselves pass this on, so they only see
+    /// the same band.
+    pub band_id: i32,
+    pub songs: Arc<dyn SongRepository>,
+    pub gigs: Arc<dyn GigRepository>,
+    pub setlists: Arc<dyn SetlistRepository>,
+    pub audit: Arc<dyn AuditRepository>,
+    pub revisions: Arc<dyn RevisionRepository>,
+    pub annotations: Arc<dyn AnnotationRepository>,
+}
+
+impl Repositories {
+    pub fn postgres(band_id: i32) -> Self {
+        Self {
+            band_id,
+            songs: Arc::new(PgSongs { band_id }),
+            gigs: Arc::new(PgGigs { band_id }),
+            setlists: Arc::new(PgSetlists { band_id }),
+            audit: Arc::new(PgAudit { band_id }),
+            revisions: Arc::new(PgRevisions { band_id }),
+            annotations: Arc::new(PgAnnotations { band_id }),
+        }
+    }
+
+    /// The repositories of one band in a database kept in memory.
+    pub fn memory(db: &Arc<MemoryDatabase>, band_id: i32) -> Self {
+        Self {
+            band_id,
+            songs: Arc::new(MemorySongs { band_id, db: db.clone() }),
+            gigs: Arc::new(MemoryGigs { band_id, db: db.clone() }),
+            setlists: Arc::new(MemorySetlists { band_id, db: db.clone() }),
+            audit: Arc::new(MemoryAudit { band_id, db: db.clone() }),
+            revisions: Arc::new(MemoryRevisions { band_id, db: db.clone() }),
+            annotations: Arc::new(MemoryAnnotations { band_id, db: db.clone() }),
+        }
+    }
+
+    /// In-memory repositories holding a single band's data.
+    pub fn in_memory(songs: Vec<Song>, gigs: Vec<GigModel>, setlists: Vec<Setlist>) -> Self {
+        let db = Arc::new(MemoryDatabase::default());
+        db.add_band(1, songs, gigs, setlists);
+        Self::memory(&db, 1)
+    }
+}
+
+/// The repositories of the band the request is for, for use in server
+/// functions. Outside a request, as in tests, they come from the Leptos
+/// context instead.
+pub fn use_repositories() -> Result<Repositories, AppError> {
+    match use_context::<http::request::Parts>() {
+        Some(parts) => parts
+            .extensions
+            .get::<Repositories>()
+            .cloned()
+            .ok_or_else(|| AppError::NotFound("geen band waar je lid van bent".to_string())),
+        None => use_context::<Repositories>()
+            .ok_or_else(|| AppError::Internal("geen repositories in de context".to_string())),
+    }
+}
+
+/// Middleware picking the band of each request, see [`Band::resolve`], and
+/// adding its repositories to the request. Without a band the user may open
+/// the request goes on without repositories, so handlers needing them fail.
+pub async fn scope_to_band(mut request: Request, next: Next) -> Response {
+    let user = request_user(request.headers());
+    match Band::resolve(requested_band(request.headers()), user.as_deref()).await {
+        Ok(band) => {
+            request
+                .extensions_mut()
+                .insert(Repositories::postgres(band.id));
+        }
+        Err(e) => log::debug!("no band for {:?}: {}", user, e),
+    }
+    next.run(request).await
+}
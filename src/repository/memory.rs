@@ -0,0 +1,555 @@
+use std::sync::{Arc, Mutex};
+
+use async_trait::async_trait;
+use chrono::NaiveDate;
+
+use super::{
+    AnnotationRepository, AuditRepository, GigRepository, RevisionRepository, SetlistRepository,
+    SongRepository,
+};
+use crate::components::edit_song::EditSongData;
+use crate::error::AppError;
+use crate::models::annotation::{Annotation, NewAnnotation};
+use crate::models::audit::{AuditEntry, NewAuditEntry};
+use crate::models::gig::GigModel;
+use crate::models::key::Key;
+use crate::models::section::Section;
+use crate::models::setlist::Setlist;
+use crate::models::song::{Song, SongFilter, SongSort};
+use crate::models::song_revision::{NewSongRevision, SongRevision};
+use crate::models::tag::Tag;
+
+/// The same error Postgres gives for a missing row.
+fn not_found() -> AppError {
+    AppError::from(sqlx::Error::RowNotFound)
+}
+
+fn today() -> NaiveDate {
+    chrono::Local::now().date_naive()
+}
+
+/// Rows of every band, each with the id of the band it belongs to.
+type Table<T> = Mutex<Vec<(i32, T)>>;
+
+fn band_rows<T: Clone>(table: &Table<T>, band_id: i32) -> Vec<T> {
+    let rows = table.lock().unwrap();
+    rows.iter()
+        .filter(|(band, _)| *band == band_id)
+        .map(|(_, row)| row.clone())
+        .collect()
+}
+
+/// The data of all bands, shared by the in-memory repositories of each band
+/// like the database is shared by the Postgres ones. Ids are unique across
+/// bands. Revisions and annotations belong to the band of their song.
+#[derive(Debug, Default)]
+pub struct MemoryDatabase {
+    songs: Table<Song>,
+    gigs: Table<GigModel>,
+    /// Most recently deleted last.
+    deleted_gigs: Table<GigModel>,
+    setlists: Table<Setlist>,
+    audit: Table<AuditEntry>,
+    revisions: Mutex<Vec<SongRevision>>,
+    annotations: Mutex<Vec<Annotation>>,
+}
+
+impl MemoryDatabase {
+    /// Adds the rows of a band.
+    pub fn add_band(&self, band_id: i32, songs: Vec<Song>, gigs: Vec<GigModel>, setlists: Vec<Setlist>) {
+        self.songs.lock().unwrap().extend(songs.into_iter().map(|s| (band_id, s)));
+        self.gigs.lock().unwrap().extend(gigs.into_iter().map(|g| (band_id, g)));
+        self.setlists.lock().unwrap().extend(setlists.into_iter().map(|s| (band_id, s)));
+    }
+
+    fn has_song(&self, band_id: i32, song_id: i32) -> bool {
+        let songs = self.songs.lock().unwrap();
+        songs.iter().any(|(band, s)| *band == band_id && s.id == song_id)
+    }
+
+    /// Leaves out the ids that aren't songs of the band, like the `EXISTS`
+    /// checks in the Postgres queries. Breaks in gigs have negative ids, so
+    /// those are kept when `breaks` is set.
+    fn known_songs(&self, band_id: i32, songs: &[i32], breaks: bool) -> Vec<i32> {
+        songs
+            .iter()
+            .copied()
+            .filter(|id| (breaks && *id < 0) || self.has_song(band_id, *id))
+            .collect()
+    }
+}
+
+/// Songs kept in memory. Search matches the query as a case insensitive
+/// substring of title, artist or lyrics instead of using full-text search.
+#[derive(Debug)]
+pub struct MemorySongs {
+    pub band_id: i32,
+    pub db: Arc<MemoryDatabase>,
+}
+
+impl MemorySongs {
+    fn matches(song: &Song, filter: &SongFilter) -> bool {
+        let query = filter
+            .query
+            .as_ref()
+            .map(|q| q.trim().to_lowercase())
+            .filter(|q| !q.is_empty());
+        let not_played_since = filter
+            .not_played_days
+            .map(|days| today() - chrono::Duration::days(days.into()));
+
+        query.is_none_or(|q| {
+            [&song.title, &song.artist, &song.lyrics]
+                .iter()
+                .any(|field| field.to_lowercase().contains(&q))
+        }) && filter.readiness.is_none_or(|r| song.readiness == r)
+            && filter
+                .min_bpm
+                .is_none_or(|min| song.bpm.is_some_and(|bpm| bpm >= min))
+            && filter
+                .max_bpm
+                .is_none_or(|max| song.bpm.is_some_and(|bpm| bpm <= max))
+            && not_played_since.is_none_or(|since| song.last_played_at.is_none_or(|d| d <= since))
+            && filter.tag.as_ref().is_none_or(|t| song.tags.contains(t))
+    }
+
+    fn change(&self, id: i32, change: impl FnOnce(&mut Song)) -> Result<(), AppError> {
+        let mut songs = self.db.songs.lock().unwrap();
+        let (_, song) = songs
+            .iter_mut()
+            .find(|(band, s)| *band == self.band_id && s.id == id)
+            .ok_or_else(not_found)?;
+        change(song);
+        Ok(())
+    }
+}
+
+#[async_trait]
+impl SongRepository for MemorySongs {
+    async fn get(&self, id: i32) -> Result<Song, AppError> {
+        band_rows(&self.db.songs, self.band_id)
+            .into_iter()
+            .find(|s| s.id == id)
+            .ok_or_else(not_found)
+    }
+
+    async fn get_all(&self) -> Result<Vec<Song>, AppError> {
+        let mut songs = band_rows(&self.db.songs, self.band_id);
+        songs.sort_by(|a, b| a.title.cmp(&b.title));
+        Ok(songs)
+    }
+
+    async fn search(&self, filter: &SongFilter) -> Result<Vec<Song>, AppError> {
+        let mut songs: Vec<Song> = self
+            .get_all()
+            .await?
+            .into_iter()
+            .filter(|s| Self::matches(s, filter))
+            .collect();
+        // Stable sorts on top of the title order, like the Postgres ORDER BY.
+        match filter.sort {
+            SongSort::Title => {}
+            SongSort::Artist => songs.sort_by(|a, b| a.artist.cmp(&b.artist)),
+            SongSort::LastPlayed => songs.sort_by_key(|s| s.last_played_at),
+            SongSort::Bpm => songs.sort_by_key(|s| (s.bpm.is_none(), s.bpm)),
+        }
+        Ok(songs)
+    }
+
+    async fn set_played(&self, id: i32) -> Result<(), AppError> {
+        // Like the Postgres UPDATE, a missing song is no error.
+        self.change(id, |song| song.last_played_at = Some(today()))
+            .or(Ok(()))
+    }
+
+    async fn update(&self, data: EditSongData) -> Result<(), AppError> {
+        let sections = Section::parse_list(&data.arrangement).map_err(AppError::Validation)?;
+        let key = Key::parse_optional(&data.key).map_err(AppError::Validation)?;
+        let mut tags = Tag::parse_list(&data.tags);
+        tags.sort();
+        self.change(data.id, |song| {
+            song.artist = data.artist;
+            song.title = data.title;
+            song.bpm = Some(data.bpm).filter(|b| *b != 0);
+            song.lyrics = data.lyrics;
+            song.readiness = data.readiness;
+            song.duration = Some(data.duration).filter(|d| *d != 0);
+            song.tags = tags;
+            song.sections = sections;
+            song.key = key;
+        })
+    }
+}
+
+/// Gigs kept in memory. There is no venue table, so venues are never linked
+/// by name.
+#[derive(Debug)]
+pub struct MemoryGigs {
+    pub band_id: i32,
+    pub db: Arc<MemoryDatabase>,
+}
+
+impl MemoryGigs {
+    fn update(&self, id: i32, change: impl FnOnce(&mut GigModel)) -> Result<(), AppError> {
+        let mut gigs = self.db.gigs.lock().unwrap();
+        if let Some((_, gig)) = gigs
+            .iter_mut()
+            .find(|(band, g)| *band == self.band_id && g.id == id)
+        {
+            change(gig);
+        }
+        Ok(())
+    }
+}
+
+#[async_trait]
+impl GigRepository for MemoryGigs {
+    async fn get(&self, id: i32) -> Result<GigModel, AppError> {
+        band_rows(&self.db.gigs, self.band_id)
+            .into_iter()
+            .find(|g| g.id == id)
+            .ok_or_else(not_found)
+    }
+
+    async fn get_all(&self) -> Result<Vec<GigModel>, AppError> {
+        let mut gigs = band_rows(&self.db.gigs, self.band_id);
+        gigs.sort_by_key(|g| g.date);
+        Ok(gigs)
+    }
+
+    async fn get_by_venue(&self, venue_id: i32) -> Result<Vec<GigModel>, AppError> {
+        let mut gigs: Vec<GigModel> = self
+            .get_all()
+            .await?
+            .into_iter()
+            .filter(|g| g.venue_id == Some(venue_id))
+            .collect();
+        gigs.reverse();
+        Ok(gigs)
+    }
+
+    async fn create(
+        &self,
+        venue: String,
+        date: NaiveDate,
+        time: Option<String>,
+        songs: &[i32],
+    ) -> Result<i32, AppError> {
+        let songs = self.db.known_songs(self.band_id, songs, true);
+        let mut gigs = self.db.gigs.lock().unwrap();
+        let id = gigs
+            .iter()
+            .chain(self.db.deleted_gigs.lock().unwrap().iter())
+            .map(|(_, g)| g.id)
+            .max()
+            .unwrap_or_default()
+            + 1;
+        gigs.push((
+            self.band_id,
+            GigModel {
+                id,
+                venue,
+                date,
+                time,
+                songs,
+                venue_id: None,
+            },
+        ));
+        Ok(id)
+    }
+
+    async fn remove(&self, id: i32) -> Result<(), AppError> {
+        let mut gigs = self.db.gigs.lock().unwrap();
+        if let Some(index) = gigs
+            .iter()
+            .position(|(band, g)| *band == self.band_id && g.id == id)
+        {
+            self.db.deleted_gigs.lock().unwrap().push(gigs.remove(index));
+        }
+        Ok(())
+    }
+
+    async fn get_deleted(&self) -> Result<Vec<GigModel>, AppError> {
+        let mut deleted = band_rows(&self.db.deleted_gigs, self.band_id);
+        deleted.reverse();
+        Ok(deleted)
+    }
+
+    async fn restore(&self, id: i32) -> Result<(), AppError> {
+        // Same lock order as `remove`.
+        let mut gigs = self.db.gigs.lock().unwrap();
+        let mut deleted = self.db.deleted_gigs.lock().unwrap();
+        if let Some(index) = deleted
+            .iter()
+            .position(|(band, g)| *band == self.band_id && g.id == id)
+        {
+            gigs.push(deleted.remove(index));
+        }
+        Ok(())
+    }
+
+    async fn set_songs(&self, id: i32, songs: &[i32]) -> Result<(), AppError> {
+        let songs = self.db.known_songs(self.band_id, songs, true);
+        self.update(id, |gig| gig.songs = songs)
+    }
+
+    async fn set_venue(&self, id: i32, venue: String) -> Result<(), AppError> {
+        self.update(id, |gig| {
+            gig.venue = venue;
+            gig.venue_id = None;
+        })
+    }
+
+    async fn set_venue_id(&self, id: i32, venue_id: Option<i32>) -> Result<(), AppError> {
+        self.update(id, |gig| gig.venue_id = venue_id)
+    }
+
+    async fn set_time(&self, id: i32, time: String) -> Result<(), AppError> {
+        self.update(id, |gig| gig.time = Some(time))
+    }
+
+    async fn set_date(&self, id: i32, date: NaiveDate) -> Result<(), AppError> {
+        self.update(id, |gig| gig.date = date)
+    }
+}
+
+#[derive(Debug)]
+pub struct MemorySetlists {
+    pub band_id: i32,
+    pub db: Arc<MemoryDatabase>,
+}
+
+impl MemorySetlists {
+    fn find(&self, matches: impl Fn(&Setlist) -> bool) -> Result<Setlist, AppError> {
+        band_rows(&self.db.setlists, self.band_id)
+            .into_iter()
+            .find(|s| matches(s))
+            .ok_or_else(not_found)
+    }
+}
+
+#[async_trait]
+impl SetlistRepository for MemorySetlists {
+    async fn get(&self, id: i32) -> Result<Setlist, AppError> {
+        self.find(|s| s.id == id)
+    }
+
+    async fn get_all(&self) -> Result<Vec<Setlist>, AppError> {
+        let mut setlists = band_rows(&self.db.setlists, self.band_id);
+        setlists.sort_by_key(|s| s.id);
+        Ok(setlists)
+    }
+
+    async fn get_practice(&self) -> Result<Setlist, AppError> {
+        self.find(|s| s.title == "Oefenen")
+    }
+
+    async fn set_songs(&self, id: i32, songs: &[i32]) -> Result<(), AppError> {
+        let songs = self.db.known_songs(self.band_id, songs, false);
+        let mut setlists = self.db.setlists.lock().unwrap();
+        if let Some((_, setlist)) = setlists
+            .iter_mut()
+            .find(|(band, s)| *band == self.band_id && s.id == id)
+        {
+            setlist.songs = songs;
+        }
+        Ok(())
+    }
+
+    async fn add_songs(&self, id: i32, songs: &[i32]) -> Result<Setlist, AppError> {
+        let songs = self.db.known_songs(self.band_id, songs, false);
+        let mut setlists = self.db.setlists.lock().unwrap();
+        let (_, setlist) = setlists
+            .iter_mut()
+            .find(|(band, s)| *band == self.band_id && s.id == id)
+            .ok_or_else(not_found)?;
+        setlist.check_unlocked()?;
+        let before = setlist.clone();
+        setlist.songs.extend(before.new_songs(&songs));
+        Ok(before)
+    }
+}
+
+#[derive(Debug)]
+pub struct MemoryAudit {
+    pub band_id: i32,
+    pub db: Arc<MemoryDatabase>,
+}
+
+#[async_trait]
+impl AuditRepository for MemoryAudit {
+    async fn record(&self, entry: NewAuditEntry) -> Result<(), AppError> {
+        let mut entries = self.db.audit.lock().unwrap();
+        let id = entries.len() as i32 + 1;
+        entries.push((
+            self.band_id,
+            AuditEntry {
+                id,
+                created_at: chrono::Local::now().naive_local(),
+                actor: entry.actor,
+                entity: entry.entity,
+                entity_id: entry.entity_id,
+                action: entry.action,
+                before: entry.before,
+                after: entry.after,
+                undone_at: None,
+            },
+        ));
+        Ok(())
+    }
+
+    async fn get_recent(&self, limit: i64) -> Result<Vec<AuditEntry>, AppError> {
+        Ok(band_rows(&self.db.audit, self.band_id)
+            .into_iter()
+            .rev()
+            .take(limit.try_into().unwrap_or_default())
+            .collect())
+    }
+
+    async fn last_undoable(&self) -> Result<Option<AuditEntry>, AppError> {
+        Ok(band_rows(&self.db.audit, self.band_id)
+            .into_iter()
+            .rev()
+            .find(|e| e.can_undo()))
+    }
+
+    async fn mark_undone(&self, id: i32) -> Result<(), AppError> {
+        let mut entries = self.db.audit.lock().unwrap();
+        if let Some((_, entry)) = entries
+            .iter_mut()
+            .find(|(band, e)| *band == self.band_id && e.id == id)
+        {
+            entry.undone_at = Some(chrono::Local::now().naive_local());
+        }
+        Ok(())
+    }
+}
+
+#[derive(Debug)]
+pub struct MemoryRevisions {
+    pub band_id: i32,
+    pub db: Arc<MemoryDatabase>,
+}
+
+#[async_trait]
+impl RevisionRepository for MemoryRevisions {
+    async fn get(&self, id: i32) -> Result<SongRevision, AppError> {
+        let revision = {
+            let revisions = self.db.revisions.lock().unwrap();
+            revisions.iter().find(|r| r.id == id).cloned()
+        };
+        revision
+            .filter(|r| self.db.has_song(self.band_id, r.song_id))
+            .ok_or_else(not_found)
+    }
+
+    async fn get_for_song(&self, song_id: i32) -> Result<Vec<SongRevision>, AppError> {
+        if !self.db.has_song(self.band_id, song_id) {
+            return Ok(vec![]);
+        }
+        let revisions = self.db.revisions.lock().unwrap();
+        Ok(revisions
+            .iter()
+            .rev()
+            .filter(|r| r.song_id == song_id)
+            .cloned()
+            .collect())
+    }
+
+    async fn record(&self, revision: NewSongRevision) -> Result<SongRevision, AppError> {
+        if !self.db.has_song(self.band_id, revision.song_id) {
+            return Err(not_found());
+        }
+        let mut revisions = self.db.revisions.lock().unwrap();
+        let revision = SongRevision {
+            id: revisions.len() as i32 + 1,
+            song_id: revision.song_id,
+            created_at: chrono::Local::now().naive_local(),
+            author: revision.author,
+            artist: revision.artist,
+            title: revision.title,
+            bpm: revision.bpm,
+            lyrics: revision.lyrics,
+            readiness: revision.readiness,
+            duration: revision.duration,
+            tags: revision.tags,
+            arrangement: revision.arrangement,
+            key: revision.key,
+        };
+        revisions.push(revision.clone());
+        Ok(revision)
+    }
+}
+
+/// Annotations kept in memory. There is no member table, so the member names
+/// are left empty.
+#[derive(Debug)]
+pub struct MemoryAnnotations {
+    pub band_id: i32,
+    pub db: Arc<MemoryDatabase>,
+}
+
+impl MemoryAnnotations {
+    fn update(&self, id: i32, change: impl FnOnce(&mut Vec<Annotation>, usize)) {
+        let song_ids: Vec<i32> = band_rows(&self.db.songs, self.band_id)
+            .iter()
+            .map(|s| s.id)
+            .collect();
+        let mut annotations = self.db.annotations.lock().unwrap();
+        if let Some(index) = annotations
+            .iter()
+            .position(|a| a.id == id && song_ids.contains(&a.song_id))
+        {
+            change(&mut annotations, index);
+        }
+    }
+}
+
+#[async_trait]
+impl AnnotationRepository for MemoryAnnotations {
+    async fn get_for_song(&self, song_id: i32) -> Result<Vec<Annotation>, AppError> {
+        if !self.db.has_song(self.band_id, song_id) {
+            return Ok(vec![]);
+        }
+        let annotations = self.db.annotations.lock().unwrap();
+        Ok(annotations
+            .iter()
+            .filter(|a| a.song_id == song_id)
+            .cloned()
+            .collect())
+    }
+
+    async fn create(&self, annotation: NewAnnotation) -> Result<i32, AppError> {
+        if !self.db.has_song(self.band_id, annotation.song_id) {
+            return Err(not_found());
+        }
+        let mut annotations = self.db.annotations.lock().unwrap();
+        let id = annotations.iter().map(|a| a.id).max().unwrap_or_default() + 1;
+        annotations.push(Annotation {
+            id,
+            song_id: annotation.song_id,
+            member_id: annotation.member_id,
+            member: String::default(),
+            line: annotation.line,
+            anchor: annotation.anchor,
+            body: annotation.body,
+            created_at: chrono::Local::now().naive_local(),
+        });
+        Ok(id)
+    }
+
+    async fn remove(&self, id: i32) -> Result<(), AppError> {
+        self.update(id, |annotations, index| {
+            annotations.remove(index);
+        });
+        Ok(())
+    }
+
+    async fn set_anchor(&self, id: i32, line: i32, anchor: String) -> Result<(), AppError> {
+        self.update(id, |annotations, index| {
+            annotations[index].line = line;
+            annotations[index].anchor = anchor;
+        });
+        Ok(())
+    }
+}
@@ -0,0 +1,755 @@
+use async_trait::async_trait;
+use chrono::NaiveDate;
+
+use super::{
+    AnnotationRepository, AuditRepository, GigRepository, RevisionRepository, SetlistRepository,
+    SongRepository,
+};
+use crate::components::edit_song::EditSongData;
+use crate::error::AppError;
+use crate::models::annotation::{Annotation, NewAnnotation};
+use crate::models::audit::{AuditEntry, NewAuditEntry};
+use crate::models::gig::GigModel;
+use crate::models::key::Key;
+use crate::models::section::Section;
+use crate::models::setlist::Setlist;
+use crate::models::song::{Song, SongFilter};
+use crate::models::song_revision::{NewSongRevision, SongRevision};
+use crate::models::song_role::SongRole;
+
+pub struct PgSongs {
+    pub band_id: i32,
+}
+
+#[async_trait]
+impl SongRepository for PgSongs {
+    async fn get(&self, song_id: i32) -> Result<Song, AppError> {
+        let mut song = sqlx::query!(
+            r#"
+        SELECT 
+          *,
+          ARRAY(
+            SELECT t.name FROM song_tags st JOIN tags t ON t.id = st.tag_id
+            WHERE st.song_id = s.id ORDER BY t.name
+          ) as "tags!"
+        FROM songs as s 
+        WHERE s.id = $1 AND s.band_id = $2"#,
+            song_id,
+            self.band_id
+        )
+        .map(|row| Song {
+            id: row.id,
+            artist: row.artist,
+            title: row.title,
+            last_played_at: row.last_played_at,
+            release_mid: row.release_mid,
+            artist_mid: row.artist_mid,
+            gs_url: row.gs_url,
+            bpm: row.bpm,
+            lyrics: row.lyrics,
+            readiness: row.readiness.parse().unwrap_or_default(),
+            tags: row.tags,
+            duration: row.duration,
+            roles: Vec::default(),
+            sections: Vec::default(),
+            key: row.musical_key.and_then(|k| k.parse().ok()),
+        })
+        .fetch_one(crate::database::get_db())
+        .await?;
+        SongRole::attach(std::slice::from_mut(&mut song)).await?;
+        Section::attach(std::slice::from_mut(&mut song)).await?;
+        Ok(song)
+    }
+
+    async fn get_all(&self) -> Result<Vec<Song>, AppError> {
+        let mut songs = sqlx::query!(
+            r#"SELECT 
+              *,
+              ARRAY(
+                SELECT t.name FROM song_tags st JOIN tags t ON t.id = st.tag_id
+                WHERE st.song_id = s.id ORDER BY t.name
+              ) as "tags!"
+            FROM songs as s 
+            WHERE s.band_id = $1
+            ORDER BY 
+              s.title ASC"#,
+            self.band_id
+        )
+        .map(|row| Song {
+            id: row.id,
+            artist: row.artist,
+            title: row.title,
+            gs_url: row.gs_url,
+            last_played_at: row.last_played_at,
+            release_mid: row.release_mid,
+            artist_mid: row.artist_mid,
+            bpm: row.bpm,
+            lyrics: row.lyrics,
+            readiness: row.readiness.parse().unwrap_or_default(),
+            tags: row.tags,
+            duration: row.duration,
+            roles: Vec::default(),
+            sections: Vec::default(),
+            key: row.musical_key.and_then(|k| k.parse().ok()),
+        })
+        .fetch_all(crate::database::get_db())
+        .await?;
+        SongRole::attach(&mut songs).await?;
+        Section::attach(&mut songs).await?;
+        Ok(songs)
+    }
+
+    async fn search(&self, filter: &SongFilter) -> Result<Vec<Song>, AppError> {
+        let query = filter
+            .query
+            .as_ref()
+            .map(|q| q.trim())
+            .filter(|q| !q.is_empty());
+
+        let mut songs = sqlx::query!(
+            r#"SELECT
+              *,
+              ARRAY(
+                SELECT t.name FROM song_tags st JOIN tags t ON t.id = st.tag_id
+                WHERE st.song_id = s.id ORDER BY t.name
+              ) as "tags!"
+            FROM songs as s
+            WHERE s.band_id = $8
+              AND ($1::text IS NULL
+                OR to_tsvector('simple', s.title || ' ' || s.artist || ' ' || s.lyrics)
+                  @@ websearch_to_tsquery('simple', $1)
+                OR s.title ILIKE '%' || $1 || '%'
+                OR s.artist ILIKE '%' || $1 || '%')
+              AND ($2::varchar IS NULL OR s.readiness = $2)
+              AND ($3::int IS NULL OR s.bpm >= $3)
+              AND ($4::int IS NULL OR s.bpm <= $4)
+              AND ($5::int IS NULL
+                OR s.last_played_at IS NULL
+                OR s.last_played_at <= CURRENT_DATE - $5::int)
+              AND ($7::varchar IS NULL OR EXISTS (
+                SELECT 1 FROM song_tags st JOIN tags t ON t.id = st.tag_id
+                WHERE st.song_id = s.id AND t.name = $7))
+            ORDER BY
+              CASE WHEN $6 = 'artist' THEN s.artist END ASC,
+              CASE WHEN $6 = 'last_played' THEN s.last_played_at END ASC NULLS FIRST,
+              CASE WHEN $6 = 'bpm' THEN s.bpm END ASC,
+              s.title ASC"#,
+            query,
+            filter.readiness.map(|r| r.as_str()),
+            filter.min_bpm,
+            filter.max_bpm,
+            filter.not_played_days,
+            filter.sort.as_str(),
+            filter.tag,
+            self.band_id,
+        )
+        .map(|row| Song {
+            id: row.id,
+            artist: row.artist,
+            title: row.title,
+            gs_url: row.gs_url,
+            last_played_at: row.last_played_at,
+            release_mid: row.release_mid,
+            artist_mid: row.artist_mid,
+            bpm: row.bpm,
+            lyrics: row.lyrics,
+            readiness: row.readiness.parse().unwrap_or_default(),
+            tags: row.tags,
+            duration: row.duration,
+            roles: Vec::default(),
+            sections: Vec::default(),
+            key: row.musical_key.and_then(|k| k.parse().ok()),
+        })
+        .fetch_all(crate::database::get_db())
+        .await?;
+        SongRole::attach(&mut songs).await?;
+        Section::attach(&mut songs).await?;
+        Ok(songs)
+    }
+
+    async fn set_played(&self, song_id: i32) -> Result<(), AppError> {
+        sqlx::query!(
+            "UPDATE songs SET last_played_at = CURRENT_DATE WHERE id = $1 AND band_id = $2",
+            song_id,
+            self.band_id
+        )
+        .execute(crate::database::get_db())
+        .await
+        .map(|_| ())
+        .map_err(AppError::from)
+    }
+
+    async fn update(&self, data: EditSongData) -> Result<(), AppError> {
+        use crate::models::tag::Tag;
+
+        let sections = Section::parse_list(&data.arrangement).map_err(AppError::Validation)?;
+        let key = Key::parse_optional(&data.key).map_err(AppError::Validation)?;
+        let mut tx = crate::database::get_db().begin().await?;
+        let updated = sqlx::query!(
+            "UPDATE songs SET artist=$2, title=$3, bpm = NULLIF($4, 0), lyrics = $5, readiness = $6, duration = NULLIF($7, 0), musical_key = $8 WHERE id = $1 AND band_id = $9",
+            data.id,
+            data.artist,
+            data.title,
+            data.bpm,
+            data.lyrics,
+            data.readiness.as_str(),
+            data.duration,
+            key.map(|k| k.to_string()),
+            self.band_id
+        )
+        .execute(&mut *tx)
+        .await?;
+        if updated.rows_affected() == 0 {
+            return Err(AppError::from(sqlx::Error::RowNotFound));
+        }
+        Tag::set_for_song(&mut tx, self.band_id, data.id, &Tag::parse_list(&data.tags)).await?;
+        Section::set_for_song(&mut tx, data.id, &sections).await?;
+
+        tx.commit().await.map_err(AppError::from)
+    }
+}
+
+pub struct PgGigs {
+    pub band_id: i32,
+}
+
+#[async_trait]
+impl GigRepository for PgGigs {
+    async fn get(&self, gig_id: i32) -> Result<GigModel, AppError> {
+        sqlx::query_as!(
+            GigModel,
+            "SELECT id, venue, date, time, songs, venue_id FROM gigs WHERE id = $1 AND band_id = $2 AND deleted_at IS NULL",
+            gig_id,
+            self.band_id
+        )
+            .fetch_one(crate::database::get_db())
+            .await
+            .map_err(AppError::from)
+    }
+
+    async fn get_all(&self) -> Result<Vec<GigModel>, AppError> {
+        sqlx::query_as!(
+            GigModel,
+            "SELECT id, venue, date, time, songs, venue_id FROM gigs WHERE band_id = $1 AND deleted_at IS NULL ORDER BY date ASC",
+            self.band_id
+        )
+            .fetch_all(crate::database::get_db())
+            .await
+            .map_err(AppError::from)
+    }
+
+    async fn get_by_venue(&self, venue_id: i32) -> Result<Vec<GigModel>, AppError> {
+        sqlx::query_as!(
+            GigModel,
+            "SELECT id, venue, date, time, songs, venue_id FROM gigs WHERE venue_id = $1 AND band_id = $2 AND deleted_at IS NULL ORDER BY date DESC",
+            venue_id,
+            self.band_id
+        )
+        .fetch_all(crate::database::get_db())
+        .await
+        .map_err(AppError::from)
+    }
+
+    async fn create(
+        &self,
+        venue: String,
+        date: NaiveDate,
+        time: Option<String>,
+        songs: &[i32],
+    ) -> Result<i32, AppError> {
+        sqlx::query!(
+            "
+INSERT INTO gigs (venue, venue_id, date, time, songs, band_id)
+VALUES (
+  $1::varchar,
+  (SELECT id FROM venues WHERE name = $1::varchar AND band_id = $5),
+  $2,
+  $3,
+  ARRAY(
+    SELECT id FROM unnest($4::int[]) WITH ORDINALITY AS t(id, n)
+    WHERE id < 0 OR EXISTS (SELECT 1 FROM songs s WHERE s.id = t.id AND s.band_id = $5)
+    ORDER BY n),
+  $5)
+RETURNING id",
+            venue,
+            date,
+            time,
+            songs,
+            self.band_id
+        )
+        .map(|row| row.id)
+        .fetch_one(crate::database::get_db())
+        .await
+        .map_err(AppError::from)
+    }
+
+    async fn remove(&self, gig_id: i32) -> Result<(), AppError> {
+        sqlx::query!(
+            "UPDATE gigs SET deleted_at = now() WHERE id = $1 AND band_id = $2 AND deleted_at IS NULL",
+            gig_id,
+            self.band_id
+        )
+            .execute(crate::database::get_db())
+            .await?;
+        Ok(())
+    }
+
+    async fn get_deleted(&self) -> Result<Vec<GigModel>, AppError> {
+        sqlx::query_as!(
+            GigModel,
+            "SELECT id, venue, date, time, songs, venue_id FROM gigs WHERE band_id = $1 AND deleted_at IS NOT NULL ORDER BY deleted_at DESC",
+            self.band_id
+        )
+        .fetch_all(crate::database::get_db())
+        .await
+        .map_err(AppError::from)
+    }
+
+    async fn restore(&self, gig_id: i32) -> Result<(), AppError> {
+        sqlx::query!(
+            "UPDATE gigs SET deleted_at = NULL WHERE id = $1 AND band_id = $2",
+            gig_id,
+            self.band_id
+        )
+        .execute(crate::database::get_db())
+        .await?;
+        Ok(())
+    }
+
+    async fn set_songs(&self, gig_id: i32, songs: &[i32]) -> Result<(), AppError> {
+        // Songs of other bands are left out.
+        sqlx::query!(
+            "
+UPDATE gigs SET songs = ARRAY(
+  SELECT id FROM unnest($1::int[]) WITH ORDINALITY AS t(id, n)
+  WHERE id < 0 OR EXISTS (SELECT 1 FROM songs s WHERE s.id = t.id AND s.band_id = $3)
+  ORDER BY n)
+WHERE id = $2 AND band_id = $3",
+            songs,
+            gig_id,
+            self.band_id
+        )
+        .execute(crate::database::get_db())
+        .await?;
+        Ok(())
+    }
+
+    async fn set_venue(&self, gig_id: i32, venue: String) -> Result<(), AppError> {
+        sqlx::query!(
+            "UPDATE gigs SET venue = $1::varchar, venue_id = (SELECT id FROM venues WHERE name = $1::varchar AND band_id = $3) WHERE id = $2 AND band_id = $3",
+            venue,
+            gig_id as i32,
+            self.band_id
+        )
+        .execute(crate::database::get_db())
+        .await?;
+        Ok(())
+    }
+
+    async fn set_venue_id(&self, gig_id: i32, venue_id: Option<i32>) -> Result<(), AppError> {
+        sqlx::query!(
+            "
+UPDATE gigs SET
+  venue_id = (SELECT id FROM venues WHERE id = $1 AND band_id = $3),
+  venue = COALESCE((SELECT name FROM venues WHERE id = $1 AND band_id = $3), venue)
+WHERE id = $2 AND band_id = $3",
+            venue_id,
+            gig_id,
+            self.band_id
+        )
+        .execute(crate::database::get_db())
+        .await?;
+        Ok(())
+    }
+
+    async fn set_time(&self, gig_id: i32, time: String) -> Result<(), AppError> {
+        sqlx::query!(
+            "UPDATE gigs SET time = $1 WHERE id = $2 AND band_id = $3",
+            time,
+            gig_id as i32,
+            self.band_id
+        )
+        .execute(crate::database::get_db())
+        .await?;
+        Ok(())
+    }
+
+    async fn set_date(&self, gig_id: i32, date: NaiveDate) -> Result<(), AppError> {
+        sqlx::query!(
+            "UPDATE gigs SET date = $1 WHERE id = $2 AND band_id = $3",
+            date,
+            gig_id,
+            self.band_id
+        )
+        .execute(crate::database::get_db())
+        .await?;
+        Ok(())
+    }
+}
+
+pub struct PgSetlists {
+    pub band_id: i32,
+}
+
+#[async_trait]
+impl SetlistRepository for PgSetlists {
+    async fn get(&self, id: i32) -> Result<Setlist, AppError> {
+        sqlx::query_as!(
+            Setlist,
+            "SELECT id, title, is_locked, songs FROM setlists WHERE id = $1 AND band_id = $2",
+            id,
+            self.band_id
+        )
+        .fetch_one(crate::database::get_db())
+            .await
+            .map_err(AppError::from)
+    }
+
+    async fn get_all(&self) -> Result<Vec<Setlist>, AppError> {
+        sqlx::query_as!(
+            Setlist,
+            "SELECT id, title, is_locked, songs FROM setlists WHERE band_id = $1 ORDER BY id ASC",
+            self.band_id
+        )
+        .fetch_all(crate::database::get_db())
+            .await
+            .map_err(AppError::from)
+    }
+
+    async fn get_practice(&self) -> Result<Setlist, AppError> {
+        sqlx::query_as!(
+            Setlist,
+            "SELECT id, title, is_locked, songs FROM setlists WHERE title = 'Oefenen' AND band_id = $1",
+            self.band_id
+        )
+        .fetch_one(crate::database::get_db())
+            .await
+            .map_err(AppError::from)
+    }
+
+    async fn set_songs(&self, id: i32, songs: &[i32]) -> Result<(), AppError> {
+        sqlx::query!(
+            "
+UPDATE setlists SET songs = ARRAY(
+  SELECT id FROM unnest($1::int[]) WITH ORDINALITY AS t(id, n)
+  WHERE EXISTS (SELECT 1 FROM songs s WHERE s.id = t.id AND s.band_id = $3)
+  ORDER BY n)
+WHERE id = $2 AND band_id = $3",
+            songs,
+            id,
+            self.band_id
+        )
+        .execute(crate::database::get_db())
+        .await?;
+        Ok(())
+    }
+
+    async fn add_songs(&self, id: i32, songs: &[i32]) -> Result<Setlist, AppError> {
+        let mut tx = crate::database::get_db().begin().await?;
+        let before = sqlx::query_as!(
+            Setlist,
+            "SELECT id, title, is_locked, songs FROM setlists WHERE id = $1 AND band_id = $2 FOR UPDATE",
+            id,
+            self.band_id
+        )
+        .fetch_one(&mut *tx)
+        .await?;
+        before.check_unlocked()?;
+
+        let new_songs = before.new_songs(songs);
+        if !new_songs.is_empty() {
+            sqlx::query!(
+                "
+UPDATE setlists SET songs = songs || ARRAY(
+  SELECT id FROM unnest($1::int[]) WITH ORDINALITY AS t(id, n)
+  WHERE EXISTS (SELECT 1 FROM songs s WHERE s.id = t.id AND s.band_id = $3)
+  ORDER BY n)
+WHERE id = $2",
+                &new_songs,
+                id,
+                self.band_id
+            )
+            .execute(&mut *tx)
+            .await?;
+        }
+
+        tx.commit().await?;
+        Ok(before)
+    }
+}
+
+pub struct PgAudit {
+    pub band_id: i32,
+}
+
+struct AuditRow {
+    id: i32,
+    created_at: chrono::NaiveDateTime,
+    actor: String,
+    entity: String,
+    entity_id: i32,
+    action: String,
+    before: Option<String>,
+    after: Option<String>,
+    undone_at: Option<chrono::NaiveDateTime>,
+}
+
+impl TryFrom<AuditRow> for AuditEntry {
+    type Error = AppError;
+
+    fn try_from(row: AuditRow) -> Result<Self, Self::Error> {
+        let json = |value: Option<String>| value.and_then(|v| serde_json::from_str(&v).ok());
+        Ok(AuditEntry {
+            id: row.id,
+            created_at: row.created_at,
+            actor: row.actor,
+            entity: row.entity.parse().map_err(AppError::Internal)?,
+            entity_id: row.entity_id,
+            action: row.action.parse().map_err(AppError::Internal)?,
+            before: json(row.before),
+            after: json(row.after),
+            undone_at: row.undone_at,
+        })
+    }
+}
+
+#[async_trait]
+impl AuditRepository for PgAudit {
+    async fn record(&self, entry: NewAuditEntry) -> Result<(), AppError> {
+        sqlx::query!(
+            "
+INSERT INTO audit_log (actor, entity, entity_id, action, before, after, band_id)
+VALUES ($1, $2, $3, $4, $5::text::jsonb, $6::text::jsonb, $7)",
+            entry.actor,
+            entry.entity.as_str(),
+            entry.entity_id,
+            entry.action.as_str(),
+            entry.before.map(|v| v.to_string()),
+            entry.after.map(|v| v.to_string()),
+            self.band_id,
+        )
+        .execute(crate::database::get_db())
+        .await?;
+        Ok(())
+    }
+
+    async fn get_recent(&self, limit: i64) -> Result<Vec<AuditEntry>, AppError> {
+        sqlx::query_as!(
+            AuditRow,
+            r#"
+SELECT id, created_at, actor, entity, entity_id, action,
+  before::text as "before?", after::text as "after?", undone_at
+FROM audit_log WHERE band_id = $2 ORDER BY id DESC LIMIT $1"#,
+            limit,
+            self.band_id
+        )
+        .fetch_all(crate::database::get_db())
+        .await?
+        .into_iter()
+        .map(AuditEntry::try_from)
+        .collect()
+    }
+
+    async fn last_undoable(&self) -> Result<Option<AuditEntry>, AppError> {
+        sqlx::query_as!(
+            AuditRow,
+            r#"
+SELECT id, created_at, actor, entity, entity_id, action,
+  before::text as "before?", after::text as "after?", undone_at
+FROM audit_log
+WHERE band_id = $1 AND action IN ('delete', 'clear', 'replace') AND undone_at IS NULL
+ORDER BY id DESC LIMIT 1"#,
+            self.band_id
+        )
+        .fetch_optional(crate::database::get_db())
+        .await?
+        .map(AuditEntry::try_from)
+        .transpose()
+    }
+
+    async fn mark_undone(&self, id: i32) -> Result<(), AppError> {
+        sqlx::query!(
+            "UPDATE audit_log SET undone_at = now() WHERE id = $1 AND band_id = $2",
+            id,
+            self.band_id
+        )
+        .execute(crate::database::get_db())
+        .await?;
+        Ok(())
+    }
+}
+
+pub struct PgRevisions {
+    pub band_id: i32,
+}
+
+struct RevisionRow {
+    id: i32,
+    song_id: i32,
+    created_at: chrono::NaiveDateTime,
+    author: String,
+    artist: String,
+    title: String,
+    bpm: Option<i32>,
+    lyrics: String,
+    readiness: String,
+    duration: Option<i32>,
+    tags: Vec<String>,
+    arrangement: String,
+    musical_key: Option<String>,
+}
+
+impl From<RevisionRow> for SongRevision {
+    fn from(row: RevisionRow) -> Self {
+        SongRevision {
+            id: row.id,
+            song_id: row.song_id,
+            created_at: row.created_at,
+            author: row.author,
+            artist: row.artist,
+            title: row.title,
+            bpm: row.bpm,
+            lyrics: row.lyrics,
+            readiness: row.readiness.parse().unwrap_or_default(),
+            duration: row.duration,
+            tags: row.tags,
+            arrangement: row.arrangement,
+            key: row.musical_key.and_then(|k| k.parse().ok()),
+        }
+    }
+}
+
+#[async_trait]
+impl RevisionRepository for PgRevisions {
+    async fn get(&self, id: i32) -> Result<SongRevision, AppError> {
+        sqlx::query_as!(
+            RevisionRow,
+            "
+SELECT r.* FROM song_revisions r JOIN songs s ON s.id = r.song_id
+WHERE r.id = $1 AND s.band_id = $2",
+            id,
+            self.band_id
+        )
+        .fetch_one(crate::database::get_db())
+            .await
+            .map(SongRevision::from)
+            .map_err(AppError::from)
+    }
+
+    async fn get_for_song(&self, song_id: i32) -> Result<Vec<SongRevision>, AppError> {
+        sqlx::query_as!(
+            RevisionRow,
+            "
+SELECT r.* FROM song_revisions r JOIN songs s ON s.id = r.song_id
+WHERE r.song_id = $1 AND s.band_id = $2
+ORDER BY r.id DESC",
+            song_id,
+            self.band_id
+        )
+        .fetch_all(crate::database::get_db())
+        .await
+        .map(|rows| rows.into_iter().map(SongRevision::from).collect())
+        .map_err(AppError::from)
+    }
+
+    async fn record(&self, revision: NewSongRevision) -> Result<SongRevision, AppError> {
+        sqlx::query_as!(
+            RevisionRow,
+            "
+INSERT INTO song_revisions
+  (song_id, author, artist, title, bpm, lyrics, readiness, duration, tags, arrangement, musical_key)
+SELECT $1, $2, $3, $4, $5, $6, $7, $8, $9, $10, $11
+WHERE EXISTS (SELECT 1 FROM songs WHERE id = $1 AND band_id = $12)
+RETURNING *",
+            revision.song_id,
+            revision.author,
+            revision.artist,
+            revision.title,
+            revision.bpm,
+            revision.lyrics,
+            revision.readiness.as_str(),
+            revision.duration,
+            &revision.tags,
+            revision.arrangement,
+            revision.key.map(|k| k.to_string()),
+            self.band_id,
+        )
+        .fetch_one(crate::database::get_db())
+        .await
+        .map(SongRevision::from)
+        .map_err(AppError::from)
+    }
+}
+
+pub struct PgAnnotations {
+    pub band_id: i32,
+}
+
+#[async_trait]
+impl AnnotationRepository for PgAnnotations {
+    async fn get_for_song(&self, song_id: i32) -> Result<Vec<Annotation>, AppError> {
+        sqlx::query_as!(
+            Annotation,
+            r#"
+SELECT a.id, a.song_id, a.member_id, m.name as member, a.line, a.anchor, a.body, a.created_at
+FROM song_annotations a
+JOIN members m ON m.id = a.member_id
+JOIN songs s ON s.id = a.song_id
+WHERE a.song_id = $1 AND s.band_id = $2 ORDER BY a.id"#,
+            song_id,
+            self.band_id
+        )
+        .fetch_all(crate::database::get_db())
+        .await
+        .map_err(AppError::from)
+    }
+
+    async fn create(&self, annotation: NewAnnotation) -> Result<i32, AppError> {
+        sqlx::query!(
+            "
+INSERT INTO song_annotations (song_id, member_id, line, anchor, body)
+SELECT $1, $2, $3, $4, $5
+WHERE EXISTS (SELECT 1 FROM songs WHERE id = $1 AND band_id = $6)
+  AND EXISTS (SELECT 1 FROM members WHERE id = $2 AND band_id = $6)
+RETURNING id",
+            annotation.song_id,
+            annotation.member_id,
+            annotation.line,
+            annotation.anchor,
+            annotation.body,
+            self.band_id,
+        )
+        .fetch_one(crate::database::get_db())
+        .await
+        .map(|row| row.id)
+        .map_err(AppError::from)
+    }
+
+    async fn remove(&self, id: i32) -> Result<(), AppError> {
+        sqlx::query!(
+            "
+DELETE FROM song_annotations a USING songs s
+WHERE a.id = $1 AND s.id = a.song_id AND s.band_id = $2",
+            id,
+            self.band_id
+        )
+        .execute(crate::database::get_db())
+        .await?;
+        Ok(())
+    }
+
+    async fn set_anchor(&self, id: i32, line: i32, anchor: String) -> Result<(), AppError> {
+        sqlx::query!(
+            "
+UPDATE song_annotations a SET line = $2, anchor = $3 FROM songs s
+WHERE a.id = $1 AND s.id = a.song_id AND s.band_id = $4",
+            id,
+            line,
+            anchor,
+            self.band_id
+        )
+        .execute(crate::database::get_db())
+        .await?;
+        Ok(())
+    }
+}
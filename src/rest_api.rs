@@ -0,0 +1,154 @@
+use axum::extract::Path;
+use axum::Extension;
+use axum::http::StatusCode;
+use axum::response::{IntoResponse, Response};
+use axum::routing::{get, post};
+use axum::{Json, Router};
+use serde::Serialize;
+use utoipa::{OpenApi, ToSchema};
+
+use crate::error::AppError;
+use crate::models::gig::GigModel;
+use crate::models::key::{Key, Mode};
+use crate::models::section::{Section, SectionKind};
+use crate::models::setlist::Setlist;
+use crate::models::song::{Readiness, Song};
+use crate::models::song_role::{RoleKind, SongRole};
+use crate::repository::Repositories;
+
+/// The versioned JSON API, meant for scripts and apps rather than the
+/// Leptos frontend. Everything here is nested under `/api/v1` and expects the
+/// [`Repositories`] as an `Extension`.
+#[derive(OpenApi)]
+#[openapi(
+    info(title = "My Dad Rocks API", version = "1"),
+    servers((url = "/api/v1")),
+    paths(
+        list_songs,
+        get_song,
+        record_play,
+        list_setlists,
+        get_setlist,
+        list_gigs,
+        get_gig
+    ),
+    components(schemas(
+        Song, Readiness, SongRole, RoleKind, Section, SectionKind, Key, Mode, Setlist, GigModel, ApiError
+    ))
+)]
+pub struct ApiDoc;
+
+pub fn routes<S: Clone + Send + Sync + 'static>() -> Router<S> {
+    Router::new()
+        .route("/openapi.json", get(openapi))
+        .route("/songs", get(list_songs))
+        .route("/songs/:id", get(get_song))
+        .route("/songs/:id/plays", post(record_play))
+        .route("/setlists", get(list_setlists))
+        .route("/setlists/:id", get(get_setlist))
+        .route("/gigs", get(list_gigs))
+        .route("/gigs/:id", get(get_gig))
+}
+
+#[derive(Debug, Serialize, ToSchema)]
+pub struct ApiError {
+    #[serde(skip)]
+    status: StatusCode,
+    error: String,
+}
+
+impl From<AppError> for ApiError {
+    fn from(e: AppError) -> Self {
+        ApiError {
+            status: e.status_code(),
+            error: e.to_string(),
+        }
+    }
+}
+
+impl IntoResponse for ApiError {
+    fn into_response(self) -> Response {
+        (self.status, Json(self)).into_response()
+    }
+}
+
+type ApiResult<T> = Result<Json<T>, ApiError>;
+
+async fn openapi() -> Json<utoipa::openapi::OpenApi> {
+    Json(ApiDoc::openapi())
+}
+
+/// All songs, ordered by title.
+#[utoipa::path(get, path = "/songs", responses((status = 200, body = [Song])))]
+pub async fn list_songs(Extension(repos): Extension<Repositories>) -> ApiResult<Vec<Song>> {
+    Ok(Json(repos.songs.get_all().await?))
+}
+
+#[utoipa::path(
+    get,
+    path = "/songs/{id}",
+    params(("id" = i32, Path, description = "Song id")),
+    responses((status = 200, body = Song), (status = 404, body = ApiError))
+)]
+pub async fn get_song(
+    Extension(repos): Extension<Repositories>,
+    Path(id): Path<i32>,
+) -> ApiResult<Song> {
+    Ok(Json(repos.songs.get(id).await?))
+}
+
+/// Marks the song as played today and returns it.
+#[utoipa::path(
+    post,
+    path = "/songs/{id}/plays",
+    params(("id" = i32, Path, description = "Song id")),
+    responses((status = 200, body = Song), (status = 404, body = ApiError))
+)]
+pub async fn record_play(
+    Extension(repos): Extension<Repositories>,
+    Path(id): Path<i32>,
+) -> ApiResult<Song> {
+    let song = repos.songs.get(id).await?;
+    repos.songs.set_played(song.id).await?;
+    Ok(Json(repos.songs.get(id).await?))
+}
+
+#[utoipa::path(get, path = "/setlists", responses((status = 200, body = [Setlist])))]
+pub async fn list_setlists(
+    Extension(repos): Extension<Repositories>,
+) -> ApiResult<Vec<Setlist>> {
+    Ok(Json(repos.setlists.get_all().await?))
+}
+
+#[utoipa::path(
+    get,
+    path = "/setlists/{id}",
+    params(("id" = i32, Path, description = "Setlist id")),
+    responses((status = 200, body = Setlist), (status = 404, body = ApiError))
+)]
+pub async fn get_setlist(
+    Extension(repos): Extension<Repositories>,
+    Path(id): Path<i32>,
+) -> ApiResult<Setlist> {
+    Ok(Json(repos.setlists.get(id).await?))
+}
+
+/// All gigs, oldest first. `songs` holds the running order as song ids,
+/// with negative ids for breaks.
+#[utoipa::path(get, path = "/gigs", responses((status = 200, body = [GigModel])))]
+pub async fn list_gigs(Extension(repos): Extension<Repositories>) -> ApiResult<Vec<GigModel>> {
+    Ok(Json(repos.gigs.get_all().await?))
+}
+
+#[utoipa::path(
+    get,
+    path = "/gigs/{id}",
+    params(("id" = i32, Path, description = "Gig id")),
+    responses((status = 200, body = GigModel), (status = 404, body = ApiError))
+)]
+pub async fn get_gig(
+    Extension(repos): Extension<Repositories>,
+    Path(id): Path<i32>,
+) -> ApiResult<GigModel> {
+    Ok(Json(repos.gigs.get(id).await?))
+}
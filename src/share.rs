@@ -0,0 +1,14 @@
+use axum::extract::Path;
+use axum::response::{IntoResponse, Response};
+use axum::Json;
+
+use crate::models::gig_share::SharedGig;
+
+/// Serves a shared setlist as JSON at `/gedeeld/:token/setlist.json`, without
+/// logging in.
+pub async fn shared_gig_json(Path(token): Path<String>) -> Response {
+    match SharedGig::get(&token, chrono::Local::now().date_naive()).await {
+        Ok(gig) => Json(gig).into_response(),
+        Err(e) => (e.status_code(), e.to_string()).into_response(),
+    }
+}
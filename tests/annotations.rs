@@ -0,0 +1,87 @@
+//! Lyric annotations finding their line again after edits.
+#![cfg(feature = "ssr")]
+
+use futures::executor::block_on;
+use my_dad_rocks::error::AppError;
+use my_dad_rocks::models::annotation::{find_anchor, similarity, Annotation};
+use my_dad_rocks::models::song::Song;
+use my_dad_rocks::repository::Repositories;
+
+const LYRICS: &str = "Wake up, little Susie\n\nWe've both been sound asleep\nWake up, little Susie";
+
+#[test]
+fn similar_lines_look_alike() {
+    assert_eq!(similarity("Hold on!", "hold   on"), 1.0);
+    assert!(similarity("We've both been sound asleep", "We both been sound asleep") > 0.9);
+    assert!(similarity("Wake up", "The movie's over") < 0.5);
+}
+
+#[test]
+fn anchors_follow_moved_and_changed_lines() {
+    let lines = ["Intro", "We've both been sound asleep", "Chorus"];
+    // Unchanged.
+    assert_eq!(
+        find_anchor(&lines, 1, "We've both been sound asleep"),
+        Some(1)
+    );
+    // Moved: the nearest identical line wins.
+    assert_eq!(find_anchor(&lines, 0, "Chorus"), Some(2));
+    // Slightly changed.
+    assert_eq!(
+        find_anchor(&lines, 1, "We've both been fast asleep"),
+        Some(1)
+    );
+    // Gone.
+    assert_eq!(
+        find_anchor(&lines, 1, "The movie's over, it's four o'clock"),
+        None
+    );
+
+    let repeated = ["Wake up", "sleep", "Wake up"];
+    assert_eq!(find_anchor(&repeated, 3, "Wake up"), Some(2));
+}
+
+#[test]
+fn annotations_survive_lyric_edits() {
+    let song = Song {
+        id: 1,
+        title: "Wake Up Little Susie".to_string(),
+        lyrics: LYRICS.to_string(),
+        ..Default::default()
+    };
+    let repos = Repositories::in_memory(vec![song], vec![], vec![]);
+    block_on(async {
+        assert_eq!(
+            Annotation::create(&repos, 1, 1, 1, "stilte".to_string()).await,
+            Err(AppError::validation("Kies een regel met tekst"))
+        );
+        Annotation::create(&repos, 1, 1, 2, "drums eruit".to_string())
+            .await
+            .unwrap();
+        Annotation::create(&repos, 1, 1, 3, " laatste keer aanhouden ".to_string())
+            .await
+            .unwrap();
+
+        let edited = "Intro\nWake up, little Susie\n\nWe've both been fast asleep";
+        Annotation::reanchor(&repos, 1, edited).await.unwrap();
+
+        let annotations = repos.annotations.get_for_song(1).await.unwrap();
+        let anchors: Vec<(i32, &str)> = annotations
+            .iter()
+            .map(|a| (a.line, a.anchor.as_str()))
+            .collect();
+        assert_eq!(
+            anchors,
+            [
+                (3, "We've both been fast asleep"),
+                (1, "Wake up, little Susie")
+            ]
+        );
+        assert_eq!(annotations[1].body, "laatste keer aanhouden");
+
+        let (placed, loose) = Annotation::place(annotations, edited);
+        let counts: Vec<usize> = placed.iter().map(Vec::len).collect();
+        assert_eq!(counts, [0, 1, 0, 1]);
+        assert!(loose.is_empty());
+    });
+}
@@ -0,0 +1,65 @@
+//! Song arrangements written as text and the length they add up to.
+use my_dad_rocks::i18n::Locale;
+use my_dad_rocks::models::section::{Section, SectionKind};
+use my_dad_rocks::models::song::Song;
+
+fn section(kind: SectionKind, bars: i32, repeats: i32) -> Section {
+    Section {
+        kind,
+        bars,
+        repeats,
+    }
+}
+
+#[test]
+fn arrangement_round_trips_through_text() {
+    let sections =
+        Section::parse_list("Intro 4, couplet 16 x2, CHORUS 8, pre-refrein 4 ×3,").unwrap();
+    assert_eq!(
+        sections,
+        [
+            section(SectionKind::Intro, 4, 1),
+            section(SectionKind::Verse, 16, 2),
+            section(SectionKind::Chorus, 8, 1),
+            section(SectionKind::PreChorus, 4, 3),
+        ]
+    );
+    let text = Section::format_list(&sections, Locale::Nl);
+    assert_eq!(text, "intro 4, couplet 16 x2, refrein 8, pre-refrein 4 x3");
+    assert_eq!(Section::parse_list(&text), Ok(sections.clone()));
+    let english = Section::format_list(&sections, Locale::En);
+    assert_eq!(english, "intro 4, verse 16 x2, chorus 8, pre-chorus 4 x3");
+    assert_eq!(Section::parse_list(&english), Ok(sections));
+    assert_eq!(Section::parse_list(" "), Ok(vec![]));
+}
+
+#[test]
+fn bad_sections_are_refused() {
+    assert_eq!(
+        Section::parse_list("intro"),
+        Err("Verwacht deel, maten en herhalingen in \"intro\"".to_string())
+    );
+    assert_eq!(
+        Section::parse_list("solo 0"),
+        Err("Ongeldig aantal maten in \"solo 0\"".to_string())
+    );
+    assert!(Section::parse_list("kazoo 4").is_err());
+}
+
+#[test]
+fn length_follows_from_bars_and_bpm() {
+    // 4 + 32 + 8 = 44 bars of 4 beats at 120 bpm.
+    let sections = Section::parse_list("intro 4, couplet 16 x2, outro 8").unwrap();
+    assert_eq!(Section::playing_seconds(&sections, Some(120)), Some(88));
+    assert_eq!(Section::playing_seconds(&sections, None), None);
+    assert_eq!(Section::playing_seconds(&[], Some(120)), None);
+
+    let mut song = Song {
+        bpm: Some(120),
+        sections,
+        ..Default::default()
+    };
+    assert_eq!(song.length_seconds(), Some(88));
+    song.duration = Some(200);
+    assert_eq!(song.length_seconds(), Some(200));
+}
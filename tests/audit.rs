@@ -0,0 +1,168 @@
+//! The audit log and undo, on top of the in-memory repositories.
+#![cfg(feature = "ssr")]
+
+use chrono::NaiveDate;
+use futures::executor::block_on;
+use http::{HeaderMap, HeaderValue};
+use my_dad_rocks::error::AppError;
+use my_dad_rocks::i18n::Locale;
+use my_dad_rocks::models::audit::{
+    actor_from_headers, AuditAction, AuditEntity, AuditEntry, UNKNOWN_ACTOR,
+};
+use my_dad_rocks::models::band::PROXY_SECRET_HEADER;
+use my_dad_rocks::models::gig::{Gig, GigModel};
+use my_dad_rocks::models::setlist::Setlist;
+use my_dad_rocks::models::song::Song;
+use my_dad_rocks::repository::Repositories;
+use serde_json::json;
+
+fn repos(setlist_songs: &[i32]) -> Repositories {
+    let gig = GigModel {
+        id: 1,
+        venue: "Paradiso".to_string(),
+        date: NaiveDate::from_ymd_opt(2024, 6, 1).unwrap(),
+        time: None,
+        songs: vec![1, -1, 2],
+        venue_id: None,
+    };
+    let setlist = Setlist {
+        id: 1,
+        title: "Oefenen".to_string(),
+        is_locked: false,
+        songs: setlist_songs.to_vec(),
+    };
+    let songs = (1..=5)
+        .map(|id| Song {
+            id,
+            ..Default::default()
+        })
+        .collect();
+    Repositories::in_memory(songs, vec![gig], vec![setlist])
+}
+
+#[test]
+fn actor_comes_from_the_proxy_or_the_client_address() {
+    const SECRET: &str = "proxy-geheim-0123456789";
+    let mut headers = HeaderMap::new();
+    assert_eq!(actor_from_headers(&headers, Some(SECRET)), UNKNOWN_ACTOR);
+
+    headers.insert(
+        "x-forwarded-for",
+        HeaderValue::from_static("10.0.0.1, 10.0.0.2"),
+    );
+    assert_eq!(actor_from_headers(&headers, Some(SECRET)), "10.0.0.1");
+
+    // A client can send the user header itself, so it only counts with the
+    // proxy's secret.
+    headers.insert("x-forwarded-user", HeaderValue::from_static("henk"));
+    assert_eq!(actor_from_headers(&headers, Some(SECRET)), "10.0.0.1");
+    headers.insert(PROXY_SECRET_HEADER, HeaderValue::from_static(SECRET));
+    assert_eq!(actor_from_headers(&headers, None), "10.0.0.1");
+    assert_eq!(actor_from_headers(&headers, Some(SECRET)), "henk");
+}
+
+#[test]
+fn summary_names_the_changed_thing() {
+    let entry = AuditEntry {
+        id: 1,
+        created_at: NaiveDate::from_ymd_opt(2024, 6, 2)
+            .unwrap()
+            .and_hms_opt(12, 0, 0)
+            .unwrap(),
+        actor: "henk".to_string(),
+        entity: AuditEntity::Gig,
+        entity_id: 7,
+        action: AuditAction::Delete,
+        before: Some(json!({"venue": "Paradiso", "date": "2024-06-01", "songs": [1, 2]})),
+        after: None,
+        undone_at: None,
+    };
+    assert_eq!(
+        entry.summary(Locale::Nl),
+        "Optreden Paradiso (01-06-2024) verwijderd"
+    );
+    assert_eq!(
+        entry.summary(Locale::En),
+        "Gig Paradiso (1 Jun 2024) deleted"
+    );
+    assert_eq!(entry.songs_before(), Ok(vec![1, 2]));
+    assert!(entry.can_undo());
+
+    let unnamed = AuditEntry {
+        entity: AuditEntity::Song,
+        action: AuditAction::Update,
+        before: Some(json!({"bpm": 120})),
+        after: Some(json!({"bpm": 128})),
+        ..entry
+    };
+    assert_eq!(unnamed.summary(Locale::Nl), "Nummer #7 gewijzigd");
+    assert_eq!(
+        unnamed.changed_fields(),
+        [("bpm".to_string(), Some(json!(120)), Some(json!(128)))]
+    );
+    assert!(!unnamed.can_undo());
+}
+
+#[test]
+fn undo_restores_a_deleted_gig() {
+    let repos = repos(&[]);
+    block_on(async {
+        Gig::remove(&repos, 1).await.unwrap();
+        assert!(repos.gigs.get(1).await.is_err());
+        assert_eq!(repos.gigs.get_deleted().await.unwrap().len(), 1);
+
+        let undone = AuditEntry::undo_last(&repos).await.unwrap().unwrap();
+        assert_eq!(undone.action, AuditAction::Delete);
+        assert_eq!(repos.gigs.get(1).await.unwrap().songs, [1, -1, 2]);
+        assert!(repos.gigs.get_deleted().await.unwrap().is_empty());
+
+        // Only once, and the undo itself is logged.
+        assert_eq!(AuditEntry::undo_last(&repos).await, Ok(None));
+        let log = repos.audit.get_recent(10).await.unwrap();
+        let actions: Vec<AuditAction> = log.iter().map(|e| e.action).collect();
+        assert_eq!(actions, [AuditAction::Undo, AuditAction::Delete]);
+        assert!(log[1].undone_at.is_some());
+    });
+}
+
+#[test]
+fn undo_refills_a_cleared_setlist_before_older_changes() {
+    let repos = repos(&[3, 4]);
+    block_on(async {
+        Gig::remove(&repos, 1).await.unwrap();
+        Setlist::clean(&repos).await.unwrap();
+        // Cleaning an empty setlist isn't logged, so it can't hide the first one.
+        Setlist::clean(&repos).await.unwrap();
+        assert!(repos.setlists.get(1).await.unwrap().songs.is_empty());
+
+        let undone = AuditEntry::undo_last(&repos).await.unwrap().unwrap();
+        assert_eq!(undone.entity, AuditEntity::Setlist);
+        assert_eq!(repos.setlists.get(1).await.unwrap().songs, [3, 4]);
+
+        let undone = AuditEntry::undo_last(&repos).await.unwrap().unwrap();
+        assert_eq!(undone.entity, AuditEntity::Gig);
+        assert!(repos.gigs.get(1).await.is_ok());
+    });
+}
+
+#[test]
+fn undo_refuses_to_throw_away_later_changes() {
+    let repos = repos(&[3, 4]);
+    block_on(async {
+        Setlist::clean(&repos).await.unwrap();
+        Setlist::set_songs(&repos, vec![5]).await.unwrap();
+
+        assert!(matches!(
+            AuditEntry::undo_last(&repos).await,
+            Err(AppError::Conflict(_))
+        ));
+        assert_eq!(repos.setlists.get(1).await.unwrap().songs, [5]);
+
+        Gig::remove(&repos, 1).await.unwrap();
+        repos.gigs.restore(1).await.unwrap();
+        assert!(matches!(
+            AuditEntry::undo_last(&repos).await,
+            Err(AppError::Conflict(_))
+        ));
+    });
+}
@@ -0,0 +1,154 @@
+#![cfg(feature = "ssr")]
+
+use chrono::NaiveDate;
+use my_dad_rocks::backup::{Archive, BackupError, TableDump, ARCHIVE_FORMAT, TABLES};
+use my_dad_rocks::config::{Config, ConfigOverrides};
+use serde_json::json;
+use sqlx::{Connection, Executor, PgConnection};
+
+fn archive() -> Archive {
+    Archive {
+        format: ARCHIVE_FORMAT,
+        schema_version: 20261019180000,
+        exported_at: NaiveDate::from_ymd_opt(2026, 10, 19)
+            .unwrap()
+            .and_hms_opt(12, 0, 0)
+            .unwrap(),
+        tables: vec![
+            TableDump {
+                name: "songs".to_string(),
+                rows: vec![json!({
+                    "id": 1,
+                    "title": "Paranoid",
+                    "artist": "Black Sabbath",
+                    "lyrics": "Finished with my woman\n'cause she couldn't help me",
+                    "last_played_at": "2024-01-03",
+                    "release_mid": "8d0bc6d4-8700-44e8-90c8-b86c23e7ff14",
+                })],
+            },
+            TableDump {
+                name: "gigs".to_string(),
+                rows: vec![json!({
+                    "id": 1,
+                    "venue": "Paard",
+                    "date": "2024-05-19",
+                    "songs": [1, -1],
+                })],
+            },
+        ],
+    }
+}
+
+#[test]
+fn json_round_trip() {
+    let archive = archive();
+    let json = serde_json::to_string_pretty(&archive).unwrap();
+
+    assert_eq!(serde_json::from_str::<Archive>(&json).unwrap(), archive);
+}
+
+#[test]
+fn rows_by_table() {
+    let archive = archive();
+
+    assert_eq!(archive.rows("gigs")[0]["venue"], "Paard");
+    assert!(archive.rows("members").is_empty());
+}
+
+#[test]
+fn validates_before_import() {
+    let archive = archive();
+    assert!(archive.validate(20261019180000).is_ok());
+    assert!(matches!(
+        archive.validate(20261019190000),
+        Err(BackupError::Schema { .. })
+    ));
+
+    let mut newer = archive.clone();
+    newer.format = ARCHIVE_FORMAT + 1;
+    assert!(matches!(
+        newer.validate(20261019180000),
+        Err(BackupError::Format(_))
+    ));
+
+    let mut injected = archive;
+    injected.tables.push(TableDump {
+        name: "songs; DROP TABLE songs".to_string(),
+        rows: vec![],
+    });
+    assert!(matches!(
+        injected.validate(20261019180000),
+        Err(BackupError::UnknownTable(_))
+    ));
+}
+
+#[test]
+fn parents_come_first() {
+    let position = |table| TABLES.iter().position(|t| *t == table).unwrap();
+
+    assert!(position("songs") < position("song_tags"));
+    assert!(position("venues") < position("gigs"));
+    assert!(position("members") < position("gig_payout_shares"));
+    assert!(position("rehearsals") < position("rehearsal_songs"));
+}
+
+#[test]
+fn only_imports_over_data_when_forced() {
+    assert!(Archive::check_empty(vec![], false).is_ok());
+    assert!(Archive::check_empty(vec!["songs".to_string()], true).is_ok());
+    assert!(matches!(
+        Archive::check_empty(vec!["songs".to_string(), "gigs".to_string()], false),
+        Err(BackupError::NotEmpty(tables)) if tables == ["songs", "gigs"]
+    ));
+}
+
+/// Migrates a new database next to the one at `DATABASE_URL`, exports it,
+/// restores that without force and exports again.
+#[tokio::test]
+async fn fresh_database_round_trip() {
+    dotenv::dotenv().ok();
+    let server = std::env::var("DATABASE_URL").expect("DATABASE_URL is needed for this test");
+    let (host, _) = server.rsplit_once('/').unwrap();
+    let mut admin = PgConnection::connect(&server).await.unwrap();
+    admin
+        .execute("DROP DATABASE IF EXISTS mdr_backup_round_trip WITH (FORCE)")
+        .await
+        .unwrap();
+    admin
+        .execute("CREATE DATABASE mdr_backup_round_trip")
+        .await
+        .unwrap();
+
+    let config = Config::load(&ConfigOverrides {
+        database_url: Some(format!("{}/mdr_backup_round_trip", host)),
+        ..Default::default()
+    })
+    .unwrap();
+    my_dad_rocks::database::init_db(&config.database)
+        .await
+        .unwrap();
+
+    let before = Archive::export().await.unwrap();
+    assert!(!before.rows("songs").is_empty());
+    before.import(false).await.unwrap();
+    before.import(false).await.unwrap();
+    let after = Archive::export().await.unwrap();
+    assert_eq!(after.tables, before.tables);
+
+    // Once the band changed the migrated rows, they are its own.
+    sqlx::query("UPDATE setlists SET songs = '{1}'")
+        .execute(my_dad_rocks::database::get_db())
+        .await
+        .unwrap();
+    assert!(matches!(
+        before.import(false).await,
+        Err(BackupError::NotEmpty(tables)) if tables == ["setlists"]
+    ));
+    before.import(true).await.unwrap();
+
+    my_dad_rocks::database::get_db().close().await;
+    admin
+        .execute("DROP DATABASE mdr_backup_round_trip WITH (FORCE)")
+        .await
+        .unwrap();
+}
@@ -0,0 +1,153 @@
+use http::header::COOKIE;
+use http::HeaderMap;
+use my_dad_rocks::error::AppError;
+use my_dad_rocks::models::band::{
+    requested_band, user_from_headers, Band, BandRole, Membership, BAND_HEADER, PROXY_SECRET_HEADER,
+};
+
+const SECRET: &str = "proxy-geheim-0123456789";
+
+fn band(id: i32, name: &str) -> Band {
+    Band {
+        id,
+        name: name.to_string(),
+    }
+}
+
+fn membership(user_name: &str, role: BandRole) -> Membership {
+    Membership {
+        band_id: 1,
+        user_name: user_name.to_string(),
+        role,
+    }
+}
+
+fn headers(pairs: &[(&'static str, &str)]) -> HeaderMap {
+    let mut headers = HeaderMap::new();
+    for (name, value) in pairs {
+        headers.append(*name, value.parse().unwrap());
+    }
+    headers
+}
+
+#[test]
+fn picks_the_requested_band_only_when_accessible() {
+    // Sorted by name, like the switcher shows them.
+    let accessible = vec![band(3, "De Coverband"), band(1, "My Dad Rocks")];
+
+    assert_eq!(
+        Band::pick(accessible.clone(), Some(3)),
+        Some(band(3, "De Coverband"))
+    );
+    assert_eq!(
+        Band::pick(accessible.clone(), Some(2)),
+        Some(band(1, "My Dad Rocks"))
+    );
+    assert_eq!(Band::pick(accessible, None), Some(band(1, "My Dad Rocks")));
+    assert_eq!(Band::pick(vec![], Some(1)), None);
+}
+
+#[test]
+fn bands_without_members_are_open_to_everyone() {
+    assert!(Membership::may_open(&[], None));
+    assert!(Membership::may_manage(&[], Some("jan")));
+
+    let memberships = vec![
+        membership("jan", BandRole::Admin),
+        membership("piet", BandRole::Member),
+    ];
+    assert!(Membership::may_open(&memberships, Some("piet")));
+    assert!(!Membership::may_open(&memberships, Some("klaas")));
+    assert!(!Membership::may_open(&memberships, None));
+    assert!(Membership::may_manage(&memberships, Some("jan")));
+    assert!(!Membership::may_manage(&memberships, Some("piet")));
+}
+
+#[test]
+fn only_the_first_band_is_made_without_a_user() {
+    assert_eq!(Membership::check_create(0, None), Ok(()));
+    assert_eq!(Membership::check_create(2, Some("jan")), Ok(()));
+    assert!(matches!(
+        Membership::check_create(1, None),
+        Err(AppError::Locked(_))
+    ));
+}
+
+#[test]
+fn a_band_with_members_keeps_an_admin() {
+    assert_eq!(Membership::check_admin_left(&[]), Ok(()));
+    assert_eq!(
+        Membership::check_admin_left(&[membership("jan", BandRole::Admin)]),
+        Ok(())
+    );
+    assert!(matches!(
+        Membership::check_admin_left(&[membership("piet", BandRole::Member)]),
+        Err(AppError::Validation(_))
+    ));
+}
+
+#[test]
+fn the_header_wins_over_the_cookie() {
+    assert_eq!(requested_band(&headers(&[])), None);
+    assert_eq!(
+        requested_band(&headers(&[(COOKIE.as_str(), "theme=dark; band=3")])),
+        Some(3)
+    );
+    assert_eq!(
+        requested_band(&headers(&[(BAND_HEADER, "2"), (COOKIE.as_str(), "band=3")])),
+        Some(2)
+    );
+    assert_eq!(
+        requested_band(&headers(&[(COOKIE.as_str(), "band=drie")])),
+        None
+    );
+}
+
+#[test]
+fn reads_the_user_from_the_proxy() {
+    let secret = (PROXY_SECRET_HEADER, SECRET);
+    assert_eq!(user_from_headers(&headers(&[secret]), Some(SECRET)), None);
+    assert_eq!(
+        user_from_headers(
+            &headers(&[secret, ("x-forwarded-user", " jan ")]),
+            Some(SECRET)
+        ),
+        Some("jan".to_string())
+    );
+    assert_eq!(
+        user_from_headers(
+            &headers(&[secret, ("x-forwarded-user", ""), ("remote-user", "piet")]),
+            Some(SECRET)
+        ),
+        Some("piet".to_string())
+    );
+}
+
+#[test]
+fn only_trusts_the_user_with_the_proxy_secret() {
+    let user = ("x-forwarded-user", "jan");
+    assert_eq!(user_from_headers(&headers(&[user]), Some(SECRET)), None);
+    assert_eq!(
+        user_from_headers(
+            &headers(&[user, (PROXY_SECRET_HEADER, "proxy-geheim-0123456780")]),
+            Some(SECRET)
+        ),
+        None
+    );
+    assert_eq!(
+        user_from_headers(
+            &headers(&[user, (PROXY_SECRET_HEADER, "kort")]),
+            Some(SECRET)
+        ),
+        None
+    );
+    // Without a secret configured nobody is trusted, not even with an empty one.
+    assert_eq!(
+        user_from_headers(&headers(&[user, (PROXY_SECRET_HEADER, SECRET)]), None),
+        None
+    );
+    assert_eq!(
+        user_from_headers(&headers(&[user, (PROXY_SECRET_HEADER, "")]), Some("")),
+        None
+    );
+}
@@ -0,0 +1,164 @@
+#![cfg(feature = "ssr")]
+
+use chrono::{NaiveDate, NaiveDateTime, NaiveTime};
+use my_dad_rocks::calendar::{
+    escape, fold, gig_event, parse_time_range, rehearsal_event, render_calendar, EventTime,
+};
+use my_dad_rocks::models::gig::GigModel;
+use my_dad_rocks::models::rehearsal::RehearsalModel;
+use my_dad_rocks::models::venue::Venue;
+
+const BASE_URL: &str = "https://mydadrocks.example";
+
+fn time(hour: u32, minute: u32) -> Option<NaiveTime> {
+    NaiveTime::from_hms_opt(hour, minute, 0)
+}
+
+fn at(day: u32, hour: u32, minute: u32) -> NaiveDateTime {
+    NaiveDate::from_ymd_opt(2026, 11, day)
+        .unwrap()
+        .and_hms_opt(hour, minute, 0)
+        .unwrap()
+}
+
+fn gig(time: Option<&str>) -> GigModel {
+    GigModel {
+        id: 4,
+        venue: "De Kroeg".to_string(),
+        date: NaiveDate::from_ymd_opt(2026, 11, 14).unwrap(),
+        time: time.map(str::to_string),
+        songs: vec![],
+        venue_id: Some(2),
+    }
+}
+
+/// The content lines of a rendered calendar, unfolded again.
+fn unfolded(calendar: &str) -> Vec<String> {
+    calendar
+        .replace("\r\n ", "")
+        .split("\r\n")
+        .filter(|line| !line.is_empty())
+        .map(str::to_string)
+        .collect()
+}
+
+#[test]
+fn parses_time_ranges() {
+    assert_eq!(parse_time_range("20:00-23:00"), (time(20, 0), time(23, 0)));
+    assert_eq!(parse_time_range("20.30 - 23u"), (time(20, 30), time(23, 0)));
+    assert_eq!(parse_time_range("21u"), (time(21, 0), None));
+    assert_eq!(parse_time_range("20:00 -"), (time(20, 0), None));
+    assert_eq!(parse_time_range("na de soundcheck"), (None, None));
+    assert_eq!(parse_time_range("25:00"), (None, None));
+    assert_eq!(parse_time_range(""), (None, None));
+}
+
+#[test]
+fn escapes_text_values() {
+    assert_eq!(
+        escape("Rock, pop; en\\meer\nnog een regel\r\nklaar"),
+        "Rock\\, pop\\; en\\\\meer\\nnog een regel\\nklaar"
+    );
+}
+
+#[test]
+fn folds_at_75_octets_without_splitting_characters() {
+    assert_eq!(fold("SUMMARY:kort"), "SUMMARY:kort\r\n");
+
+    let line = format!("DESCRIPTION:{}", "é".repeat(100));
+    let folded = fold(&line);
+    for physical in folded.split("\r\n").filter(|l| !l.is_empty()) {
+        assert!(physical.len() <= 75, "{} octets", physical.len());
+    }
+    assert!(folded.contains("\r\n "));
+    assert_eq!(folded.replace("\r\n ", ""), format!("{}\r\n", line));
+
+    // Exactly 75 octets fits; one more wraps onto a continuation line.
+    assert_eq!(fold(&"a".repeat(75)), format!("{}\r\n", "a".repeat(75)));
+    assert_eq!(
+        fold(&"a".repeat(76)),
+        format!("{}\r\n a\r\n", "a".repeat(75))
+    );
+}
+
+#[test]
+fn a_gig_without_a_time_is_an_all_day_event() {
+    for time in [None, Some("na de soundcheck")] {
+        let event = gig_event(&gig(time), None, 0, BASE_URL);
+        assert_eq!(
+            event.start,
+            EventTime::AllDay(NaiveDate::from_ymd_opt(2026, 11, 14).unwrap())
+        );
+        assert_eq!(
+            event.end,
+            EventTime::AllDay(NaiveDate::from_ymd_opt(2026, 11, 15).unwrap())
+        );
+        assert_eq!(event.location, Some("De Kroeg".to_string()));
+    }
+}
+
+#[test]
+fn open_ended_gigs_last_their_running_order() {
+    let event = gig_event(&gig(Some("20:00")), None, 90 * 60, BASE_URL);
+    assert_eq!(event.end, EventTime::At(at(14, 21, 30)));
+
+    let event = gig_event(&gig(Some("22:00-01:00")), None, 0, BASE_URL);
+    assert_eq!(event.end, EventTime::At(at(15, 1, 0)));
+}
+
+#[test]
+fn renders_a_gig_and_a_rehearsal() {
+    let venue = Venue {
+        id: 2,
+        name: "De Kroeg".to_string(),
+        address: "Dorpsstraat 1, Ergens".to_string(),
+        ..Default::default()
+    };
+    let rehearsal = RehearsalModel {
+        id: 9,
+        date: NaiveDate::from_ymd_opt(2026, 11, 10).unwrap(),
+        location: "Oefenruimte".to_string(),
+        attendees: vec![],
+        notes: "Nieuwe nummers".to_string(),
+        started_at: at(10, 19, 30),
+        closed_at: Some(at(10, 22, 0)),
+    };
+    let events = vec![
+        gig_event(&gig(Some("20:00-23:00")), Some(&venue), 0, BASE_URL),
+        rehearsal_event(&rehearsal, BASE_URL),
+    ];
+
+    let calendar = render_calendar(&events, at(1, 12, 0));
+    assert!(calendar.ends_with("END:VCALENDAR\r\n"));
+    let lines = unfolded(&calendar);
+    let gig_start = lines.iter().position(|l| l == "UID:gig-4@my-dad-rocks").unwrap();
+    assert_eq!(
+        lines[gig_start - 1..gig_start + 9],
+        [
+            "BEGIN:VEVENT",
+            "UID:gig-4@my-dad-rocks",
+            "DTSTAMP:20261101T120000Z",
+            "DTSTART:20261114T200000",
+            "DTEND:20261114T230000",
+            "SUMMARY:My Dad Rocks @ De Kroeg",
+            "LOCATION:De Kroeg\\, Dorpsstraat 1\\, Ergens",
+            "DESCRIPTION:Setlist: https://mydadrocks.example/gig/4",
+            "URL:https://mydadrocks.example/gig/4",
+            "END:VEVENT",
+        ]
+    );
+    let rehearsal_start = lines
+        .iter()
+        .position(|l| l == "UID:rehearsal-9@my-dad-rocks")
+        .unwrap();
+    assert_eq!(
+        lines[rehearsal_start + 2..rehearsal_start + 7],
+        [
+            "DTSTART:20261110T193000",
+            "DTEND:20261110T220000",
+            "SUMMARY:My Dad Rocks repetitie",
+            "LOCATION:Oefenruimte",
+            "DESCRIPTION:Nieuwe nummers\\nhttps://mydadrocks.example/rehearsal/9",
+        ]
+    );
+}
@@ -0,0 +1,73 @@
+use http::StatusCode;
+use leptos::ServerFnError;
+use my_dad_rocks::error::AppError;
+
+fn all() -> Vec<AppError> {
+    vec![
+        AppError::NotFound("gig 12".to_string()),
+        AppError::Locked("setlist Kerst is vergrendeld".to_string()),
+        AppError::validation("\"12,3x\" is geen geldig bedrag"),
+        AppError::Conflict("dit bestaat al".to_string()),
+        AppError::Storage("de database is niet bereikbaar".to_string()),
+        AppError::Internal("kapot: echt".to_string()),
+    ]
+}
+
+#[test]
+fn display_parses_back_to_the_same_error() {
+    for error in all() {
+        assert_eq!(error.to_string().parse::<AppError>(), Ok(error));
+    }
+    assert!("geen fout".parse::<AppError>().is_err());
+    assert!("Onbekend: iets".parse::<AppError>().is_err());
+}
+
+#[test]
+fn status_codes_per_kind() {
+    let codes: Vec<StatusCode> = all().iter().map(AppError::status_code).collect();
+    assert_eq!(
+        codes,
+        [
+            StatusCode::NOT_FOUND,
+            StatusCode::LOCKED,
+            StatusCode::UNPROCESSABLE_ENTITY,
+            StatusCode::CONFLICT,
+            StatusCode::SERVICE_UNAVAILABLE,
+            StatusCode::INTERNAL_SERVER_ERROR,
+        ]
+    );
+}
+
+#[test]
+fn server_fn_errors_keep_the_domain_error() {
+    let locked = AppError::Locked("setlist Kerst is vergrendeld".to_string());
+    let wrapped: ServerFnError<AppError> = locked.clone().into();
+    assert_eq!(AppError::from_server_fn(&wrapped), locked);
+    assert_eq!(locked.kind(), "locked");
+    assert_eq!(locked.message(), "setlist Kerst is vergrendeld");
+
+    let transport: ServerFnError<AppError> = ServerFnError::Request("timeout".to_string());
+    assert!(matches!(
+        AppError::from_server_fn(&transport),
+        AppError::Internal(_)
+    ));
+}
+
+#[test]
+fn server_fn_errors_get_their_status() {
+    use leptos::server_fn::error::ServerFnErrorSerde;
+
+    for error in all() {
+        let body = ServerFnError::WrappedServerError(error.clone()).ser().unwrap();
+        assert_eq!(
+            AppError::status_for_server_fn_body(&body),
+            Some(error.status_code())
+        );
+    }
+    let transport = ServerFnError::<AppError>::Request("verbinding weg".to_string());
+    assert_eq!(
+        AppError::status_for_server_fn_body(&transport.ser().unwrap()),
+        None
+    );
+    assert_eq!(AppError::status_for_server_fn_body("<html>kapot</html>"), None);
+}
@@ -0,0 +1,96 @@
+use chrono::NaiveDate;
+use my_dad_rocks::error::AppError;
+use my_dad_rocks::models::gig::{Gig, GigModel};
+use my_dad_rocks::models::gig_share::{GigShare, SharedGig};
+use my_dad_rocks::models::song::Song;
+
+fn date(day: u32) -> NaiveDate {
+    NaiveDate::from_ymd_opt(2026, 11, day).unwrap()
+}
+
+fn song(id: i32) -> Song {
+    Song {
+        id,
+        title: format!("Song {}", id),
+        artist: "Band".to_string(),
+        lyrics: format!("Tekst van {}", id),
+        key: "Am".parse().ok(),
+        ..Default::default()
+    }
+}
+
+fn gig(songs: Vec<i32>) -> Gig {
+    Gig::from_model(
+        GigModel {
+            id: 1,
+            venue: "De Boerderij".to_string(),
+            date: date(14),
+            time: Some("21:00".to_string()),
+            songs,
+            venue_id: None,
+        },
+        (1..=4).map(song).collect(),
+    )
+}
+
+#[test]
+fn splits_the_running_order_into_sets() {
+    let shared = SharedGig::from_gig(&gig(vec![-1, 1, 2, -2, -3, 3, 4, -4]), false);
+
+    assert_eq!(shared.venue, "De Boerderij");
+    assert_eq!(shared.time.as_deref(), Some("21:00"));
+    let titles: Vec<Vec<&str>> = shared
+        .sets
+        .iter()
+        .map(|set| set.iter().map(|s| s.title.as_str()).collect())
+        .collect();
+    assert_eq!(
+        titles,
+        vec![vec!["Song 1", "Song 2"], vec!["Song 3", "Song 4"]]
+    );
+}
+
+#[test]
+fn lyrics_only_when_shared_with_lyrics() {
+    let without = SharedGig::from_gig(&gig(vec![1]), false);
+    assert_eq!(without.sets[0][0].lyrics, None);
+    assert_eq!(
+        without.sets[0][0].key.as_ref().map(|k| k.to_string()),
+        Some("Am".to_string())
+    );
+
+    let with = SharedGig::from_gig(&gig(vec![1]), true);
+    assert_eq!(with.sets[0][0].lyrics.as_deref(), Some("Tekst van 1"));
+}
+
+#[test]
+fn links_expire_after_their_last_day() {
+    let share = GigShare {
+        id: 1,
+        gig_id: 1,
+        token: "abc".to_string(),
+        include_lyrics: false,
+        expires_on: date(21),
+    };
+    assert!(!share.is_expired(date(21)));
+    assert!(share.is_expired(date(22)));
+}
+
+#[test]
+fn expiry_defaults_to_a_week_after_the_gig() {
+    assert_eq!(GigShare::parse_expiry("", date(14), date(1)), Ok(date(21)));
+    // For a gig in the past the week starts today.
+    assert_eq!(GigShare::parse_expiry(" ", date(1), date(14)), Ok(date(21)));
+    assert_eq!(
+        GigShare::parse_expiry("2026-11-30", date(14), date(1)),
+        Ok(date(30))
+    );
+    assert!(matches!(
+        GigShare::parse_expiry("2026-11-01", date(14), date(2)),
+        Err(AppError::Validation(_))
+    ));
+    assert!(matches!(
+        GigShare::parse_expiry("morgen", date(14), date(2)),
+        Err(AppError::Validation(_))
+    ));
+}
@@ -0,0 +1,22 @@
+use my_dad_rocks::models::gig::GigSource;
+
+#[test]
+fn round_trips_through_the_select_value() {
+    for source in [
+        GigSource::Empty,
+        GigSource::Gig(12),
+        GigSource::Template(3),
+    ] {
+        assert_eq!(GigSource::from_value(&source.to_value()), source);
+    }
+    assert_eq!(GigSource::Gig(12).to_value(), "gig:12");
+    assert_eq!(GigSource::Template(3).to_value(), "template:3");
+    assert_eq!(GigSource::Empty.to_value(), "");
+}
+
+#[test]
+fn unknown_values_are_empty() {
+    for value in ["", "gig", "gig:", "gig:twaalf", "template:-", "setlist:1", ":1"] {
+        assert_eq!(GigSource::from_value(value), GigSource::Empty, "{}", value);
+    }
+}
@@ -0,0 +1,48 @@
+#![cfg(feature = "ssr")]
+
+use std::time::Duration;
+
+use axum::http::StatusCode;
+use my_dad_rocks::health::{route_label, Metrics, PoolStats};
+
+#[test]
+fn labels_server_functions_without_hash() {
+    assert_eq!(route_label(Some("/api/get_gig17363482210982")), "get_gig");
+    assert_eq!(route_label(Some("/api/v1/songs/:id")), "/api/v1/songs/:id");
+    assert_eq!(route_label(Some("/gig/:id")), "/gig/:id");
+    assert_eq!(route_label(None), "fallback");
+}
+
+#[test]
+fn renders_prometheus_text() {
+    let metrics = Metrics::default();
+    metrics.record("get_gig", Duration::from_millis(3), StatusCode::OK);
+    metrics.record("get_gig", Duration::from_millis(40), StatusCode::OK);
+    metrics.record(
+        "get_gig",
+        Duration::from_secs(10),
+        StatusCode::INTERNAL_SERVER_ERROR,
+    );
+
+    let text = metrics.render(Some(PoolStats {
+        size: 3,
+        idle: 1,
+        max: 4,
+    }));
+    let lines: Vec<&str> = text.lines().collect();
+
+    for line in [
+        "mdr_request_duration_seconds_bucket{route=\"get_gig\",le=\"0.005\"} 1",
+        "mdr_request_duration_seconds_bucket{route=\"get_gig\",le=\"0.025\"} 1",
+        "mdr_request_duration_seconds_bucket{route=\"get_gig\",le=\"0.05\"} 2",
+        "mdr_request_duration_seconds_bucket{route=\"get_gig\",le=\"5\"} 2",
+        "mdr_request_duration_seconds_bucket{route=\"get_gig\",le=\"+Inf\"} 3",
+        "mdr_request_duration_seconds_count{route=\"get_gig\"} 3",
+        "mdr_request_errors_total{route=\"get_gig\"} 1",
+        "mdr_db_pool_connections{state=\"idle\"} 1",
+        "mdr_db_pool_connections{state=\"in_use\"} 2",
+        "mdr_db_pool_max_connections 4",
+    ] {
+        assert!(lines.contains(&line), "missing {}", line);
+    }
+}
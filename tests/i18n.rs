@@ -0,0 +1,86 @@
+use chrono::NaiveDate;
+use http::header::{ACCEPT_LANGUAGE, COOKIE};
+use http::HeaderMap;
+use my_dad_rocks::i18n::Locale;
+
+fn message_ids(catalogue: &str) -> Vec<&str> {
+    let mut ids: Vec<&str> = catalogue
+        .lines()
+        .filter(|line| line.starts_with(|c: char| c.is_ascii_alphabetic()))
+        .filter_map(|line| line.split_once(" =").map(|(id, _)| id))
+        .collect();
+    ids.sort();
+    ids
+}
+
+fn headers(pairs: &[(http::header::HeaderName, &str)]) -> HeaderMap {
+    let mut headers = HeaderMap::new();
+    for (name, value) in pairs {
+        headers.append(name.clone(), value.parse().unwrap());
+    }
+    headers
+}
+
+#[test]
+fn every_message_is_translated() {
+    let nl = message_ids(include_str!("../locales/nl.ftl"));
+    let en = message_ids(include_str!("../locales/en.ftl"));
+    assert!(!nl.is_empty());
+    assert_eq!(nl, en);
+}
+
+#[test]
+fn translates_with_arguments_and_plurals() {
+    assert_eq!(Locale::Nl.t("setlist-fill"), "Vullen");
+    assert_eq!(Locale::En.t("setlist-fill"), "Fill");
+    assert_eq!(
+        Locale::En.t_with("gig-awkward-keys", &[("count", 1.into())]),
+        "One awkward key change. Smoother:"
+    );
+    assert_eq!(
+        Locale::Nl.t_with("gig-awkward-keys", &[("count", 3.into())]),
+        "3 lastige toonsoortwissels. Soepeler:"
+    );
+    assert_eq!(
+        Locale::En.t_with(
+            "gig-key-change",
+            &[("from", "C".into()), ("to", "F#".into())]
+        ),
+        "Key C → F#"
+    );
+    assert_eq!(
+        Locale::Nl.t_with("song-count", &[("count", 1.into())]),
+        "1 nummer"
+    );
+    assert_eq!(Locale::En.t("share-with-lyrics"), "with lyrics, ");
+    // Unknown ids show up as themselves rather than as an empty string.
+    assert_eq!(Locale::En.t("no-such-message"), "no-such-message");
+}
+
+#[test]
+fn formats_dates_per_locale() {
+    let date = NaiveDate::from_ymd_opt(2026, 11, 4).unwrap();
+    assert_eq!(Locale::Nl.format_date(date), "04-11-2026");
+    assert_eq!(Locale::En.format_date(date), "4 Nov 2026");
+    assert_eq!(
+        Locale::En.format_date_time(date.and_hms_opt(21, 30, 0).unwrap()),
+        "4 Nov 2026 21:30"
+    );
+}
+
+#[test]
+fn the_picked_language_wins_over_the_browser() {
+    assert_eq!(Locale::from_headers(&headers(&[])), Locale::Nl);
+    assert_eq!(
+        Locale::from_headers(&headers(&[(ACCEPT_LANGUAGE, "de-DE,en-GB;q=0.8,nl;q=0.5")])),
+        Locale::En
+    );
+    assert_eq!(
+        Locale::from_headers(&headers(&[
+            (ACCEPT_LANGUAGE, "en-GB"),
+            (COOKIE, "band=2; lang=nl")
+        ])),
+        Locale::Nl
+    );
+    assert_eq!(Locale::from_accept_language("fr, de"), None);
+}
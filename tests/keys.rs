@@ -0,0 +1,109 @@
+use my_dad_rocks::models::gig::GigSong;
+use my_dad_rocks::models::key::{smoother_order, Key, KeyTransition, Mode};
+use my_dad_rocks::models::song::Song;
+
+fn song(id: i32, key: &str) -> GigSong {
+    GigSong {
+        index: 0,
+        id,
+        song: Some(Song {
+            id,
+            title: format!("Song {}", id),
+            key: key.parse().ok(),
+            ..Default::default()
+        }),
+    }
+}
+
+fn pause(id: i32) -> GigSong {
+    GigSong {
+        index: 0,
+        id,
+        song: None,
+    }
+}
+
+fn key(key: &str) -> Key {
+    key.parse().unwrap()
+}
+
+#[test]
+fn parses_and_displays_keys() {
+    assert_eq!(
+        key("F#m"),
+        Key {
+            tonic: "F#".to_string(),
+            mode: Mode::Minor
+        }
+    );
+    assert_eq!(key("bb").to_string(), "Bb");
+    assert_eq!(key("A minor").to_string(), "Am");
+    assert_eq!(key("E mol").to_string(), "Em");
+    assert!("H".parse::<Key>().is_err());
+    assert!("Cx".parse::<Key>().is_err());
+    assert_eq!(Key::parse_optional("  "), Ok(None));
+}
+
+#[test]
+fn reads_the_key_from_chordpro() {
+    let lyrics = "{title: Whiskey in the Jar}\n{Key: Dm}\n[Dm]As I was going";
+    assert_eq!(Key::from_chordpro(lyrics), Some(key("Dm")));
+    assert_eq!(Key::from_chordpro("[C]Geen directive"), None);
+}
+
+#[test]
+fn relative_keys_are_close() {
+    assert_eq!(key("C").distance(&key("Am")), 0);
+    assert_eq!(key("C").distance(&key("G")), 1);
+    assert_eq!(key("C").distance(&key("F")), 1);
+    assert_eq!(key("C").distance(&key("F#")), 6);
+    assert_eq!(key("Db").distance(&key("C#")), 0);
+}
+
+#[test]
+fn flags_awkward_transitions_but_not_across_breaks() {
+    let songs = vec![
+        song(1, "C"),
+        song(2, "E"),
+        pause(-1),
+        song(3, "Bb"),
+        song(4, ""),
+    ];
+    assert_eq!(
+        KeyTransition::find(&songs),
+        vec![KeyTransition {
+            song_id: 2,
+            from: key("C"),
+            to: key("E"),
+        }]
+    );
+}
+
+#[test]
+fn smoother_order_keeps_the_ends_of_each_set() {
+    let songs = vec![
+        song(1, "C"),
+        song(2, "F#"),
+        song(3, "G"),
+        song(4, "D"),
+        song(5, "A"),
+        pause(-1),
+        song(6, "E"),
+        song(7, "B"),
+    ];
+    let order = smoother_order(&songs).expect("a smoother order");
+    assert_eq!(order.first(), Some(&1));
+    assert_eq!(order[4..], [5, -1, 6, 7]);
+
+    let reordered: Vec<GigSong> = order
+        .iter()
+        .map(|id| songs.iter().find(|s| s.id == *id).unwrap().clone())
+        .collect();
+    assert!(KeyTransition::find(&reordered).len() < KeyTransition::find(&songs).len());
+}
+
+#[test]
+fn no_suggestion_when_the_order_is_already_smooth() {
+    let songs = vec![song(1, "C"), song(2, "G"), song(3, "D"), song(4, "Am")];
+    assert_eq!(smoother_order(&songs), None);
+}
@@ -0,0 +1,87 @@
+use my_dad_rocks::i18n::Locale;
+use my_dad_rocks::models::member::{Attendance, Member, Rsvp};
+
+fn attendance(
+    name: &str,
+    required: bool,
+    rsvp: Option<Rsvp>,
+    unavailable: Option<&str>,
+) -> Attendance {
+    Attendance {
+        member: Member {
+            id: 1,
+            name: name.to_string(),
+            instrument: "Drums".to_string(),
+            required,
+        },
+        rsvp,
+        unavailable: unavailable.map(str::to_string),
+    }
+}
+
+#[test]
+fn warns_when_required_member_is_unavailable() {
+    let warnings = Attendance::warnings(
+        &[
+            attendance("Henk", true, None, Some("vakantie")),
+            attendance("Piet", true, None, None),
+        ],
+        Locale::Nl,
+    );
+
+    assert_eq!(
+        warnings,
+        vec!["Henk is niet beschikbaar op deze datum: vakantie".to_string()]
+    );
+}
+
+#[test]
+fn warns_when_required_member_says_no() {
+    let warnings = Attendance::warnings(
+        &[attendance("Henk", true, Some(Rsvp::No), None)],
+        Locale::Nl,
+    );
+
+    assert_eq!(warnings, vec!["Henk kan niet".to_string()]);
+    let warnings = Attendance::warnings(
+        &[attendance("Henk", true, Some(Rsvp::No), None)],
+        Locale::En,
+    );
+    assert_eq!(warnings, vec!["Henk can't make it".to_string()]);
+}
+
+#[test]
+fn no_warning_for_optional_members() {
+    let warnings = Attendance::warnings(
+        &[
+            attendance("Gast", false, Some(Rsvp::No), Some("")),
+            attendance("Invaller", false, None, Some("")),
+        ],
+        Locale::Nl,
+    );
+
+    assert!(warnings.is_empty());
+}
+
+#[test]
+fn yes_overrides_unavailable_date() {
+    let a = attendance("Henk", true, Some(Rsvp::Yes), Some(""));
+
+    assert!(Attendance::warnings(std::slice::from_ref(&a), Locale::Nl).is_empty());
+    assert!(a.is_in());
+    assert!(!a.is_out());
+}
+
+#[test]
+fn unavailable_without_answer_counts_as_out() {
+    assert!(attendance("Henk", true, None, Some("")).is_out());
+    assert!(!attendance("Henk", true, Some(Rsvp::Maybe), Some("")).is_out());
+}
+
+#[test]
+fn rsvp_round_trips_through_str() {
+    for rsvp in Rsvp::ALL {
+        assert_eq!(rsvp.as_str().parse::<Rsvp>(), Ok(rsvp));
+    }
+    assert!("nope".parse::<Rsvp>().is_err());
+}
@@ -0,0 +1,139 @@
+use chrono::NaiveDate;
+use my_dad_rocks::models::finance::{
+    Expense, ExpenseCategory, GigFinanceLine, GigFinances, PayoutShare, YearOverview,
+};
+use my_dad_rocks::models::money::Euros;
+
+fn share(member_id: i32, name: &str, shares: u32) -> PayoutShare {
+    PayoutShare {
+        member_id,
+        name: name.to_string(),
+        shares,
+    }
+}
+
+fn finances(fee: i64, expenses: &[i64]) -> GigFinances {
+    GigFinances {
+        gig_id: 1,
+        fee: Euros::from_cents(fee),
+        deposit: Euros::from_cents(fee / 2),
+        expenses: expenses
+            .iter()
+            .enumerate()
+            .map(|(i, amount)| Expense {
+                id: i as i32,
+                gig_id: 1,
+                category: ExpenseCategory::Fuel,
+                description: String::default(),
+                amount: Euros::from_cents(*amount),
+            })
+            .collect(),
+        shares: vec![share(1, "Henk", 1), share(2, "Piet", 2)],
+    }
+}
+
+#[test]
+fn parses_amounts() {
+    let cases = [
+        ("12", 1200),
+        ("12,5", 1250),
+        ("12.50", 1250),
+        ("1.234,56", 123456),
+        ("1,234.56", 123456),
+        ("1.234", 123400),
+        ("€ 12,00", 1200),
+        ("-7,25", -725),
+        (",75", 75),
+    ];
+    for (input, cents) in cases {
+        assert_eq!(input.parse(), Ok(Euros::from_cents(cents)), "{}", input);
+    }
+}
+
+#[test]
+fn rejects_invalid_amounts() {
+    for input in ["", "€", "12,345.678", "twaalf", "1,2,3.4.5x"] {
+        assert!(input.parse::<Euros>().is_err(), "{}", input);
+    }
+}
+
+#[test]
+fn displays_dutch_notation() {
+    assert_eq!(Euros::from_cents(123450).to_string(), "€ 1.234,50");
+    assert_eq!(Euros::from_cents(5).to_string(), "€ 0,05");
+    assert_eq!(Euros::from_cents(-1200).to_string(), "-€ 12,00");
+    assert_eq!(Euros::from_cents(-123450).to_decimal(), "-1234.50");
+}
+
+#[test]
+fn split_keeps_every_cent() {
+    let parts = Euros::from_cents(1000).split(&[1, 1, 1]);
+    assert_eq!(
+        parts,
+        vec![
+            Euros::from_cents(334),
+            Euros::from_cents(333),
+            Euros::from_cents(333)
+        ]
+    );
+
+    let parts = Euros::from_cents(-1001).split(&[2, 1, 0]);
+    assert_eq!(
+        parts.iter().copied().sum::<Euros>(),
+        Euros::from_cents(-1001)
+    );
+    assert_eq!(parts[2], Euros::ZERO);
+
+    assert_eq!(Euros::from_cents(500).split(&[0, 0]), vec![Euros::ZERO; 2]);
+}
+
+#[test]
+fn payouts_follow_shares() {
+    let gig = finances(40000, &[4000, 6000]);
+
+    assert_eq!(gig.net(), Euros::from_cents(30000));
+    assert_eq!(gig.outstanding(), Euros::from_cents(20000));
+    assert_eq!(
+        gig.payouts(),
+        vec![
+            (share(1, "Henk", 1), Euros::from_cents(10000)),
+            (share(2, "Piet", 2), Euros::from_cents(20000)),
+        ]
+    );
+}
+
+#[test]
+fn yearly_overview_as_csv() {
+    let overview = YearOverview {
+        year: 2026,
+        gigs: vec![
+            GigFinanceLine {
+                gig_id: 1,
+                date: NaiveDate::from_ymd_opt(2026, 3, 14).unwrap(),
+                venue: "Café \"De Kroeg\", Utrecht".to_string(),
+                finances: finances(30000, &[]),
+            },
+            GigFinanceLine {
+                gig_id: 2,
+                date: NaiveDate::from_ymd_opt(2026, 6, 1).unwrap(),
+                venue: "Zaal".to_string(),
+                finances: finances(15000, &[3000]),
+            },
+        ],
+    };
+
+    assert_eq!(overview.total_net(), Euros::from_cents(42000));
+    assert_eq!(
+        overview.payouts_per_member(),
+        vec![
+            ("Henk".to_string(), Euros::from_cents(14000)),
+            ("Piet".to_string(), Euros::from_cents(28000)),
+        ]
+    );
+    assert_eq!(
+        overview.to_csv(),
+        "datum,locatie,gage,aanbetaling,kosten,netto,Henk,Piet\r\n\
+         2026-03-14,\"Café \"\"De Kroeg\"\", Utrecht\",300.00,150.00,0.00,300.00,100.00,200.00\r\n\
+         2026-06-01,Zaal,150.00,75.00,30.00,120.00,40.00,80.00\r\n"
+    );
+}
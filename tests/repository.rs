@@ -0,0 +1,351 @@
+//! Model behaviour on top of the in-memory repositories, no Postgres needed.
+#![cfg(feature = "ssr")]
+
+use std::sync::Arc;
+
+use chrono::NaiveDate;
+use futures::executor::block_on;
+use my_dad_rocks::error::AppError;
+use my_dad_rocks::models::audit::{AuditAction, AuditEntry};
+use my_dad_rocks::models::gig::{move_in_order, next_break_id, Gig, GigModel, GigSource, MoveKind};
+use my_dad_rocks::models::section::Section;
+use my_dad_rocks::models::setlist::Setlist;
+use my_dad_rocks::models::song::{Song, SongFilter, SongSort};
+use my_dad_rocks::models::song_revision::SongRevision;
+use my_dad_rocks::repository::{MemoryDatabase, Repositories};
+
+fn song(id: i32, title: &str, bpm: i32, tags: &[&str]) -> Song {
+    Song {
+        id,
+        artist: format!("Artiest {}", id),
+        title: title.to_string(),
+        bpm: Some(bpm),
+        tags: tags.iter().map(|t| t.to_string()).collect(),
+        ..Default::default()
+    }
+}
+
+fn songs() -> Vec<Song> {
+    vec![
+        song(1, "Black Betty", 120, &["rock"]),
+        song(2, "Alive", 140, &["rock", "snel"]),
+        song(3, "Johnny B. Goode", 170, &["snel"]),
+        song(4, "Love Shack", 130, &[]),
+    ]
+}
+
+fn gig(id: i32, songs: &[i32]) -> GigModel {
+    GigModel {
+        id,
+        venue: "Paradiso".to_string(),
+        date: NaiveDate::from_ymd_opt(2024, 6, 1).unwrap(),
+        time: None,
+        songs: songs.to_vec(),
+        venue_id: None,
+    }
+}
+
+fn practice(songs: &[i32], is_locked: bool) -> Setlist {
+    Setlist {
+        id: 1,
+        title: "Oefenen".to_string(),
+        is_locked,
+        songs: songs.to_vec(),
+    }
+}
+
+fn repos(gig_songs: &[i32], setlist: Setlist) -> Repositories {
+    Repositories::in_memory(songs(), vec![gig(1, gig_songs)], vec![setlist])
+}
+
+#[test]
+fn breaks_take_the_index_of_the_song_before() {
+    let gig = Gig::from_model(gig(1, &[-1, 1, 2, -2, 3, 99]), songs());
+    let indexed: Vec<(i32, usize)> = gig.songs.iter().map(|s| (s.id, s.index)).collect();
+    // The deleted song 99 is left out, the leading break doesn't underflow.
+    assert_eq!(indexed, [(-1, 0), (1, 0), (2, 1), (-2, 1), (3, 2)]);
+    assert!(gig
+        .songs
+        .iter()
+        .filter(|s| s.id < 0)
+        .all(|s| s.song.is_none()));
+    let unselected: Vec<i32> = gig.unselected_songs.iter().map(|s| s.id).collect();
+    assert_eq!(unselected, [4]);
+}
+
+#[test]
+fn break_ids_are_unique_and_negative() {
+    assert_eq!(next_break_id(&[]), -1);
+    assert_eq!(next_break_id(&[5, 6]), -1);
+    assert_eq!(next_break_id(&[5, -1, 6, -4]), -5);
+}
+
+#[test]
+fn moving_stops_at_the_ends() {
+    let mut songs = vec![1, -1, 2];
+    assert!(!move_in_order(&mut songs, 1, MoveKind::Up));
+    assert!(!move_in_order(&mut songs, 2, MoveKind::Down));
+    assert!(!move_in_order(&mut songs, 7, MoveKind::Up));
+    assert_eq!(songs, [1, -1, 2]);
+
+    assert!(move_in_order(&mut songs, -1, MoveKind::Up));
+    assert_eq!(songs, [-1, 1, 2]);
+    assert!(move_in_order(&mut songs, 1, MoveKind::Down));
+    assert_eq!(songs, [-1, 2, 1]);
+}
+
+#[test]
+fn setlist_only_adds_new_songs_once() {
+    let setlist = practice(&[2, 3], false);
+    assert_eq!(setlist.new_songs(&[3, 1, 4, 1, 2]), [1, 4]);
+    assert!(setlist.new_songs(&[2]).is_empty());
+}
+
+#[test]
+fn gig_running_order_through_the_repository() {
+    let repos = repos(&[1], practice(&[], false));
+    block_on(async {
+        Gig::add_song(&repos, 1, -1).await.unwrap();
+        Gig::add_song(&repos, 1, 2).await.unwrap();
+        Gig::add_song(&repos, 1, -1).await.unwrap();
+        assert_eq!(repos.gigs.get(1).await.unwrap().songs, [1, -1, 2, -2]);
+
+        Gig::move_song(&repos, 1, 1, MoveKind::Up).await.unwrap();
+        Gig::move_song(&repos, 1, 2, MoveKind::Up).await.unwrap();
+        assert_eq!(repos.gigs.get(1).await.unwrap().songs, [1, 2, -1, -2]);
+
+        Gig::remove_song(&repos, 1, -1).await.unwrap();
+        let gig = Gig::get_by_id(&repos, 1).await.unwrap();
+        let ids: Vec<i32> = gig.songs.iter().map(|s| s.id).collect();
+        assert_eq!(ids, [1, 2, -2]);
+
+        assert_eq!(
+            Gig::set_date(&repos, 1, "1 juni").await,
+            Err(AppError::validation("ongeldige datum 1 juni"))
+        );
+        assert!(matches!(
+            Gig::get_by_id(&repos, 42).await,
+            Err(AppError::NotFound(_))
+        ));
+    });
+}
+
+#[test]
+fn new_gig_copies_existing_songs_from_the_source() {
+    let repos = repos(&[3, -1, 99, 1], practice(&[], false));
+    block_on(async {
+        let date = NaiveDate::from_ymd_opt(2024, 7, 1).unwrap();
+        let id = Gig::create(&repos, "Melkweg".to_string(), date, None, GigSource::Gig(1))
+            .await
+            .unwrap();
+        let copy = repos.gigs.get(id).await.unwrap();
+        assert_eq!(copy.songs, [3, -1, 1]);
+        assert_eq!(copy.venue, "Melkweg");
+    });
+}
+
+#[test]
+fn practice_setlist_additions() {
+    let repos = repos(&[], practice(&[2], false));
+    block_on(async {
+        Setlist::set_songs(&repos, vec![1, 2, 1]).await.unwrap();
+        assert_eq!(repos.setlists.get(1).await.unwrap().songs, [2, 1]);
+
+        let filter = SongFilter {
+            tag: Some("snel".to_string()),
+            sort: SongSort::Bpm,
+            ..Default::default()
+        };
+        let rehearsal = Song::get_rehearsal(&repos, &filter).await.unwrap();
+        let selected: Vec<i32> = rehearsal.selected_songs.iter().map(|s| s.id).collect();
+        let unselected: Vec<i32> = rehearsal.unselected_songs.iter().map(|s| s.id).collect();
+        // Ordered by title, and by bpm for the filtered songs not yet picked.
+        assert_eq!(selected, [2, 1]);
+        assert_eq!(unselected, [3]);
+
+        // Two fills at once both end up on the setlist, without the unknown
+        // song 5.
+        let (first, second) = futures::join!(
+            Setlist::set_songs(&repos, vec![3, 4]),
+            Setlist::set_songs(&repos, vec![4, 5])
+        );
+        first.unwrap();
+        second.unwrap();
+        assert_eq!(repos.setlists.get(1).await.unwrap().songs, [2, 1, 3, 4]);
+
+        Setlist::clean(&repos).await.unwrap();
+        assert!(repos.setlists.get(1).await.unwrap().songs.is_empty());
+    });
+}
+
+#[test]
+fn locked_setlist_refuses_songs() {
+    let repos = repos(&[], practice(&[2], true));
+    block_on(async {
+        assert_eq!(
+            Setlist::set_songs(&repos, vec![1]).await,
+            Err(AppError::Locked(
+                "setlist Oefenen is vergrendeld".to_string()
+            ))
+        );
+        assert_eq!(repos.setlists.get(1).await.unwrap().songs, [2]);
+    });
+}
+
+#[test]
+fn invalid_arrangement_keeps_the_sections() {
+    let sections = Section::parse_list("intro 4, refrein 8").unwrap();
+    let mut black_betty = song(1, "Black Betty", 120, &["rock"]);
+    black_betty.sections = sections.clone();
+    let repos = Repositories::in_memory(vec![black_betty], vec![], vec![]);
+    let edit = |arrangement: &str| {
+        SongRevision {
+            id: 0,
+            song_id: 1,
+            created_at: Default::default(),
+            author: String::default(),
+            artist: "Ram Jam".to_string(),
+            title: "Black Betty".to_string(),
+            bpm: Some(120),
+            lyrics: String::default(),
+            readiness: Default::default(),
+            duration: None,
+            tags: vec![],
+            arrangement: arrangement.to_string(),
+            key: None,
+        }
+        .to_edit_data()
+    };
+    block_on(async {
+        assert!(matches!(
+            repos.songs.update(edit("kazoo 4")).await,
+            Err(AppError::Validation(_))
+        ));
+        let song = repos.songs.get(1).await.unwrap();
+        assert_eq!(song.artist, "Artiest 1");
+        assert_eq!(song.sections, sections);
+    });
+}
+
+/// Band 1 with the usual songs, and band 2 with one song of its own and a
+/// practice setlist that isn't the first one.
+fn two_bands() -> (Repositories, Repositories) {
+    let db = Arc::new(MemoryDatabase::default());
+    db.add_band(
+        1,
+        songs(),
+        vec![gig(1, &[1, 2])],
+        vec![practice(&[1], false)],
+    );
+    let mut coverband_practice = practice(&[10], false);
+    coverband_practice.id = 2;
+    db.add_band(
+        2,
+        vec![song(10, "Dancing Queen", 100, &["disco"])],
+        vec![gig(2, &[10])],
+        vec![coverband_practice],
+    );
+    (Repositories::memory(&db, 1), Repositories::memory(&db, 2))
+}
+
+#[test]
+fn bands_only_see_their_own_songs_gigs_and_setlists() {
+    let (first, second) = two_bands();
+    block_on(async {
+        let titles: Vec<String> = second
+            .songs
+            .get_all()
+            .await
+            .unwrap()
+            .into_iter()
+            .map(|s| s.title)
+            .collect();
+        assert_eq!(titles, ["Dancing Queen"]);
+        assert!(second.songs.get(1).await.is_err());
+        assert_eq!(first.songs.get_all().await.unwrap().len(), 4);
+
+        assert!(second.gigs.get(1).await.is_err());
+        second.gigs.remove(1).await.unwrap();
+        assert!(first.gigs.get(1).await.is_ok());
+        let id = second
+            .gigs
+            .create(
+                "Melkweg".to_string(),
+                NaiveDate::from_ymd_opt(2024, 7, 1).unwrap(),
+                None,
+                &[10, -1, 1],
+            )
+            .await
+            .unwrap();
+        assert_eq!(second.gigs.get(id).await.unwrap().songs, [10, -1]);
+        assert!(first.gigs.get(id).await.is_err());
+
+        assert_eq!(second.setlists.get_practice().await.unwrap().id, 2);
+        assert!(second.setlists.get(1).await.is_err());
+        let setlist_ids: Vec<i32> = first
+            .setlists
+            .get_all()
+            .await
+            .unwrap()
+            .iter()
+            .map(|s| s.id)
+            .collect();
+        assert_eq!(setlist_ids, [1]);
+    });
+}
+
+#[test]
+fn rehearsal_uses_the_bands_own_practice_setlist() {
+    let (_, second) = two_bands();
+    block_on(async {
+        let rehearsal = Song::get_rehearsal(&second, &SongFilter::default())
+            .await
+            .unwrap();
+        let selected: Vec<i32> = rehearsal.selected_songs.iter().map(|s| s.id).collect();
+        assert_eq!(selected, [10]);
+        assert!(rehearsal.unselected_songs.is_empty());
+    });
+}
+
+#[test]
+fn bands_only_see_their_own_audit_log_and_revisions() {
+    let (first, second) = two_bands();
+    block_on(async {
+        Gig::remove(&second, 2).await.unwrap();
+        assert!(first.audit.get_recent(10).await.unwrap().is_empty());
+        assert_eq!(AuditEntry::undo_last(&first).await, Ok(None));
+        let actions: Vec<AuditAction> = second
+            .audit
+            .get_recent(10)
+            .await
+            .unwrap()
+            .iter()
+            .map(|e| e.action)
+            .collect();
+        assert_eq!(actions, [AuditAction::Delete]);
+
+        let edit = SongRevision {
+            id: 0,
+            song_id: 10,
+            created_at: Default::default(),
+            author: String::default(),
+            artist: "ABBA".to_string(),
+            title: "Dancing Queen".to_string(),
+            bpm: Some(101),
+            lyrics: String::default(),
+            readiness: Default::default(),
+            duration: None,
+            tags: vec![],
+            arrangement: String::default(),
+            key: None,
+        }
+        .to_edit_data();
+        Song::update(&second, edit.clone()).await.unwrap();
+        let revisions = second.revisions.get_for_song(10).await.unwrap();
+        assert_eq!(revisions.len(), 2);
+        assert!(first.revisions.get_for_song(10).await.unwrap().is_empty());
+        assert!(first.revisions.get(revisions[0].id).await.is_err());
+        // Nor can one band change the songs of another.
+        assert!(first.songs.update(edit).await.is_err());
+    });
+}
@@ -0,0 +1,40 @@
+#![cfg(feature = "ssr")]
+
+use my_dad_rocks::rest_api::ApiDoc;
+use utoipa::OpenApi;
+
+#[test]
+fn documents_every_route() {
+    let doc = ApiDoc::openapi();
+    let paths: Vec<&str> = doc.paths.paths.keys().map(String::as_str).collect();
+
+    assert_eq!(
+        paths,
+        vec![
+            "/gigs",
+            "/gigs/{id}",
+            "/setlists",
+            "/setlists/{id}",
+            "/songs",
+            "/songs/{id}",
+            "/songs/{id}/plays",
+        ]
+    );
+}
+
+#[test]
+fn schemas_follow_serde_names() {
+    let json = serde_json::to_value(ApiDoc::openapi()).unwrap();
+    let schemas = &json["components"]["schemas"];
+
+    assert_eq!(
+        schemas["Readiness"]["enum"],
+        serde_json::json!(["new", "learning", "ready"])
+    );
+    assert_eq!(
+        schemas["RoleKind"]["enum"],
+        serde_json::json!(["lead_vocal", "backing", "tuning", "capo", "instrument"])
+    );
+    assert!(schemas["Song"]["properties"]["roles"].is_object());
+    assert!(schemas["GigModel"]["properties"]["date"].is_object());
+}
@@ -0,0 +1,218 @@
+#![cfg(feature = "ssr")]
+
+use my_dad_rocks::i18n::Locale;
+use my_dad_rocks::models::setlist_generator::{generate, GeneratorSettings};
+use my_dad_rocks::models::song::{Readiness, Song};
+
+fn song(id: i32, artist: &str, bpm: i32, tags: &[&str]) -> Song {
+    Song {
+        id,
+        artist: artist.to_string(),
+        title: format!("Song {}", id),
+        bpm: Some(bpm),
+        duration: Some(240),
+        tags: tags.iter().map(|t| t.to_string()).collect(),
+        ..Default::default()
+    }
+}
+
+fn repertoire() -> Vec<Song> {
+    let artists = ["Pearl Jam", "Muse", "Doe Maar", "Foo Fighters", "Ram Jam"];
+    let mut songs: Vec<Song> = (1..=30)
+        .map(|id| song(id, artists[id as usize % artists.len()], 80 + id * 4, &[]))
+        .collect();
+    songs[4].tags = vec!["opener".to_string()];
+    songs[9].tags = vec!["opener".to_string()];
+    songs[14].tags = vec!["closer".to_string()];
+    songs[19].tags = vec!["closer".to_string()];
+    songs
+}
+
+fn settings() -> GeneratorSettings {
+    GeneratorSettings {
+        target_minutes: 90,
+        sets: 2,
+        pause_minutes: 10,
+        seed: 42,
+        ..Default::default()
+    }
+}
+
+fn find(songs: &[Song], id: i32) -> &Song {
+    songs.iter().find(|s| s.id == id).unwrap()
+}
+
+/// The running order split on breaks.
+fn sets(order: &[i32]) -> Vec<Vec<i32>> {
+    order.split(|id| *id < 0).map(|set| set.to_vec()).collect()
+}
+
+#[test]
+fn same_seed_gives_same_setlist() {
+    let songs = repertoire();
+    assert_eq!(
+        generate(&songs, &settings(), Locale::Nl),
+        generate(&songs, &settings(), Locale::Nl)
+    );
+}
+
+#[test]
+fn different_seed_gives_different_setlist() {
+    let songs = repertoire();
+    let other = GeneratorSettings {
+        seed: 7,
+        ..settings()
+    };
+    assert_ne!(
+        generate(&songs, &settings(), Locale::Nl).songs,
+        generate(&songs, &other, Locale::Nl).songs
+    );
+}
+
+#[test]
+fn inserts_a_break_between_sets() {
+    let generated = generate(
+        &repertoire(),
+        &GeneratorSettings {
+            sets: 3,
+            ..settings()
+        },
+        Locale::Nl,
+    );
+
+    let breaks: Vec<i32> = generated
+        .songs
+        .iter()
+        .copied()
+        .filter(|id| *id < 0)
+        .collect();
+    assert_eq!(breaks, vec![-1, -2]);
+    assert!(sets(&generated.songs).iter().all(|set| !set.is_empty()));
+}
+
+#[test]
+fn stays_within_the_playing_time() {
+    let generated = generate(&repertoire(), &settings(), Locale::Nl);
+
+    // 90 minutes minus a 10 minute break, with a minute of slack per set.
+    assert!(generated.playing_seconds <= 80 * 60 + 2 * 60);
+    assert!(generated.playing_seconds >= 80 * 60 - 2 * 240);
+}
+
+#[test]
+fn sets_open_and_close_with_tagged_songs() {
+    let songs = repertoire();
+    let generated = generate(&songs, &settings(), Locale::Nl);
+
+    for set in sets(&generated.songs) {
+        let opener = find(&songs, set[0]);
+        let closer = find(&songs, *set.last().unwrap());
+        assert!(opener.tags.contains(&"opener".to_string()));
+        assert!(closer.tags.contains(&"closer".to_string()));
+    }
+}
+
+#[test]
+fn falls_back_to_most_energetic_opener_without_tags() {
+    let songs: Vec<Song> = repertoire()
+        .into_iter()
+        .map(|s| Song { tags: vec![], ..s })
+        .collect();
+    let generated = generate(
+        &songs,
+        &GeneratorSettings {
+            sets: 1,
+            ..settings()
+        },
+        Locale::Nl,
+    );
+
+    let fastest = songs.iter().map(|s| s.bpm.unwrap()).max().unwrap();
+    assert_eq!(find(&songs, generated.songs[0]).bpm, Some(fastest));
+}
+
+#[test]
+fn never_plays_the_same_artist_twice_in_a_row() {
+    let songs = repertoire();
+    for seed in 0..20 {
+        let generated = generate(
+            &songs,
+            &GeneratorSettings { seed, ..settings() },
+            Locale::Nl,
+        );
+        for set in sets(&generated.songs) {
+            for pair in set.windows(2) {
+                assert_ne!(
+                    find(&songs, pair[0]).artist,
+                    find(&songs, pair[1]).artist,
+                    "seed {}",
+                    seed
+                );
+            }
+        }
+    }
+}
+
+#[test]
+fn never_repeats_a_song() {
+    let generated = generate(
+        &repertoire(),
+        &GeneratorSettings {
+            target_minutes: 600,
+            ..settings()
+        },
+        Locale::Nl,
+    );
+
+    let mut ids: Vec<i32> = generated
+        .songs
+        .iter()
+        .copied()
+        .filter(|id| *id > 0)
+        .collect();
+    let count = ids.len();
+    ids.sort();
+    ids.dedup();
+    assert_eq!(ids.len(), count);
+    assert_eq!(count, 30);
+}
+
+#[test]
+fn skips_songs_that_are_not_ready() {
+    let mut songs = repertoire();
+    songs[0].readiness = Readiness::New;
+    songs[1].readiness = Readiness::Learning;
+    let generated = generate(
+        &songs,
+        &GeneratorSettings {
+            target_minutes: 600,
+            ..settings()
+        },
+        Locale::Nl,
+    );
+
+    assert!(!generated.songs.contains(&songs[0].id));
+    assert!(!generated.songs.contains(&songs[1].id));
+}
+
+#[test]
+fn explains_every_choice() {
+    let generated = generate(&repertoire(), &settings(), Locale::Nl);
+
+    // One line per song, one per break and a summary.
+    assert_eq!(generated.explanations.len(), generated.songs.len() + 1);
+    assert!(generated.explanations[0].contains("tag 'opener'"));
+    assert!(generated
+        .explanations
+        .last()
+        .unwrap()
+        .starts_with("Totale speeltijd"));
+
+    let generated = generate(&repertoire(), &settings(), Locale::En);
+    assert!(generated.explanations[0].starts_with("Set 1 opens with"));
+    assert!(generated
+        .explanations
+        .last()
+        .unwrap()
+        .starts_with("Total playing time"));
+}
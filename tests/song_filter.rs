@@ -0,0 +1,72 @@
+use leptos_router::ParamsMap;
+use my_dad_rocks::models::song::{Readiness, SongFilter, SongSort};
+
+fn params(pairs: &[(&str, &str)]) -> ParamsMap {
+    let mut params = ParamsMap::new();
+    for (key, value) in pairs {
+        params.insert(key.to_string(), value.to_string());
+    }
+    params
+}
+
+#[test]
+fn an_empty_query_is_no_filter() {
+    let filter = SongFilter::from_query(&params(&[]));
+
+    assert_eq!(filter, SongFilter::default());
+    assert_eq!(filter.sort, SongSort::Title);
+    assert!(!filter.is_active());
+}
+
+#[test]
+fn reads_every_parameter() {
+    let filter = SongFilter::from_query(&params(&[
+        ("query", " betty "),
+        ("readiness", "learning"),
+        ("min_bpm", "90"),
+        ("max_bpm", "140"),
+        ("not_played_days", "30"),
+        ("tag", "stevig"),
+        ("sort", "last_played"),
+    ]));
+
+    assert_eq!(
+        filter,
+        SongFilter {
+            query: Some("betty".to_string()),
+            readiness: Some(Readiness::Learning),
+            min_bpm: Some(90),
+            max_bpm: Some(140),
+            not_played_days: Some(30),
+            tag: Some("stevig".to_string()),
+            sort: SongSort::LastPlayed,
+        }
+    );
+    assert!(filter.is_active());
+}
+
+#[test]
+fn ignores_empty_and_malformed_values() {
+    let filter = SongFilter::from_query(&params(&[
+        ("query", "   "),
+        ("readiness", "bijna"),
+        ("min_bpm", "snel"),
+        ("max_bpm", ""),
+        ("not_played_days", "-"),
+        ("sort", "random"),
+    ]));
+
+    assert_eq!(filter, SongFilter::default());
+}
+
+#[test]
+fn sorts_round_trip_through_the_query() {
+    for sort in SongSort::ALL {
+        let filter = SongFilter::from_query(&params(&[("sort", sort.as_str())]));
+        assert_eq!(filter.sort, sort);
+    }
+    for readiness in Readiness::ALL {
+        let filter = SongFilter::from_query(&params(&[("readiness", readiness.as_str())]));
+        assert_eq!(filter.readiness, Some(readiness));
+    }
+}
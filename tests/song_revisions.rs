@@ -0,0 +1,134 @@
+//! Song revisions on top of the in-memory repositories.
+#![cfg(feature = "ssr")]
+
+use futures::executor::block_on;
+use my_dad_rocks::i18n::Locale;
+use my_dad_rocks::models::audit::UNKNOWN_ACTOR;
+use my_dad_rocks::models::song::Song;
+use my_dad_rocks::models::song_revision::{diff_lines, DiffLine, SongRevision};
+use my_dad_rocks::repository::Repositories;
+
+fn repos() -> Repositories {
+    let song = Song {
+        id: 1,
+        artist: "Golden Earring".to_string(),
+        title: "Radar Love".to_string(),
+        bpm: Some(130),
+        lyrics: "I've been drivin' all night\nmy hand's wet on the wheel".to_string(),
+        ..Default::default()
+    };
+    Repositories::in_memory(vec![song], vec![], vec![])
+}
+
+#[test]
+fn diff_keeps_common_lines() {
+    use DiffLine::*;
+    let line = |s: &str| s.to_string();
+    assert_eq!(
+        diff_lines("a\nb\nc", "a\nc\nd"),
+        [
+            Same(line("a")),
+            Removed(line("b")),
+            Same(line("c")),
+            Added(line("d"))
+        ]
+    );
+    assert!(diff_lines("", "").is_empty());
+}
+
+#[test]
+fn edits_are_kept_and_can_be_restored() {
+    let repos = repos();
+    block_on(async {
+        let original = repos.songs.get(1).await.unwrap();
+        let mut edit = SongRevision {
+            id: 0,
+            song_id: 1,
+            created_at: Default::default(),
+            author: String::default(),
+            artist: original.artist.clone(),
+            title: original.title.clone(),
+            bpm: Some(130),
+            lyrics: "I've been drivin' all night".to_string(),
+            readiness: original.readiness,
+            duration: None,
+            tags: vec![],
+            arrangement: String::default(),
+            key: None,
+        }
+        .to_edit_data();
+        Song::update(&repos, edit.clone()).await.unwrap();
+
+        // The state before the first edit is kept as well.
+        let revisions = repos.revisions.get_for_song(1).await.unwrap();
+        assert_eq!(revisions.len(), 2);
+        assert_eq!(revisions[1].author, UNKNOWN_ACTOR);
+        assert_eq!(revisions[1].lyrics, original.lyrics);
+        assert_eq!(
+            revisions[0].lyrics_diff(&revisions[1]),
+            [
+                DiffLine::Same("I've been drivin' all night".to_string()),
+                DiffLine::Removed("my hand's wet on the wheel".to_string()),
+            ]
+        );
+
+        // Saving without changes adds nothing.
+        Song::update(&repos, edit.clone()).await.unwrap();
+        assert_eq!(repos.revisions.get_for_song(1).await.unwrap().len(), 2);
+
+        edit.bpm = 128;
+        Song::update(&repos, edit).await.unwrap();
+        let revisions = repos.revisions.get_for_song(1).await.unwrap();
+        assert_eq!(
+            revisions[0].changed_fields(&revisions[1], Locale::Nl),
+            [("BPM".to_string(), "130".to_string(), "128".to_string())]
+        );
+
+        SongRevision::restore(&repos, revisions[2].id)
+            .await
+            .unwrap();
+        let song = repos.songs.get(1).await.unwrap();
+        assert_eq!((song.lyrics, song.bpm), (original.lyrics, Some(130)));
+        assert_eq!(repos.revisions.get_for_song(1).await.unwrap().len(), 4);
+    });
+}
+
+#[test]
+fn restoring_keeps_an_unknown_bpm_unknown() {
+    let repos = Repositories::in_memory(
+        vec![Song {
+            id: 1,
+            title: "Ik Wil Niet Dat Je Liegt".to_string(),
+            bpm: None,
+            ..Default::default()
+        }],
+        vec![],
+        vec![],
+    );
+    block_on(async {
+        let edit = SongRevision {
+            id: 0,
+            song_id: 1,
+            created_at: Default::default(),
+            author: String::default(),
+            artist: String::default(),
+            title: "Ik Wil Niet Dat Je Liegt".to_string(),
+            bpm: Some(96),
+            lyrics: String::default(),
+            readiness: Default::default(),
+            duration: None,
+            tags: vec![],
+            arrangement: String::default(),
+            key: None,
+        }
+        .to_edit_data();
+        Song::update(&repos, edit).await.unwrap();
+        assert_eq!(repos.songs.get(1).await.unwrap().bpm, Some(96));
+
+        let revisions = repos.revisions.get_for_song(1).await.unwrap();
+        SongRevision::restore(&repos, revisions[1].id)
+            .await
+            .unwrap();
+        assert_eq!(repos.songs.get(1).await.unwrap().bpm, None);
+    });
+}
@@ -0,0 +1,106 @@
+use my_dad_rocks::i18n::Locale;
+use my_dad_rocks::models::gig::GigSong;
+use my_dad_rocks::models::song::Song;
+use my_dad_rocks::models::song_role::{Changeover, RoleKind, SongRole};
+
+fn role(song_id: i32, member_id: i32, kind: RoleKind, value: &str) -> SongRole {
+    SongRole {
+        id: 0,
+        song_id,
+        member_id,
+        member_name: ["", "Henk", "Piet"][member_id as usize].to_string(),
+        kind,
+        value: value.to_string(),
+    }
+}
+
+fn song(id: i32, roles: Vec<SongRole>) -> GigSong {
+    GigSong {
+        index: 0,
+        id,
+        song: Some(Song {
+            id,
+            title: format!("Song {}", id),
+            roles,
+            ..Default::default()
+        }),
+    }
+}
+
+fn pause(id: i32) -> GigSong {
+    GigSong {
+        index: 0,
+        id,
+        song: None,
+    }
+}
+
+#[test]
+fn no_changeover_for_vocals_only() {
+    let order = vec![
+        song(1, vec![role(1, 1, RoleKind::LeadVocal, "")]),
+        song(2, vec![role(2, 2, RoleKind::LeadVocal, "")]),
+    ];
+
+    assert!(Changeover::find(&order, Locale::Nl).is_empty());
+}
+
+#[test]
+fn tuning_change_between_songs() {
+    let order = vec![
+        song(1, vec![]),
+        song(2, vec![role(2, 2, RoleKind::Tuning, "Drop D")]),
+        song(3, vec![]),
+    ];
+
+    assert_eq!(
+        Changeover::find(&order, Locale::Nl),
+        vec![
+            Changeover {
+                song_id: 2,
+                changes: vec!["Piet: Stemming standaard → Drop D".to_string()],
+            },
+            Changeover {
+                song_id: 3,
+                changes: vec!["Piet: Stemming Drop D → standaard".to_string()],
+            },
+        ]
+    );
+}
+
+#[test]
+fn same_setup_needs_no_changeover() {
+    let order = vec![
+        song(1, vec![role(1, 1, RoleKind::Capo, "2")]),
+        song(2, vec![role(2, 1, RoleKind::Capo, "2")]),
+    ];
+
+    assert!(Changeover::find(&order, Locale::Nl).is_empty());
+}
+
+#[test]
+fn break_leaves_time_to_change() {
+    let order = vec![
+        song(1, vec![role(1, 1, RoleKind::Instrument, "keys")]),
+        pause(-1),
+        song(2, vec![]),
+    ];
+
+    assert!(Changeover::find(&order, Locale::Nl).is_empty());
+}
+
+#[test]
+fn describes_roles() {
+    assert_eq!(
+        role(1, 1, RoleKind::LeadVocal, "").describe(Locale::Nl),
+        "Henk: Leadzang"
+    );
+    assert_eq!(
+        role(1, 2, RoleKind::Capo, "3").describe(Locale::Nl),
+        "Piet: Capo 3"
+    );
+    assert_eq!(
+        role(1, 1, RoleKind::LeadVocal, "").describe(Locale::En),
+        "Henk: Lead vocals"
+    );
+}
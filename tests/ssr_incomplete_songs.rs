@@ -0,0 +1,183 @@
+//! Renders the song views server side with songs that are missing a
+//! MusicBrainz release id and/or an audio file, which used to panic on
+//! `release_mid.unwrap()` and take down the whole page.
+#![cfg(feature = "ssr")]
+
+use leptos::*;
+use my_dad_rocks::components::gig::{SelectedGigSong, UnSelectedGigSong};
+use my_dad_rocks::components::homepage_songs::SongView;
+use my_dad_rocks::components::player::{Player, PlayerData};
+use my_dad_rocks::components::shared::{fallback_colour, initials};
+use my_dad_rocks::components::song_item::SongItem;
+use my_dad_rocks::models::gig::GigSong;
+use my_dad_rocks::models::song::Song;
+
+fn complete_song() -> Song {
+    Song {
+        id: 1,
+        artist: "Ram Jam".to_string(),
+        title: "Black Betty".to_string(),
+        release_mid: Some("90323744-6881-47fe-9795-4318af253f14".to_string()),
+        gs_url: Some("https://example.com/blackbetty.mp3".to_string()),
+        bpm: Some(120),
+        ..Default::default()
+    }
+}
+
+fn incomplete_song() -> Song {
+    Song {
+        id: 2,
+        artist: "Nieuwe Band".to_string(),
+        title: "Nog geen hoes".to_string(),
+        ..Default::default()
+    }
+}
+
+/// The opening tag of the play button, so other buttons on the page don't
+/// affect the assertions.
+fn play_button(html: &str) -> &str {
+    let icon = html.find("fa fa-play").expect("a play button");
+    let start = html[..icon].rfind("<button").expect("the play button element");
+    let end = start + html[start..].find('>').expect("the end of the tag");
+    &html[start..=end]
+}
+
+fn provide_player_context(player_data: Option<PlayerData>) {
+    let (get_player_data, set_player_data) = create_signal(player_data);
+    provide_context(get_player_data);
+    provide_context(set_player_data);
+}
+
+#[test]
+fn song_item_renders_initials_without_release() {
+    let html = ssr::render_to_string(|| view! { <SongItem song=incomplete_song()/> });
+
+    assert!(html.contains("NG"));
+    assert!(html.contains(&fallback_colour("Nog geen hoes")));
+    assert!(!html.contains("/coverart/"));
+}
+
+#[test]
+fn song_item_renders_cover_art_with_release() {
+    let html = ssr::render_to_string(|| view! { <SongItem song=complete_song()/> });
+
+    assert!(html.contains("/coverart/90323744-6881-47fe-9795-4318af253f14.jpeg"));
+}
+
+#[test]
+fn home_song_view_disables_play_without_audio() {
+    let html = ssr::render_to_string(|| {
+        provide_player_context(None);
+        let (get_selected_song, set_selected_song) = create_signal(None::<i32>);
+        let songs = vec![complete_song(), incomplete_song()];
+        view! {
+          <SongView
+            song=incomplete_song()
+            all_songs=songs
+            pick_song=create_server_action()
+            in_rehearsal=false
+            open_rehearsal=Signal::derive(|| None)
+            tick_song=create_server_action()
+            get_selected_song
+            set_selected_song
+          />
+        }
+    });
+
+    assert!(play_button(&html).contains("disabled"));
+    assert!(play_button(&html).contains("Geen audio"));
+}
+
+#[test]
+fn home_song_view_enables_play_with_audio() {
+    let html = ssr::render_to_string(|| {
+        provide_player_context(None);
+        let (get_selected_song, set_selected_song) = create_signal(None::<i32>);
+        view! {
+          <SongView
+            song=complete_song()
+            all_songs=vec![complete_song()]
+            pick_song=create_server_action()
+            in_rehearsal=false
+            open_rehearsal=Signal::derive(|| None)
+            tick_song=create_server_action()
+            get_selected_song
+            set_selected_song
+          />
+        }
+    });
+
+    assert!(!play_button(&html).contains("disabled"));
+}
+
+#[test]
+fn gig_songs_render_without_release() {
+    let html = ssr::render_to_string(|| {
+        provide_player_context(None);
+        let (get_selected_song, set_selected_song) = create_signal(None::<i32>);
+        provide_context(get_selected_song);
+        provide_context(set_selected_song);
+        let gig_song = GigSong {
+            index: 0,
+            id: 2,
+            song: Some(incomplete_song()),
+        };
+        view! {
+          <SelectedGigSong
+            selected_song=gig_song
+            all_songs=vec![incomplete_song()]
+            gig_id=1
+            remove_song=create_server_action()
+            move_song=create_server_action()
+          />
+          <UnSelectedGigSong song=incomplete_song() gig_id=1 add_song=create_server_action()/>
+        }
+    });
+
+    assert!(html.contains("Nog geen hoes"));
+    assert!(!html.contains("/coverart/"));
+}
+
+#[test]
+fn player_renders_song_without_audio() {
+    let html = ssr::render_to_string(|| {
+        provide_player_context(Some(PlayerData {
+            song: incomplete_song(),
+            all_songs: vec![incomplete_song()],
+        }));
+        view! { <Player/> }
+    });
+
+    assert!(html.contains("Geen audio beschikbaar"));
+    assert!(!html.contains("<audio"));
+}
+
+#[test]
+fn next_song_skips_songs_without_audio() {
+    let mut third = complete_song();
+    third.id = 3;
+    let player_data = PlayerData {
+        song: complete_song(),
+        all_songs: vec![complete_song(), incomplete_song(), third.clone()],
+    };
+
+    assert_eq!(player_data.next_song(), Some(third));
+}
+
+#[test]
+fn initials_use_first_two_words() {
+    assert_eq!(initials("Black Betty"), "BB");
+    assert_eq!(initials("Rockin' in the Free World"), "RI");
+    assert_eq!(initials("(I Can't Get No) Satisfaction"), "IC");
+    assert_eq!(initials("Paranoid"), "P");
+    assert_eq!(initials(""), "?");
+}
+
+#[test]
+fn fallback_colour_is_stable_per_title() {
+    assert_eq!(
+        fallback_colour("Black Betty"),
+        fallback_colour("Black Betty")
+    );
+    assert_ne!(fallback_colour("Black Betty"), fallback_colour("Paranoid"));
+}
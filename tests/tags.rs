@@ -0,0 +1,24 @@
+use my_dad_rocks::models::tag::Tag;
+
+#[test]
+fn trims_and_lowercases() {
+    assert_eq!(
+        Tag::parse_list(" Stevig ,Ballad,  MEEZINGER"),
+        vec!["stevig", "ballad", "meezinger"]
+    );
+}
+
+#[test]
+fn drops_duplicates_keeping_the_first() {
+    assert_eq!(
+        Tag::parse_list("ballad, stevig, Ballad, STEVIG, ballad"),
+        vec!["ballad", "stevig"]
+    );
+}
+
+#[test]
+fn skips_empty_entries() {
+    assert_eq!(Tag::parse_list(""), Vec::<String>::new());
+    assert_eq!(Tag::parse_list(" , ,,"), Vec::<String>::new());
+    assert_eq!(Tag::parse_list(",ballad,, ,"), vec!["ballad"]);
+}
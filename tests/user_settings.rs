@@ -0,0 +1,55 @@
+#![cfg(feature = "ssr")]
+
+use http::header::COOKIE;
+use http::HeaderMap;
+use my_dad_rocks::config::{Config, ConfigOverrides};
+use my_dad_rocks::i18n::Locale;
+use sqlx::{Connection, Executor, PgConnection};
+
+#[test]
+fn the_stored_language_wins_over_the_cookie() {
+    let mut headers = HeaderMap::new();
+    headers.insert(COOKIE, "lang=en".parse().unwrap());
+    assert_eq!(Locale::from_request(None, &headers), Locale::En);
+    assert_eq!(Locale::from_request(Some(Locale::Nl), &headers), Locale::Nl);
+}
+
+/// Stores languages in a new database next to the one at `DATABASE_URL`.
+#[tokio::test]
+async fn language_is_kept_per_user() {
+    dotenv::dotenv().ok();
+    let server = std::env::var("DATABASE_URL").expect("DATABASE_URL is needed for this test");
+    let (host, _) = server.rsplit_once('/').unwrap();
+    let mut admin = PgConnection::connect(&server).await.unwrap();
+    admin
+        .execute("DROP DATABASE IF EXISTS mdr_user_settings WITH (FORCE)")
+        .await
+        .unwrap();
+    admin
+        .execute("CREATE DATABASE mdr_user_settings")
+        .await
+        .unwrap();
+
+    let config = Config::load(&ConfigOverrides {
+        database_url: Some(format!("{}/mdr_user_settings", host)),
+        ..Default::default()
+    })
+    .unwrap();
+    my_dad_rocks::database::init_db(&config.database)
+        .await
+        .unwrap();
+
+    assert_eq!(Locale::for_user("henk").await.unwrap(), None);
+    Locale::En.save_for_user("henk").await.unwrap();
+    Locale::Nl.save_for_user("piet").await.unwrap();
+    assert_eq!(Locale::for_user("henk").await.unwrap(), Some(Locale::En));
+    Locale::Nl.save_for_user("henk").await.unwrap();
+    assert_eq!(Locale::for_user("henk").await.unwrap(), Some(Locale::Nl));
+    assert_eq!(Locale::for_user("piet").await.unwrap(), Some(Locale::Nl));
+
+    my_dad_rocks::database::get_db().close().await;
+    admin
+        .execute("DROP DATABASE mdr_user_settings WITH (FORCE)")
+        .await
+        .unwrap();
+}
@@ -0,0 +1,64 @@
+use chrono::NaiveDate;
+use my_dad_rocks::models::gig::{group_by_venue, GigModel};
+use my_dad_rocks::models::venue::Venue;
+
+fn gig(id: i32, venue: &str, venue_id: Option<i32>) -> GigModel {
+    GigModel {
+        id,
+        venue: venue.to_string(),
+        date: NaiveDate::from_ymd_opt(2026, 1, id as u32).unwrap(),
+        time: None,
+        songs: vec![],
+        venue_id,
+    }
+}
+
+fn venue(id: i32, name: &str) -> Venue {
+    Venue {
+        id,
+        name: name.to_string(),
+        ..Default::default()
+    }
+}
+
+fn ids(groups: &[(String, Vec<GigModel>)]) -> Vec<(&str, Vec<i32>)> {
+    groups
+        .iter()
+        .map(|(name, gigs)| (name.as_str(), gigs.iter().map(|g| g.id).collect()))
+        .collect()
+}
+
+#[test]
+fn a_renamed_venue_keeps_its_history() {
+    let gigs = vec![
+        gig(1, "Café De Kroeg", Some(7)),
+        gig(2, "Paradiso", None),
+        gig(3, "De Kroeg", Some(7)),
+        gig(4, "Paradiso", None),
+    ];
+    let venues = vec![venue(7, "Eetcafé De Kroeg")];
+
+    assert_eq!(
+        ids(&group_by_venue(gigs, &venues)),
+        vec![("Eetcafé De Kroeg", vec![1, 3]), ("Paradiso", vec![2, 4])]
+    );
+}
+
+#[test]
+fn linked_and_unlinked_gigs_with_the_same_name_stay_apart() {
+    let gigs = vec![
+        gig(1, "Paradiso", Some(2)),
+        gig(2, "Paradiso", None),
+        gig(3, "Paradiso", Some(5)),
+    ];
+
+    // Without the venues loaded the linked gigs fall back to their own name.
+    assert_eq!(
+        ids(&group_by_venue(gigs, &[])),
+        vec![
+            ("Paradiso", vec![1]),
+            ("Paradiso", vec![2]),
+            ("Paradiso", vec![3])
+        ]
+    );
+}